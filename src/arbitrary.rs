@@ -0,0 +1,130 @@
+use crate::generator::GeneratorParams;
+use crate::location::{Location, LocationKind};
+use crate::route::Route;
+use crate::vrp::Vrp;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Side of the square random locations are placed on
+const GRID_SIZE: u16 = 100;
+
+/// Due date of random warehouses
+const HORIZON: u16 = 1000;
+
+impl Location {
+    /// A random customer on a 100 x 100 grid with a time window within `0..1000`
+    pub fn arbitrary(rng: &mut impl Rng) -> Location {
+        let ready_time = rng.gen_range(0..HORIZON);
+
+        Location {
+            id: rng.gen_range(1..=u16::MAX),
+            x: f64::from(rng.gen_range(0..=GRID_SIZE)),
+            y: f64::from(rng.gen_range(0..=GRID_SIZE)),
+            demand: f64::from(rng.gen_range(1..=40u16)),
+            ready_time: f64::from(ready_time),
+            due_date: f64::from(rng.gen_range(ready_time..=HORIZON)),
+            service_time: f64::from(rng.gen_range(0..=20u16)),
+            ..Default::default()
+        }
+    }
+
+    /// A random warehouse at the center of the grid, open during `0..1000`
+    pub fn arbitrary_warehouse() -> Location {
+        Location {
+            id: 0,
+            x: f64::from(GRID_SIZE / 2),
+            y: f64::from(GRID_SIZE / 2),
+            due_date: f64::from(HORIZON),
+            kind: LocationKind::Depot,
+            ..Default::default()
+        }
+    }
+}
+
+impl Route {
+    /// A random route from `warehouse` that is always [valid](Route::is_valid) for `capacity`.
+    /// Time windows are drawn around the arrival times so the route may wait but is never late,
+    /// customers are numbered from 1
+    pub fn arbitrary(rng: &mut impl Rng, warehouse: &Location, capacity: f64) -> Route {
+        let mut route = Route {
+            warehouse: warehouse.clone(),
+            customers: Vec::new(),
+            open: false,
+            max_duration: None,
+            breaks: Vec::new(),
+        };
+
+        let max_customers = rng.gen_range(0..=10);
+        let mut load = 0.0;
+        let mut time = warehouse.ready_time as f32;
+
+        for id in 1..=max_customers {
+            // Demands are whole units, stop once not even one fits
+            if capacity - load < 1.0 {
+                break;
+            }
+
+            let mut customer = Location {
+                id,
+                x: f64::from(rng.gen_range(0..=GRID_SIZE)),
+                y: f64::from(rng.gen_range(0..=GRID_SIZE)),
+                demand: f64::from(rng.gen_range(1..=(capacity - load).min(40.0) as u16)),
+                service_time: f64::from(rng.gen_range(0..=20u16)),
+                ..Default::default()
+            };
+
+            let previous = route.customers.last().unwrap_or(warehouse);
+            let arrival = previous.cost_to(&customer, time);
+
+            let latest_ready = arrival.ceil() as u16 + 30;
+            customer.ready_time =
+                f64::from(rng.gen_range(arrival.floor() as u16 / 2..=latest_ready));
+            let start = arrival.max(customer.ready_time as f32);
+            customer.due_date = f64::from(start.ceil() as u16 + rng.gen_range(0..=100));
+
+            // Stop once the vehicle could not make it back to the warehouse
+            let departure = start + customer.service_time as f32;
+            if customer.cost_to(warehouse, departure) > warehouse.due_date as f32 {
+                break;
+            }
+
+            load += customer.demand;
+            time = departure;
+            route.customers.push(customer);
+        }
+
+        route
+    }
+}
+
+impl Vrp {
+    /// A random instance of 1 to 20 customers, with one vehicle per customer so it always has a
+    /// feasible solution
+    pub fn arbitrary(rng: &mut impl Rng) -> Vrp {
+        let n_customers = rng.gen_range(1..=20);
+        let vehicle_capacity = f64::from(rng.gen_range(40..=200u16));
+
+        Vrp::generate(&GeneratorParams {
+            n_customers,
+            n_vehicles: n_customers,
+            vehicle_capacity,
+            grid_size: GRID_SIZE,
+            horizon: HORIZON,
+            demand: (1, (vehicle_capacity / 2.0) as u16),
+            seed: rng.gen(),
+            ..Default::default()
+        })
+    }
+}
+
+/// Check `property` against `cases` random number generators seeded from 0, like a minimal
+/// proptest runner. Panics with the seed of the first failing case, so it can be replayed with
+/// `StdRng::seed_from_u64`
+pub fn check(cases: u64, mut property: impl FnMut(&mut StdRng) -> bool) {
+    for seed in 0..cases {
+        let mut rng = StdRng::seed_from_u64(seed);
+        if !property(&mut rng) {
+            panic!("Property failed for seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use crate::heuristics::aco::AcoParams;
+use crate::heuristics::registry::Heuristic;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Timings of one micro-benchmark
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    pub name: String,
+    pub iterations: u32,
+    pub total: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl BenchResult {
+    pub fn mean(&self) -> Duration {
+        self.total / self.iterations.max(1)
+    }
+
+    pub fn as_string(&self) -> String {
+        format!(
+            "{:<20} {:>6} iter  mean: {:>12?}  min: {:>12?}  max: {:>12?}",
+            self.name,
+            self.iterations,
+            self.mean(),
+            self.min,
+            self.max
+        )
+    }
+}
+
+/// Time `iterations` runs of `f`, after one warm-up run
+pub fn bench<T>(name: &str, iterations: u32, mut f: impl FnMut() -> T) -> BenchResult {
+    black_box(f());
+
+    let mut result = BenchResult {
+        name: String::from(name),
+        iterations,
+        total: Duration::ZERO,
+        min: Duration::MAX,
+        max: Duration::ZERO,
+    };
+
+    for _ in 0..iterations {
+        let start = Instant::now();
+        black_box(f());
+        let elapsed = start.elapsed();
+
+        result.total += elapsed;
+        result.min = result.min.min(elapsed);
+        result.max = result.max.max(elapsed);
+    }
+
+    result
+}
+
+/// Build the full distance matrix of `vrp`
+pub fn bench_distance_matrix(vrp: &Vrp, iterations: u32) -> BenchResult {
+    bench("distance_matrix", iterations, || {
+        let table = vrp.location_table();
+        (0..table.len())
+            .flat_map(|a| (0..table.len()).map(move |b| (a, b)))
+            .map(|(a, b)| table.distance(a, b))
+            .collect::<Vec<f32>>()
+    })
+}
+
+/// Validate every route of `result`
+pub fn bench_route_validation(result: &VrpResult, iterations: u32) -> BenchResult {
+    bench("route_validation", iterations, || {
+        result
+            .routes
+            .iter()
+            .filter(|route| route.is_valid(result.vehicle_capacity))
+            .count()
+    })
+}
+
+/// Try to insert every customer of `result` into every other route
+pub fn bench_try_insert(result: &VrpResult, iterations: u32) -> BenchResult {
+    bench("try_insert", iterations, || {
+        let mut n_feasible = 0;
+        for (i, route) in result.routes.iter().enumerate() {
+            for (j, other) in result.routes.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                n_feasible += other
+                    .customers
+                    .iter()
+                    .filter_map(|customer| route.try_insert(customer, result.vehicle_capacity))
+                    .count();
+            }
+        }
+        n_feasible
+    })
+}
+
+/// Run one iteration of the aco heuristic with `params`
+pub fn bench_aco_iteration(vrp: &Vrp, params: &AcoParams, iterations: u32) -> BenchResult {
+    let params = AcoParams {
+        max_iter: 1,
+        ..params.clone()
+    };
+
+    bench("aco_iteration", iterations, || vrp.aco_heuristic(&params))
+}
+
+/// Solve `vrp` with `heuristic`, named after it
+pub fn bench_heuristic(vrp: &Vrp, heuristic: &dyn Heuristic, iterations: u32) -> BenchResult {
+    bench(heuristic.name(), iterations, || heuristic.solve(vrp))
+}
+
+/// Run all standard micro-benchmarks on `vrp`, using nearest neighbour routes where routes are
+/// needed
+pub fn run_all(vrp: &Vrp, iterations: u32) -> Vec<BenchResult> {
+    let result = vrp.nearest_neighbour_heuristic();
+
+    let aco_params = AcoParams {
+        pheromone_amt: 1.0 / result.total_cost(),
+        ..AcoParams::default()
+    };
+
+    vec![
+        bench_distance_matrix(vrp, iterations),
+        bench_route_validation(&result, iterations),
+        bench_try_insert(&result, iterations),
+        bench_aco_iteration(vrp, &aco_params, iterations),
+    ]
+}
@@ -0,0 +1,372 @@
+use crate::report::ReportOptions;
+use crate::statistics::{InstanceStatistics, SolomonClass, Summary};
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::collections::BTreeMap;
+
+/// Style sheet of the HTML pages of this crate
+pub(crate) const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }\n\
+                                table { border-collapse: collapse; }\n\
+                                th, td { border: 1px solid #ccc; padding: 2px 8px; text-align: left; }\n";
+
+/// One solved instance of a [ReportBook]
+#[derive(Debug, Clone)]
+pub struct BookEntry {
+    /// Name of the instance, like `C101`
+    pub name: String,
+    /// The class given by the name of the instance, else the best guess of its
+    /// [statistics](Vrp::statistics)
+    pub class: SolomonClass,
+    pub statistics: InstanceStatistics,
+    pub result: VrpResult,
+}
+
+/// A single report covering every instance of a batch run: a table of contents, aggregate
+/// tables per solomon class, then the detailed report of every instance.
+///
+/// Print it as one Markdown file with [as_md_string](ReportBook::as_md_string), or as one self
+/// contained HTML page with [as_html_string](ReportBook::as_html_string)
+#[derive(Debug, Clone)]
+pub struct ReportBook {
+    pub title: String,
+    /// Options of the detailed report of every instance
+    pub options: ReportOptions,
+    pub entries: Vec<BookEntry>,
+}
+
+impl ReportBook {
+    /// An empty book
+    pub fn new(title: &str, options: ReportOptions) -> ReportBook {
+        ReportBook {
+            title: String::from(title),
+            options,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add the solution `result` of the instance `vrp` named `name`, instances are reported in
+    /// the order they are added
+    pub fn add(&mut self, name: &str, vrp: &Vrp, result: VrpResult) {
+        let statistics = vrp.statistics();
+        self.entries.push(BookEntry {
+            name: String::from(name),
+            class: SolomonClass::from_instance_name(name).unwrap_or(statistics.class),
+            statistics,
+            result,
+        });
+    }
+
+    /// Anchor of the detailed section of the entry at `index`, unique even if two instances
+    /// have the same name
+    fn anchor(index: usize) -> String {
+        format!("instance-{}", index + 1)
+    }
+
+    /// The entries of every class, in class order
+    fn by_class(&self) -> BTreeMap<SolomonClass, Vec<&BookEntry>> {
+        let mut classes: BTreeMap<SolomonClass, Vec<&BookEntry>> = BTreeMap::new();
+        for entry in &self.entries {
+            classes.entry(entry.class).or_default().push(entry);
+        }
+        classes
+    }
+
+    /// Print this book to a Markdown string
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format! {"# {}\n\n", self.title});
+
+        output.push_str("<a id=\"contents\"></a>\n\n## Contents\n\n");
+        output.push_str("- [Summary by class](#summary-by-class)\n");
+        output.push_str("- [Instances](#instances)\n");
+        for (i, entry) in self.entries.iter().enumerate() {
+            output.push_str(&format! {"- [{}](#{})\n", entry.name, Self::anchor(i)});
+        }
+
+        output.push_str("\n<a id=\"summary-by-class\"></a>\n\n## Summary by class\n\n");
+        output.push_str(
+            "| Class | Instances | Complete | Mean cost | Cost (min - max) | Mean routes | Total routes | Mean time (s) |\n",
+        );
+        output.push_str("|---|---|---|---|---|---|---|---|\n");
+        for (class, entries) in self.by_class() {
+            let cost = Summary::of(entries.iter().map(|e| e.result.total_cost()));
+            let routes = Summary::of(entries.iter().map(|e| e.result.routes.len() as f32));
+            let time = Summary::of(entries.iter().map(|e| wall_time(&e.result)));
+            output.push_str(&format! {
+                "| {} | {} | {} | {:.2} | {:.2} - {:.2} | {:.2} | {} | {:.3} |\n",
+                class,
+                entries.len(),
+                entries.iter().filter(|e| e.result.is_complete()).count(),
+                cost.mean,
+                cost.min,
+                cost.max,
+                routes.mean,
+                entries.iter().map(|e| e.result.routes.len()).sum::<usize>(),
+                time.mean
+            });
+        }
+
+        output.push_str("\n<a id=\"instances\"></a>\n\n## Instances\n\n");
+        output.push_str("| Instance | Class | Customers | Cost | Routes | Complete | Time (s) |\n");
+        output.push_str("|---|---|---|---|---|---|---|\n");
+        for (i, entry) in self.entries.iter().enumerate() {
+            output.push_str(&format! {
+                "| [{}](#{}) | {} | {} | {:.2} | {} | {} | {:.3} |\n",
+                entry.name,
+                Self::anchor(i),
+                entry.class,
+                entry.statistics.n_customers,
+                entry.result.total_cost(),
+                entry.result.routes.len(),
+                entry.result.is_complete(),
+                wall_time(&entry.result)
+            });
+        }
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            output.push_str(
+                &format! {"\n<a id=\"{}\"></a>\n\n## {}\n\n", Self::anchor(i), entry.name},
+            );
+            output.push_str(&demote_headings(&entry.statistics.as_md_string(), 2));
+            output.push('\n');
+            output.push_str(&demote_headings(
+                &entry.result.as_md_string_with(&self.options),
+                2,
+            ));
+            output.push_str("\n[Back to contents](#contents)\n");
+        }
+        output
+    }
+
+    /// Print this book to a self contained HTML page, charts included
+    pub fn as_html_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        output.push_str(&format! {"<title>{}</title>\n", escape_html(&self.title)});
+        output.push_str(&format! {"<style>\n{}</style>\n", STYLE});
+        output.push_str("</head>\n<body>\n");
+        output.push_str(&markdown_to_html(&self.as_md_string()));
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+}
+
+/// Solving time of a solution in seconds, 0 without [stats](VrpResult::stats)
+fn wall_time(result: &VrpResult) -> f32 {
+    result
+        .stats
+        .as_ref()
+        .map_or(0.0, |stats| stats.wall_time.as_secs_f32())
+}
+
+/// Add `levels` to every Markdown heading of `markdown`, outside of code blocks, so a report
+/// nests in a section of a larger one. Headings never go deeper than level 6
+fn demote_headings(markdown: &str, levels: usize) -> String {
+    let mut output = String::with_capacity(markdown.len());
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code = !in_code;
+        }
+
+        let level = line.chars().take_while(|&c| c == '#').count();
+        if !in_code && level > 0 && line[level..].starts_with(' ') {
+            output.push_str(&"#".repeat((level + levels).min(6)));
+            output.push_str(&line[level..]);
+        } else {
+            output.push_str(line);
+        }
+        output.push('\n');
+    }
+    output
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Convert the inline Markdown of the reports: `**bold**`, `` `code` `` and `[links](url)`
+fn inline_to_html(text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**") {
+            if let Some(end) = after.find("**") {
+                output.push_str(&format! {"<strong>{}</strong>", inline_to_html(&after[..end])});
+                rest = &after[end + 2..];
+                continue;
+            }
+        } else if let Some(after) = rest.strip_prefix('`') {
+            if let Some(end) = after.find('`') {
+                output.push_str(&format! {"<code>{}</code>", escape_html(&after[..end])});
+                rest = &after[end + 1..];
+                continue;
+            }
+        } else if let Some(after) = rest.strip_prefix('[') {
+            if let Some((label, url, tail)) = after.split_once("](").and_then(|(label, tail)| {
+                let (url, tail) = tail.split_once(')')?;
+                Some((label, url, tail))
+            }) {
+                output.push_str(&format! {
+                    "<a href=\"{}\">{}</a>", escape_html(url), inline_to_html(label)
+                });
+                rest = tail;
+                continue;
+            }
+        }
+
+        output.push_str(&escape_html(&c.to_string()));
+        rest = &rest[c.len_utf8()..];
+    }
+    output
+}
+
+/// Split a Markdown table row into cells, `\|` is a pipe inside a cell
+fn table_cells(line: &str) -> Vec<String> {
+    let line = line.trim().trim_start_matches('|');
+    let line = line.strip_suffix('|').unwrap_or(line);
+
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                chars.next();
+                cells.last_mut().unwrap().push('|');
+            }
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.iter().map(|cell| String::from(cell.trim())).collect()
+}
+
+/// Convert the Markdown written by the reports of this crate to HTML: headings, lists, tables,
+/// block quotes, code blocks and paragraphs. HTML lines, like anchors and SVG charts, are kept
+/// as they are.
+///
+/// This is not a general Markdown converter, it only covers what the reports print
+pub(crate) fn markdown_to_html(markdown: &str) -> String {
+    #[derive(PartialEq)]
+    enum Block {
+        None,
+        List,
+        Table,
+        Paragraph,
+        Code,
+        Html,
+    }
+
+    let mut output = String::new();
+    let mut block = Block::None;
+    let close = |block: &Block, output: &mut String| {
+        output.push_str(match block {
+            Block::List => "</ul>\n",
+            Block::Table => "</table>\n",
+            Block::Paragraph => "</p>\n",
+            Block::Code => "</code></pre>\n",
+            Block::None | Block::Html => "",
+        });
+    };
+
+    for line in markdown.lines() {
+        // -- Blocks copied as they are until their end --
+        if block == Block::Code {
+            if line.starts_with("```") {
+                close(&block, &mut output);
+                block = Block::None;
+            } else {
+                output.push_str(&escape_html(line));
+                output.push('\n');
+            }
+            continue;
+        }
+        if block == Block::Html {
+            output.push_str(line);
+            output.push('\n');
+            if line.contains("</svg>") {
+                block = Block::None;
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+
+        // -- Lines continuing the current block --
+        if block == Block::List && trimmed.starts_with("- ") {
+            output.push_str(&format! {"<li>{}</li>\n", inline_to_html(&trimmed[2..])});
+            continue;
+        }
+        if block == Block::Table && trimmed.starts_with('|') {
+            let cells = table_cells(trimmed);
+            // The separator row under the header
+            if cells
+                .iter()
+                .all(|cell| !cell.is_empty() && cell.chars().all(|c| "-:".contains(c)))
+            {
+                continue;
+            }
+            output.push_str("<tr>");
+            for cell in cells {
+                output.push_str(&format! {"<td>{}</td>", inline_to_html(&cell)});
+            }
+            output.push_str("</tr>\n");
+            continue;
+        }
+        let starts_block = trimmed.is_empty()
+            || trimmed.starts_with("- ")
+            || trimmed.starts_with('|')
+            || trimmed.starts_with('>')
+            || trimmed.starts_with('<')
+            || trimmed.starts_with("```")
+            || (level > 0 && trimmed[level..].starts_with(' '));
+        if block == Block::Paragraph && !starts_block {
+            output.push_str(&format! {"\n{}", inline_to_html(trimmed)});
+            continue;
+        }
+
+        // -- Lines starting a new block --
+        close(&block, &mut output);
+        block = Block::None;
+
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with("```") {
+            output.push_str("<pre><code>");
+            block = Block::Code;
+        } else if trimmed.starts_with('<') {
+            output.push_str(line);
+            output.push('\n');
+            if trimmed.starts_with("<svg") && !trimmed.contains("</svg>") {
+                block = Block::Html;
+            }
+        } else if level > 0 && trimmed[level..].starts_with(' ') {
+            let text = inline_to_html(trimmed[level..].trim());
+            let level = level.min(6);
+            output.push_str(&format! {"<h{}>{}</h{}>\n", level, text, level});
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            output
+                .push_str(&format! {"<blockquote>{}</blockquote>\n", inline_to_html(quote.trim())});
+        } else if let Some(item) = trimmed.strip_prefix("- ") {
+            output.push_str(&format! {"<ul>\n<li>{}</li>\n", inline_to_html(item)});
+            block = Block::List;
+        } else if trimmed.starts_with('|') {
+            output.push_str("<table>\n<tr>");
+            for cell in table_cells(trimmed) {
+                output.push_str(&format! {"<th>{}</th>", inline_to_html(&cell)});
+            }
+            output.push_str("</tr>\n");
+            block = Block::Table;
+        } else {
+            output.push_str(&format! {"<p>{}", inline_to_html(trimmed)});
+            block = Block::Paragraph;
+        }
+    }
+    close(&block, &mut output);
+    output
+}
@@ -0,0 +1,136 @@
+use crate::vrp::Vrp;
+use crate::vrp_result::{LoadError, VrpResult};
+
+/// Number of decimals of the cost line
+const COST_PRECISION: usize = 3;
+
+impl VrpResult {
+    /// Print this solution in the canonical text format, two lines listing the customer ids of
+    /// each route and the total cost with 3 decimals:
+    ///
+    /// ```text
+    /// Route 1: 5 3 7 | Route 2: 8 9
+    /// Cost: 1234.568
+    /// ```
+    ///
+    /// An empty route prints as `Route 3:` and a solution without routes has an empty first line.
+    /// Partial solutions have an `Unassigned: 4 6` line before the cost line. Routes of a
+    /// [multi-depot](Vrp::is_multi_depot) `vrp` name their depot, like `Route 1 @D2: 5 3 7`.
+    /// This format does not change between versions, which makes it suitable for snapshot
+    /// tests and for diffing solver outputs
+    pub fn to_canonical_string(&self, vrp: &Vrp) -> String {
+        let routes: Vec<String> = self
+            .routes
+            .iter()
+            .enumerate()
+            .map(|(i, route)| {
+                let mut output = if vrp.is_multi_depot() {
+                    format!("Route {} @D{}:", i + 1, route.warehouse.id)
+                } else {
+                    format!("Route {}:", i + 1)
+                };
+                for customer in &route.customers {
+                    output.push_str(&format! {" {}", customer.id});
+                }
+                output
+            })
+            .collect();
+
+        let mut output = routes.join(" | ");
+        output.push('\n');
+
+        if !self.is_complete() {
+            output.push_str("Unassigned:");
+            for id in &self.unassigned {
+                output.push_str(&format! {" {}", id});
+            }
+            output.push('\n');
+        }
+
+        output.push_str(&format! {"Cost: {:.*}\n", COST_PRECISION, self.total_cost()});
+        output
+    }
+
+    /// Read a solution printed by [to_canonical_string](VrpResult::to_canonical_string),
+    /// customers are looked up by id in `vrp` and checked like [from_json](VrpResult::from_json).
+    /// The cost line must be present but is recomputed from the routes. Routes start from the
+    /// warehouse unless they name another depot of `vrp`
+    pub fn from_canonical_str(text: &str, vrp: &Vrp) -> Result<VrpResult, LoadError> {
+        let lines: Vec<&str> = text.lines().collect();
+        let (routes_line, unassigned_line, cost_line) = match lines.as_slice() {
+            [routes, cost] => (*routes, None, *cost),
+            [routes, unassigned, cost] => (*routes, Some(*unassigned), *cost),
+            _ => {
+                return Err(LoadError::Invalid(String::from(
+                    "Expected a routes line, an optional unassigned line and a cost line",
+                )))
+            }
+        };
+
+        let cost = cost_line
+            .strip_prefix("Cost: ")
+            .ok_or_else(|| LoadError::Invalid(format!("Invalid cost line: {}", cost_line)))?;
+        cost.parse::<f32>()
+            .map_err(|_| LoadError::Invalid(format!("Invalid cost: {}", cost)))?;
+
+        let mut ids = Vec::new();
+        let mut depots = Vec::new();
+        if !routes_line.is_empty() {
+            for (i, route) in routes_line.split(" | ").enumerate() {
+                let expected =
+                    || LoadError::Invalid(format!("Expected route {}: {}", i + 1, route));
+                let (name, customers) = route.split_once(':').ok_or_else(expected)?;
+                let depot = match name.strip_prefix(&format!("Route {}", i + 1)) {
+                    Some("") => None,
+                    Some(depot) => Some(
+                        depot
+                            .strip_prefix(" @D")
+                            .and_then(|id| id.parse::<u16>().ok())
+                            .ok_or_else(|| {
+                                LoadError::Invalid(format!("Invalid route depot: {}", depot))
+                            })?,
+                    ),
+                    None => return Err(expected()),
+                };
+
+                ids.push(parse_ids(customers)?);
+                depots.push(depot);
+            }
+        }
+
+        let unassigned = match unassigned_line {
+            Some(line) => parse_ids(line.strip_prefix("Unassigned:").ok_or_else(|| {
+                LoadError::Invalid(format!("Invalid unassigned line: {}", line))
+            })?)?,
+            None => Vec::new(),
+        };
+
+        let mut routes = VrpResult::routes_from_ids(&ids, vrp)?;
+        VrpResult::check_unassigned(&unassigned, &ids, vrp)?;
+
+        for (route, depot) in routes.iter_mut().zip(depots) {
+            if let Some(id) = depot {
+                route.warehouse = vrp
+                    .depots()
+                    .find(|d| d.id == id)
+                    .cloned()
+                    .ok_or_else(|| LoadError::Invalid(format!("Unknown depot {}", id)))?;
+            }
+        }
+
+        Ok(VrpResult {
+            unassigned,
+            ..VrpResult::from_vrp(vrp, routes, None)
+        })
+    }
+}
+
+/// Parse the space separated customer ids of a line
+fn parse_ids(line: &str) -> Result<Vec<u16>, LoadError> {
+    line.split_whitespace()
+        .map(|id| {
+            id.parse::<u16>()
+                .map_err(|_| LoadError::Invalid(format!("Invalid customer id: {}", id)))
+        })
+        .collect()
+}
@@ -0,0 +1,151 @@
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::collections::{HashMap, HashSet};
+
+/// A solution storing location indices (see [Vrp::locations]) instead of [Location] clones.
+///
+/// A [VrpResult] holds a copy of every visited customer and of the warehouse for each route,
+/// this stores 2 bytes per visit. Populations of candidate solutions, like the ones of genetic
+/// or ant colony heuristics, can keep their members this way and only build a [VrpResult] for
+/// the solutions they return
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CompactSolution {
+    /// The customer indices of each route, without the warehouse
+    pub routes: Vec<Vec<u16>>,
+    /// The depot of each route, as its position in [depots](Vrp::depots). Empty when every
+    /// route starts at the warehouse
+    pub depots: Vec<u16>,
+}
+
+impl CompactSolution {
+    /// Convert the routes of `result` to indices of `vrp`, returns `None` if a route visits a
+    /// location that is not a customer of `vrp`
+    pub fn from_result(result: &VrpResult, vrp: &Vrp) -> Option<CompactSolution> {
+        let indices: HashMap<&Location, u16> = vrp
+            .locations()
+            .enumerate()
+            .map(|(i, location)| (location, i as u16))
+            .collect();
+
+        let routes = result
+            .routes
+            .iter()
+            .map(|route| {
+                route
+                    .customers
+                    .iter()
+                    .map(|c| indices.get(c).copied().filter(|&i| i != 0))
+                    .collect()
+            })
+            .collect::<Option<_>>()?;
+
+        let depots = if vrp.is_multi_depot() {
+            result
+                .routes
+                .iter()
+                .map(|route| {
+                    let depot = vrp.depots().position(|d| *d == route.warehouse)?;
+                    Some(depot as u16)
+                })
+                .collect::<Option<_>>()?
+        } else {
+            Vec::new()
+        };
+
+        Some(CompactSolution { routes, depots })
+    }
+
+    /// The depot of the route at `index`, `None` if it is not a depot of `vrp`
+    fn depot<'a>(&self, index: usize, vrp: &'a Vrp) -> Option<&'a Location> {
+        match self.depots.get(index) {
+            Some(&depot) => vrp.depots().nth(depot as usize),
+            None if self.depots.is_empty() => Some(&vrp.warehouse),
+            None => None,
+        }
+    }
+
+    /// The customers of `route`, `None` if an index is not a customer of `vrp`
+    fn customers<'a>(route: &[u16], vrp: &'a Vrp) -> Option<Vec<&'a Location>> {
+        route
+            .iter()
+            .map(|&i| vrp.location(i as usize).filter(|_| i != 0))
+            .collect()
+    }
+
+    /// Build the full solution on `vrp`, the instance these indices were taken from. The customers
+    /// of `vrp` no route visits are [unassigned](VrpResult::unassigned). Returns `None` if an
+    /// index is not a customer or a depot of `vrp`
+    pub fn to_result(&self, vrp: &Vrp) -> Option<VrpResult> {
+        let routes = self
+            .routes
+            .iter()
+            .enumerate()
+            .map(|(index, route)| {
+                Some(Route {
+                    customers: Self::customers(route, vrp)?.into_iter().cloned().collect(),
+                    ..vrp.empty_route_from(self.depot(index, vrp)?)
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        let visited: HashSet<u16> = self.routes.iter().flatten().copied().collect();
+        let unassigned = (1..=vrp.customers.len() as u16)
+            .filter(|i| !visited.contains(i))
+            .map(|i| vrp.customers[i as usize - 1].id)
+            .collect();
+
+        Some(VrpResult {
+            unassigned,
+            ..VrpResult::from_vrp(vrp, routes, None)
+        })
+    }
+
+    /// Get the total cost of all routes of `vrp` without cloning their customers. Same as
+    /// [VrpResult::total_cost], `None` if an index is not a customer or a depot of `vrp`
+    pub fn total_cost(&self, vrp: &Vrp) -> Option<f32> {
+        self.routes
+            .iter()
+            .enumerate()
+            .filter(|(_, route)| !route.is_empty())
+            .map(|(index, route)| {
+                let customers = Self::customers(route, vrp)?;
+                Some(
+                    vrp.empty_route_from(self.depot(index, vrp)?)
+                        .total_cost_with(&customers),
+                )
+            })
+            .sum()
+    }
+
+    /// Get the number of visited customers
+    pub fn n_customers(&self) -> usize {
+        self.routes.iter().map(Vec::len).sum()
+    }
+
+    /// The same solution in a canonical form, see [VrpResult::normalized]. Solutions only
+    /// differing by mirror-image routes of equal cost or by the order of their routes are equal
+    /// and hash the same once normalized. `None` if an index is not a customer or a depot of
+    /// `vrp`
+    pub fn normalized(&self, vrp: &Vrp) -> Option<CompactSolution> {
+        CompactSolution::from_result(&self.to_result(vrp)?.normalized(), vrp)
+    }
+
+    /// Remove the solutions equal to an earlier one once [normalized](CompactSolution::normalized),
+    /// keeps the order of the others. Solutions that are not solutions of `vrp` are compared as
+    /// they are
+    pub fn dedup(solutions: &mut Vec<CompactSolution>, vrp: &Vrp) {
+        let mut seen = HashSet::new();
+        solutions.retain(|solution| {
+            seen.insert(solution.normalized(vrp).unwrap_or_else(|| solution.clone()))
+        });
+    }
+}
+
+impl VrpResult {
+    /// Convert this solution to a [CompactSolution] of `vrp`
+    pub fn to_compact(&self, vrp: &Vrp) -> Option<CompactSolution> {
+        CompactSolution::from_result(self, vrp)
+    }
+}
@@ -0,0 +1,1007 @@
+use crate::distance::CoordinateMode;
+use crate::fleet::FleetPolicy;
+use crate::heuristics::aco::{AcoParams, AcoVariant};
+use crate::heuristics::checkpoint::Checkpoint;
+use crate::heuristics::cross_exchange::DEFAULT_MAX_SEGMENT_LEN;
+use crate::heuristics::ejection::EjectionParams;
+use crate::heuristics::grasp::GraspParams;
+use crate::heuristics::local_search::PostOptimizeOptions;
+use crate::heuristics::registry::{Heuristic, HeuristicKind};
+use crate::heuristics::simulated_annealing::{CoolingSchedule, SaParams};
+use crate::heuristics::stats::{SolveStats, Stopwatch};
+use crate::integer_time::IntegerTime;
+use crate::location::DropPenalties;
+use crate::objective::Objective;
+use crate::report::ReportOptions;
+use crate::robustness::TimeBuffer;
+use crate::session::SessionTracker;
+use crate::vehicle::AssignmentStrategy;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::borrow::Cow;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Settings of a solver run: which heuristics to chain and their parameters
+#[derive(Debug, Clone)]
+pub struct RunConfig {
+    /// Heuristics to run in order, the best result of all steps is kept
+    pub pipeline: Vec<String>,
+    /// Time limit of each step, for the heuristics that support one
+    pub time_limit: Option<Duration>,
+    /// Parameters of the aco steps
+    pub aco: AcoParams,
+    /// Initial pheromone of the aco steps, defaults to 1 / cost of the best solution of the
+    /// previous steps
+    pub pheromone_amt: Option<f32>,
+    /// Parameters of the simulated annealing steps
+    pub sa: SaParams,
+    /// Parameters of the GRASP steps
+    pub grasp: GraspParams,
+    /// Parameters of the minimize steps
+    pub ejection: EjectionParams,
+    /// Operators and strategy of the polish steps
+    pub post_optimize: PostOptimizeOptions,
+    /// Longest segment exchanged by the cross steps
+    pub cross_segment_len: usize,
+    /// Report format
+    pub format: Option<String>,
+    /// Report path
+    pub out: Option<String>,
+    /// Seed of the report charts and order of the routes in every output
+    pub report: ReportOptions,
+    /// Periodically write the best solution of the grasp, aco and sa steps to disk
+    pub checkpoint: Option<Checkpoint>,
+    /// Keep slack before every due date, the reported costs use the original time windows
+    pub time_buffer: Option<TimeBuffer>,
+    /// How the result of every step is held to the number of vehicles of the instance
+    pub fleet: FleetPolicy,
+    /// Cost of the customers the fleet repair leaves unassigned, added when comparing results
+    pub drop_penalties: DropPenalties,
+    /// How the vehicles of the instance are given to the routes of the best result
+    pub vehicle_assignment: AssignmentStrategy,
+    /// Rank the results of the steps and of the heuristics by this instead of the
+    /// [objective](Vrp::objective) of the instance
+    pub objective: Option<Objective>,
+    /// Read the coordinates of the instance as this, see
+    /// [with_coordinate_mode](Vrp::with_coordinate_mode)
+    pub coordinates: Option<CoordinateMode>,
+    /// Solve with these integer travel and service times, see
+    /// [with_integer_time](Vrp::with_integer_time), the reported costs use the original times
+    pub integer_time: Option<IntegerTime>,
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        RunConfig {
+            pipeline: vec![String::from("aco")],
+            time_limit: None,
+            aco: AcoParams::default(),
+            pheromone_amt: None,
+            sa: SaParams::default(),
+            grasp: GraspParams::default(),
+            ejection: EjectionParams::default(),
+            post_optimize: PostOptimizeOptions::default(),
+            cross_segment_len: DEFAULT_MAX_SEGMENT_LEN,
+            format: None,
+            out: None,
+            report: ReportOptions::default(),
+            checkpoint: None,
+            time_buffer: None,
+            fleet: FleetPolicy::default(),
+            drop_penalties: DropPenalties::default(),
+            vehicle_assignment: AssignmentStrategy::default(),
+            objective: None,
+            coordinates: None,
+            integer_time: None,
+        }
+    }
+}
+
+/// Error raised when a configuration cannot be loaded
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    /// The file is not valid TOML, or the JSON configuration is not an object
+    Syntax(String),
+    /// The requested profile does not exist
+    UnknownProfile(String),
+    /// A key is unknown or has a value of the wrong type
+    InvalidKey(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "Failed to read config: {}", e),
+            ConfigError::Syntax(e) => write!(f, "Invalid config: {}", e),
+            ConfigError::UnknownProfile(name) => write!(f, "Unknown profile: {}", name),
+            ConfigError::InvalidKey(key) => write!(f, "Invalid config key: {}", key),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Time between two checkpoint writes when no interval is configured
+pub const DEFAULT_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The heuristics that can be used in a [RunConfig] pipeline, `pdp` is the
+/// [pair insertion heuristic](Vrp::pair_insertion_heuristic), the only construction serving
+/// pickup and delivery pairs. Construction steps solve multi-depot instances
+/// [per depot](Vrp::solve_multi_depot). `split` improves the best result
+/// of the previous steps with [resplit](VrpResult::resplit), `2opt` with
+/// [improve_2opt](VrpResult::improve_2opt), `swap` with [improve_swap](VrpResult::improve_swap),
+/// `cross` with [improve_cross_exchange](VrpResult::improve_cross_exchange), `minimize` with
+/// [minimize_routes](VrpResult::minimize_routes), `polish` with
+/// [post_optimize](VrpResult::post_optimize) and `sa` with
+/// [simulated annealing](Vrp::simulated_annealing)
+pub const PIPELINE_STEPS: [&str; 13] = [
+    "nn", "i1", "sweep", "grasp", "aco", "pdp", "split", "2opt", "swap", "cross", "minimize",
+    "polish", "sa",
+];
+
+impl RunConfig {
+    /// Run every step of the pipeline on `vrp`, returns the best result with the
+    /// [stats](VrpResult::stats) of all steps and its [vehicles](RunConfig::vehicle_assignment).
+    /// Fails if it breaks the [fleet policy](RunConfig::fleet)
+    pub fn run(&self, vrp: &Vrp) -> Result<VrpResult, String> {
+        self.run_tracked(vrp, &mut SessionTracker::new())
+    }
+
+    /// Same as [run](RunConfig::run), recording the result of every step in `tracker`
+    pub fn run_tracked(
+        &self,
+        vrp: &Vrp,
+        tracker: &mut SessionTracker,
+    ) -> Result<VrpResult, String> {
+        let mut vrp = Cow::Borrowed(vrp);
+        if let Some(mode) = self.coordinates {
+            vrp = Cow::Owned(vrp.with_coordinate_mode(mode));
+        }
+        if let Some(objective) = self.objective {
+            vrp.to_mut().objective = objective;
+        }
+        let vrp = vrp.as_ref();
+
+        let mut solved = Cow::Borrowed(vrp);
+        if let Some(buffer) = self.time_buffer {
+            solved = Cow::Owned(solved.with_time_buffer(buffer));
+        }
+        if let Some(time) = &self.integer_time {
+            solved = Cow::Owned(solved.with_integer_time(time)?);
+        }
+
+        match solved {
+            Cow::Borrowed(vrp) => self.run_steps(vrp, tracker),
+            Cow::Owned(solved) => Ok(self.run_steps(&solved, tracker)?.with_instance(vrp)),
+        }
+    }
+
+    fn run_steps(&self, vrp: &Vrp, tracker: &mut SessionTracker) -> Result<VrpResult, String> {
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats::default();
+        let mut best: Option<VrpResult> = None;
+
+        for step in &self.pipeline {
+            let result = match (step.parse::<HeuristicKind>(), step.as_str()) {
+                // The sa step anneals the best result so far instead of building one
+                (Ok(kind), _) if kind != HeuristicKind::SimulatedAnnealing => {
+                    // These build routes one customer at a time and would leave every pair
+                    // unassigned
+                    if vrp.has_pairs() && kind != HeuristicKind::PairInsertion {
+                        return Err(format!(
+                            "The {} step cannot serve pickup and delivery pairs, use pdp",
+                            step
+                        ));
+                    }
+
+                    let (heuristic, params) = self.heuristic(kind, best.as_ref());
+                    let result = vrp.solve_multi_depot(heuristic.as_ref());
+                    tracker.record(step, params, &result);
+                    result
+                }
+                // Local improvements of the best result so far
+                (_, "split" | "2opt" | "swap" | "cross" | "minimize" | "polish") => {
+                    let mut result = VrpResult {
+                        stats: None,
+                        ..best
+                            .clone()
+                            .ok_or_else(|| format!("The {} step needs a previous step", step))?
+                    };
+                    // Whether the step changed the result does not matter, the best one is kept
+                    let _ = match step.as_str() {
+                        "split" => result.resplit(vrp),
+                        "2opt" => result.improve_2opt(),
+                        "swap" => result.improve_swap(),
+                        "cross" => result.improve_cross_exchange(self.cross_segment_len),
+                        "polish" => {
+                            let opts = PostOptimizeOptions {
+                                time_limit: self.time_limit.or(self.post_optimize.time_limit),
+                                ..self.post_optimize.clone()
+                            };
+                            result.post_optimize(&opts).iterations > 0
+                        }
+                        _ => {
+                            let params = EjectionParams {
+                                time_limit: self.time_limit.or(self.ejection.time_limit),
+                                ..self.ejection.clone()
+                            };
+                            result.minimize_routes(&params) > 0
+                        }
+                    };
+                    tracker.record(step, "", &result);
+                    result
+                }
+                (_, "sa") => {
+                    let initial = best
+                        .as_ref()
+                        .ok_or_else(|| String::from("The sa step needs a previous step"))?;
+                    let params = SaParams {
+                        time_limit: self.time_limit.or(self.sa.time_limit),
+                        checkpoint: self.checkpoint.clone().or(self.sa.checkpoint.clone()),
+                        ..self.sa.clone()
+                    };
+                    let result = vrp.simulated_annealing(initial, &params);
+                    tracker.record(
+                        "sa",
+                        format!(
+                            "initial_temperature: {}, final_temperature: {}, cooling: {:?}",
+                            params.initial_temperature, params.final_temperature, params.cooling
+                        ),
+                        &result,
+                    );
+                    result
+                }
+                (_, other) => return Err(format!("Unknown pipeline step: {}", other)),
+            };
+
+            stats += result.stats.unwrap_or_default();
+            let result = self.fleet.apply(result);
+
+            let penalty = |result: &VrpResult| result.drop_penalty(vrp, &self.drop_penalties);
+            if best
+                .as_ref()
+                .is_none_or(|best| self.fleet.is_better_by(&result, best, penalty))
+            {
+                best = Some(result);
+            }
+        }
+
+        let mut best = best.ok_or_else(|| String::from("Empty pipeline"))?;
+        self.fleet.check(&best).map_err(|e| e.to_string())?;
+
+        // A fleet policy may allow more routes than vehicles, these results keep anonymous routes
+        let _ = best.assign_vehicles(vrp, self.vehicle_assignment);
+
+        stats.wall_time = stopwatch.elapsed();
+        best.stats = Some(stats);
+        Ok(best)
+    }
+
+    /// The heuristic of a construction step with the parameters of this configuration, and
+    /// these parameters as recorded by the [tracker](SessionTracker). Without a configured
+    /// pheromone, ACO starts from 1 / the cost of the `best` result of the previous steps, or
+    /// of the nearest neighbor solution
+    fn heuristic(
+        &self,
+        kind: HeuristicKind,
+        best: Option<&VrpResult>,
+    ) -> (Box<dyn Heuristic>, String) {
+        match kind {
+            HeuristicKind::Grasp => {
+                let params = GraspParams {
+                    time_limit: self.time_limit.or(self.grasp.time_limit),
+                    checkpoint: self.checkpoint.clone().or(self.grasp.checkpoint.clone()),
+                    ..self.grasp.clone()
+                };
+                let description = format!(
+                    "iterations: {}, alpha: {}, local_search: {}",
+                    params.iterations, params.alpha, params.local_search
+                );
+                (Box::new(params), description)
+            }
+            HeuristicKind::Aco => {
+                let pheromone_amt = self
+                    .pheromone_amt
+                    .or(best.map(|best| 1.0 / best.total_cost()))
+                    .unwrap_or(self.aco.pheromone_amt);
+                let params = AcoParams {
+                    pheromone_amt,
+                    time_limit: self.time_limit.or(self.aco.time_limit),
+                    checkpoint: self.checkpoint.clone().or(self.aco.checkpoint.clone()),
+                    ..self.aco.clone()
+                };
+                let description = format!(
+                    "n_ants: {}, max_iter: {}, alpha: {}, beta: {}, rho: {}",
+                    params.n_ants, params.max_iter, params.alpha, params.beta, params.rho
+                );
+                (Box::new(params), description)
+            }
+            kind => (kind.with_defaults(), String::new()),
+        }
+    }
+}
+
+#[cfg(feature = "config")]
+impl RunConfig {
+    /// Load a configuration from a TOML file, see [from_toml_str](RunConfig::from_toml_str)
+    pub fn from_file(path: &str, profile: Option<&str>) -> Result<RunConfig, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        RunConfig::from_toml_str(&content, profile)
+    }
+
+    /// Load a configuration from a TOML string.
+    ///
+    /// Top level keys are applied first, then the keys of `[profiles.<profile>]` if a profile is
+    /// given:
+    ///
+    /// ```toml
+    /// pipeline = ["nn", "aco"]
+    /// time_limit = 30
+    /// # Absolute (in time units) or relative ("5%") slack before due dates
+    /// time_buffer = 10
+    /// # One of "repair" (default), "error", "penalize" or "ignore"
+    /// fleet = "penalize"
+    /// # Cost of every vehicle over the fleet size with "penalize"
+    /// vehicle_penalty = 500
+    /// # Cost of every high and low priority customer left unassigned to respect the fleet
+    /// high_priority_penalty = 1000
+    /// low_priority_penalty = 100
+    /// # Give vehicles to routes by "index" (default) or "capacity_fit"
+    /// vehicle_assignment = "capacity_fit"
+    /// # Rank results by "cost" (default), "distance", "vehicles_then_distance" or "weighted",
+    /// # or by the weights of a table like { vehicle = 1000, distance = 1, cost = 0 }
+    /// objective = "vehicles_then_distance"
+    /// # Read x as the longitude and y as the latitude with "geographic", travelling `speed`
+    /// # kilometers per time unit (default: 1), instead of "cartesian" (default)
+    /// coordinates = "geographic"
+    /// speed = 0.5
+    /// # Solve with "floor", "round" or "ceil" integer times, multiplied by integer_scale
+    /// # (default: 1) before rounding
+    /// integer_time = "round"
+    /// integer_scale = 10
+    /// # Seed of the route colors of the report
+    /// report_seed = 42
+    /// # Order of the routes in reports, one of "construction" (default), "cost", "load",
+    /// # "angle" or "vehicle"
+    /// route_order = "angle"
+    ///
+    /// [aco]
+    /// n_ants = 50
+    /// rho = 0.1
+    /// # Same results on every run, also accepted by [sa] and [grasp]
+    /// seed = 7
+    ///
+    /// [sa]
+    /// initial_temperature = 50
+    /// final_temperature = 0.1
+    /// # Geometric cooling, the temperature is multiplied by this after every step, or linear
+    /// # cooling with cooling_step
+    /// cooling_factor = 0.95
+    /// iterations_per_temperature = 1000
+    ///
+    /// [grasp]
+    /// iterations = 50
+    /// # Share of the cost range of the candidates in the restricted candidate list
+    /// alpha = 0.2
+    /// local_search = true
+    ///
+    /// [profiles.quick]
+    /// time_limit = 5
+    /// aco = { n_ants = 10 }
+    /// ```
+    pub fn from_toml_str(content: &str, profile: Option<&str>) -> Result<RunConfig, ConfigError> {
+        let document: toml_edit::DocumentMut = content
+            .parse()
+            .map_err(|e: toml_edit::TomlError| ConfigError::Syntax(e.to_string()))?;
+
+        let mut config = RunConfig::default();
+
+        let entries: Vec<(&str, &toml_edit::Item)> = document
+            .as_table()
+            .iter()
+            .filter(|(key, _)| *key != "profiles")
+            .collect();
+        config.apply(&entries, "")?;
+
+        if let Some(name) = profile {
+            let table = document
+                .get("profiles")
+                .and_then(|profiles| profiles.get(name))
+                .and_then(ConfigValue::as_entries)
+                .ok_or_else(|| ConfigError::UnknownProfile(String::from(name)))?;
+
+            config.apply(&table, &format!("profiles.{}.", name))?;
+        }
+
+        Ok(config)
+    }
+}
+
+impl RunConfig {
+    /// Load a configuration from a JSON object, with the keys of a
+    /// [TOML configuration](RunConfig::from_toml_str) without profiles, tables become objects:
+    ///
+    /// ```json
+    /// { "pipeline": ["nn", "aco"], "time_limit": 30, "aco": { "n_ants": 10 } }
+    /// ```
+    pub fn from_json(value: &serde_json::Value) -> Result<RunConfig, ConfigError> {
+        let entries = value
+            .as_entries()
+            .ok_or_else(|| ConfigError::Syntax(String::from("Expected an object")))?;
+
+        let mut config = RunConfig::default();
+        config.apply(&entries, "")?;
+        Ok(config)
+    }
+
+    fn apply<V: ConfigValue>(
+        &mut self,
+        table: &[(&str, &V)],
+        prefix: &str,
+    ) -> Result<(), ConfigError> {
+        for &(key, item) in table {
+            let invalid = || ConfigError::InvalidKey(format!("{}{}", prefix, key));
+
+            match key {
+                "solver" | "pipeline" => {
+                    self.pipeline = match key {
+                        "solver" => vec![String::from(item.as_str().ok_or_else(invalid)?)],
+                        _ => item
+                            .as_strings()
+                            .ok_or_else(invalid)?
+                            .into_iter()
+                            .map(String::from)
+                            .collect(),
+                    };
+
+                    if let Some(step) = self
+                        .pipeline
+                        .iter()
+                        .find(|step| !PIPELINE_STEPS.contains(&step.as_str()))
+                    {
+                        return Err(ConfigError::InvalidKey(format!(
+                            "{}{}: unknown step {}",
+                            prefix, key, step
+                        )));
+                    }
+                }
+                "time_limit" => {
+                    self.time_limit = Some(
+                        item.as_number()
+                            .and_then(|v| Duration::try_from_secs_f64(v).ok())
+                            .ok_or_else(invalid)?,
+                    )
+                }
+                "high_priority_penalty" => {
+                    self.drop_penalties.high =
+                        item.as_number().filter(|&v| v >= 0.0).ok_or_else(invalid)? as f32
+                }
+                "low_priority_penalty" => {
+                    self.drop_penalties.low =
+                        item.as_number().filter(|&v| v >= 0.0).ok_or_else(invalid)? as f32
+                }
+                "route_order" => {
+                    self.report.route_order = item
+                        .as_str()
+                        .ok_or_else(invalid)?
+                        .parse()
+                        .map_err(|_| invalid())?
+                }
+                "report_seed" => {
+                    self.report.seed = item
+                        .as_integer()
+                        .and_then(|v| u64::try_from(v).ok())
+                        .ok_or_else(invalid)?
+                }
+                "time_buffer" => {
+                    self.time_buffer = Some(match item.as_str() {
+                        Some(value) => value.parse().map_err(|_| invalid())?,
+                        None => TimeBuffer::Absolute(
+                            item.as_number().filter(|&v| v >= 0.0).ok_or_else(invalid)?,
+                        ),
+                    })
+                }
+                "fleet" => {
+                    let policy = item
+                        .as_str()
+                        .ok_or_else(invalid)?
+                        .parse()
+                        .map_err(|_| invalid())?;
+                    // Keep a penalty set by vehicle_penalty
+                    if !matches!(
+                        (policy, self.fleet),
+                        (FleetPolicy::Penalize { .. }, FleetPolicy::Penalize { .. })
+                    ) {
+                        self.fleet = policy;
+                    }
+                }
+                "vehicle_penalty" => {
+                    self.fleet = FleetPolicy::Penalize {
+                        cost_per_vehicle: item
+                            .as_number()
+                            .filter(|&v| v >= 0.0)
+                            .ok_or_else(invalid)? as f32,
+                    }
+                }
+                "coordinates" => {
+                    let mode = item
+                        .as_str()
+                        .ok_or_else(invalid)?
+                        .parse()
+                        .map_err(|_| invalid())?;
+                    // Keep a speed set by speed
+                    if !matches!(
+                        (mode, self.coordinates),
+                        (
+                            CoordinateMode::Geographic { .. },
+                            Some(CoordinateMode::Geographic { .. })
+                        )
+                    ) {
+                        self.coordinates = Some(mode);
+                    }
+                }
+                "integer_time" => {
+                    let rounding = item
+                        .as_str()
+                        .ok_or_else(invalid)?
+                        .parse()
+                        .map_err(|_| invalid())?;
+                    // Keep a scale set by integer_scale
+                    self.integer_time = Some(IntegerTime {
+                        rounding,
+                        ..self.integer_time.unwrap_or_default()
+                    });
+                }
+                "integer_scale" => {
+                    let scale = item
+                        .as_integer()
+                        .and_then(|v| u32::try_from(v).ok())
+                        .filter(|&v| v > 0)
+                        .ok_or_else(invalid)?;
+                    self.integer_time = Some(IntegerTime {
+                        scale,
+                        ..self.integer_time.unwrap_or_default()
+                    });
+                }
+                "speed" => {
+                    self.coordinates = Some(CoordinateMode::Geographic {
+                        speed: item.as_number().filter(|&v| v > 0.0).ok_or_else(invalid)?,
+                    })
+                }
+                "objective" => {
+                    self.objective = Some(match item.as_str() {
+                        Some(objective) => objective.parse().map_err(|_| invalid())?,
+                        None => {
+                            let weights = item.as_entries().ok_or_else(invalid)?;
+                            let weight = |name: &str| match weights.iter().find(|(n, _)| *n == name)
+                            {
+                                Some((_, item)) => {
+                                    item.as_number().map(|v| v as f32).ok_or_else(invalid)
+                                }
+                                None => Ok(0.0),
+                            };
+                            if let Some((name, _)) = weights
+                                .iter()
+                                .find(|(name, _)| !["vehicle", "distance", "cost"].contains(name))
+                            {
+                                return Err(ConfigError::InvalidKey(format!(
+                                    "{}{}.{}",
+                                    prefix, key, name
+                                )));
+                            }
+                            Objective::Weighted {
+                                vehicle: weight("vehicle")?,
+                                distance: weight("distance")?,
+                                cost: weight("cost")?,
+                            }
+                        }
+                    })
+                }
+                "vehicle_assignment" => {
+                    self.vehicle_assignment = item
+                        .as_str()
+                        .ok_or_else(invalid)?
+                        .parse()
+                        .map_err(|_| invalid())?
+                }
+                "cross_segment_len" => {
+                    self.cross_segment_len = item
+                        .as_integer()
+                        .and_then(|v| usize::try_from(v).ok())
+                        .filter(|&v| v > 0)
+                        .ok_or_else(invalid)?
+                }
+                "format" => self.format = Some(String::from(item.as_str().ok_or_else(invalid)?)),
+                "out" => self.out = Some(String::from(item.as_str().ok_or_else(invalid)?)),
+                "checkpoint" => {
+                    let path = PathBuf::from(item.as_str().ok_or_else(invalid)?);
+                    let interval = self
+                        .checkpoint
+                        .as_ref()
+                        .map_or(DEFAULT_CHECKPOINT_INTERVAL, |c| c.interval);
+                    self.checkpoint = Some(Checkpoint::new(path, interval));
+                }
+                "checkpoint_interval" => {
+                    let interval = item
+                        .as_number()
+                        .and_then(|v| Duration::try_from_secs_f64(v).ok())
+                        .ok_or_else(invalid)?;
+                    match &mut self.checkpoint {
+                        Some(checkpoint) => checkpoint.interval = interval,
+                        None => {
+                            return Err(ConfigError::InvalidKey(format!(
+                                "{}{}: needs a checkpoint path",
+                                prefix, key
+                            )))
+                        }
+                    }
+                }
+                "aco" => {
+                    let table = item.as_entries().ok_or_else(invalid)?;
+                    self.apply_aco(&table, &format!("{}aco.", prefix))?;
+                }
+                "sa" => {
+                    let table = item.as_entries().ok_or_else(invalid)?;
+                    self.apply_sa(&table, &format!("{}sa.", prefix))?;
+                }
+                "grasp" => {
+                    let table = item.as_entries().ok_or_else(invalid)?;
+                    self.apply_grasp(&table, &format!("{}grasp.", prefix))?;
+                }
+                "post_optimize" => {
+                    let table = item.as_entries().ok_or_else(invalid)?;
+                    self.apply_post_optimize(&table, &format!("{}post_optimize.", prefix))?;
+                }
+                "ejection" => {
+                    let table = item.as_entries().ok_or_else(invalid)?;
+                    self.apply_ejection(&table, &format!("{}ejection.", prefix))?;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_aco<V: ConfigValue>(
+        &mut self,
+        table: &[(&str, &V)],
+        prefix: &str,
+    ) -> Result<(), ConfigError> {
+        if let Some((_, item)) = table.iter().find(|(key, _)| *key == "variant") {
+            let variant: AcoVariant = item
+                .as_str()
+                .and_then(|v| v.parse().ok())
+                .ok_or_else(|| ConfigError::InvalidKey(format!("{}variant", prefix)))?;
+            // Keep the parameters of a variant selected by a previous profile
+            if std::mem::discriminant(&variant) != std::mem::discriminant(&self.aco.variant) {
+                self.aco.variant = variant;
+            }
+        }
+
+        for &(key, item) in table {
+            let invalid = || ConfigError::InvalidKey(format!("{}{}", prefix, key));
+
+            let integer = || {
+                item.as_integer()
+                    .and_then(|v| u16::try_from(v).ok())
+                    .ok_or_else(invalid)
+            };
+
+            match key {
+                "n_ants" => self.aco.n_ants = integer()?,
+                "max_iter" => self.aco.max_iter = integer()?,
+                "alpha" => self.aco.alpha = integer()?,
+                "beta" => self.aco.beta = integer()?,
+                "rho" => self.aco.rho = item.as_number().ok_or_else(invalid)? as f32,
+                "pheromone_amt" => {
+                    self.pheromone_amt = Some(item.as_number().ok_or_else(invalid)? as f32)
+                }
+                "candidate_list_size" => self.aco.candidate_list_size = Some(integer()? as usize),
+                "stagnation_limit" => self.aco.stagnation_limit = integer()?,
+                "restart_keep_best" => {
+                    self.aco.restart_keep_best = item.as_bool().ok_or_else(invalid)?
+                }
+                "elitist_weight" => {
+                    self.aco.elitist_weight =
+                        item.as_number().filter(|&v| v >= 0.0).ok_or_else(invalid)? as f32
+                }
+                "local_search" => {
+                    self.aco.local_search = item
+                        .as_str()
+                        .ok_or_else(invalid)?
+                        .parse()
+                        .map_err(|_| invalid())?
+                }
+                "local_search_operators" => {
+                    self.aco.local_search_options.operators = item
+                        .as_strings()
+                        .ok_or_else(invalid)?
+                        .into_iter()
+                        .map(|v| v.parse().map_err(|_| invalid()))
+                        .collect::<Result<_, _>>()?
+                }
+                "seed" => {
+                    self.aco.seed = Some(
+                        item.as_integer()
+                            .and_then(|v| u64::try_from(v).ok())
+                            .ok_or_else(invalid)?,
+                    )
+                }
+                "q0" => {
+                    self.aco.q0 = item
+                        .as_number()
+                        .filter(|v| (0.0..=1.0).contains(v))
+                        .ok_or_else(invalid)? as f32
+                }
+                "xi" => {
+                    self.aco.xi = item
+                        .as_number()
+                        .filter(|v| (0.0..=1.0).contains(v))
+                        .ok_or_else(invalid)? as f32
+                }
+                // Read before the other keys
+                "variant" => {}
+                "ranked_ants" => {
+                    let AcoVariant::RankBased { ranked_ants } = &mut self.aco.variant else {
+                        return Err(ConfigError::InvalidKey(format!(
+                            "{}{}: needs variant = \"rank\"",
+                            prefix, key
+                        )));
+                    };
+                    *ranked_ants = integer()?;
+                }
+                "mmas_deposit" | "tau_min" | "tau_max" | "reinit_after" => {
+                    let AcoVariant::MaxMin(mmas) = &mut self.aco.variant else {
+                        return Err(ConfigError::InvalidKey(format!(
+                            "{}{}: needs variant = \"mmas\"",
+                            prefix, key
+                        )));
+                    };
+                    let bound = || {
+                        item.as_number()
+                            .filter(|&v| v > 0.0)
+                            .map(|v| Some(v as f32))
+                            .ok_or_else(invalid)
+                    };
+
+                    match key {
+                        "mmas_deposit" => {
+                            mmas.deposit = item
+                                .as_str()
+                                .ok_or_else(invalid)?
+                                .parse()
+                                .map_err(|_| invalid())?
+                        }
+                        "tau_min" => mmas.tau_min = bound()?,
+                        "tau_max" => mmas.tau_max = bound()?,
+                        _ => mmas.reinit_after = integer()?,
+                    }
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_sa<V: ConfigValue>(
+        &mut self,
+        table: &[(&str, &V)],
+        prefix: &str,
+    ) -> Result<(), ConfigError> {
+        for &(key, item) in table {
+            let invalid = || ConfigError::InvalidKey(format!("{}{}", prefix, key));
+            let positive = || {
+                item.as_number()
+                    .filter(|&v| v > 0.0)
+                    .map(|v| v as f32)
+                    .ok_or_else(invalid)
+            };
+
+            match key {
+                "initial_temperature" => self.sa.initial_temperature = positive()?,
+                "final_temperature" => self.sa.final_temperature = positive()?,
+                "cooling_factor" => {
+                    self.sa.cooling = CoolingSchedule::Geometric {
+                        factor: Some(positive()?).filter(|&v| v < 1.0).ok_or_else(invalid)?,
+                    }
+                }
+                "cooling_step" => self.sa.cooling = CoolingSchedule::Linear { step: positive()? },
+                "iterations_per_temperature" => {
+                    self.sa.iterations_per_temperature = item
+                        .as_integer()
+                        .and_then(|v| u32::try_from(v).ok())
+                        .ok_or_else(invalid)?
+                }
+                "seed" => {
+                    self.sa.seed = Some(
+                        item.as_integer()
+                            .and_then(|v| u64::try_from(v).ok())
+                            .ok_or_else(invalid)?,
+                    )
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_grasp<V: ConfigValue>(
+        &mut self,
+        table: &[(&str, &V)],
+        prefix: &str,
+    ) -> Result<(), ConfigError> {
+        for &(key, item) in table {
+            let invalid = || ConfigError::InvalidKey(format!("{}{}", prefix, key));
+
+            match key {
+                "iterations" => {
+                    self.grasp.iterations = item
+                        .as_integer()
+                        .and_then(|v| u32::try_from(v).ok())
+                        .ok_or_else(invalid)?
+                }
+                "alpha" => {
+                    self.grasp.alpha = item
+                        .as_number()
+                        .filter(|v| (0.0..=1.0).contains(v))
+                        .ok_or_else(invalid)? as f32
+                }
+                "local_search" => self.grasp.local_search = item.as_bool().ok_or_else(invalid)?,
+                "seed" => {
+                    self.grasp.seed = Some(
+                        item.as_integer()
+                            .and_then(|v| u64::try_from(v).ok())
+                            .ok_or_else(invalid)?,
+                    )
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_post_optimize<V: ConfigValue>(
+        &mut self,
+        table: &[(&str, &V)],
+        prefix: &str,
+    ) -> Result<(), ConfigError> {
+        for &(key, item) in table {
+            let invalid = || ConfigError::InvalidKey(format!("{}{}", prefix, key));
+
+            match key {
+                "operators" => {
+                    self.post_optimize.operators = item
+                        .as_strings()
+                        .ok_or_else(invalid)?
+                        .into_iter()
+                        .map(|v| v.parse().map_err(|_| invalid()))
+                        .collect::<Result<_, _>>()?
+                }
+                "strategy" => {
+                    self.post_optimize.strategy = item
+                        .as_str()
+                        .ok_or_else(invalid)?
+                        .parse()
+                        .map_err(|_| invalid())?
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_ejection<V: ConfigValue>(
+        &mut self,
+        table: &[(&str, &V)],
+        prefix: &str,
+    ) -> Result<(), ConfigError> {
+        for &(key, item) in table {
+            let invalid = || ConfigError::InvalidKey(format!("{}{}", prefix, key));
+
+            match key {
+                "max_ejected" => {
+                    self.ejection.max_ejected = item
+                        .as_integer()
+                        .and_then(|v| usize::try_from(v).ok())
+                        .filter(|&v| v > 0)
+                        .ok_or_else(invalid)?
+                }
+                "max_iterations" => {
+                    self.ejection.max_iterations = item
+                        .as_integer()
+                        .and_then(|v| u32::try_from(v).ok())
+                        .ok_or_else(invalid)?
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A value of a configuration, read from TOML or JSON
+trait ConfigValue {
+    fn as_str(&self) -> Option<&str>;
+
+    fn as_bool(&self) -> Option<bool>;
+
+    fn as_integer(&self) -> Option<i64>;
+
+    /// An integer or finite float value
+    fn as_number(&self) -> Option<f64>;
+
+    /// An array of strings
+    fn as_strings(&self) -> Option<Vec<&str>>;
+
+    /// The keys and values of a table, in order
+    fn as_entries(&self) -> Option<Vec<(&str, &Self)>>;
+}
+
+#[cfg(feature = "config")]
+impl ConfigValue for toml_edit::Item {
+    fn as_str(&self) -> Option<&str> {
+        toml_edit::Item::as_str(self)
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        toml_edit::Item::as_bool(self)
+    }
+
+    fn as_integer(&self) -> Option<i64> {
+        toml_edit::Item::as_integer(self)
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        self.as_float()
+            .or_else(|| self.as_integer().map(|v| v as f64))
+            .filter(|v| v.is_finite())
+    }
+
+    fn as_strings(&self) -> Option<Vec<&str>> {
+        self.as_array()?
+            .iter()
+            .map(toml_edit::Value::as_str)
+            .collect()
+    }
+
+    fn as_entries(&self) -> Option<Vec<(&str, &Self)>> {
+        Some(self.as_table_like()?.iter().collect())
+    }
+}
+
+impl ConfigValue for serde_json::Value {
+    fn as_str(&self) -> Option<&str> {
+        serde_json::Value::as_str(self)
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        serde_json::Value::as_bool(self)
+    }
+
+    fn as_integer(&self) -> Option<i64> {
+        self.as_i64()
+    }
+
+    fn as_number(&self) -> Option<f64> {
+        self.as_f64().filter(|v| v.is_finite())
+    }
+
+    fn as_strings(&self) -> Option<Vec<&str>> {
+        self.as_array()?
+            .iter()
+            .map(serde_json::Value::as_str)
+            .collect()
+    }
+
+    fn as_entries(&self) -> Option<Vec<(&str, &Self)>> {
+        Some(
+            self.as_object()?
+                .iter()
+                .map(|(key, value)| (key.as_str(), value))
+                .collect(),
+        )
+    }
+}
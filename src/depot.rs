@@ -0,0 +1,166 @@
+use crate::route::Route;
+use crate::vrp_result::VrpResult;
+
+/// Vehicle movements at the depot during one time bucket, see
+/// [depot_occupancy](VrpResult::depot_occupancy)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DepotBucket {
+    pub start: f32,
+    pub end: f32,
+    /// Vehicles leaving the depot during the bucket
+    pub departures: usize,
+    /// Vehicles coming back to the depot during the bucket
+    pub arrivals: usize,
+    /// Most vehicles at the depot at the same time during the bucket, the loading docks needed
+    pub at_depot: usize,
+}
+
+/// Most buckets of a [depot occupancy](VrpResult::depot_occupancy), every bucket counts the
+/// vehicles of every route
+pub const MAX_DEPOT_BUCKETS: usize = 100_000;
+
+/// Use of the depot over the planning horizon, in buckets of equal length
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DepotOccupancy {
+    pub bucket_size: f32,
+    pub buckets: Vec<DepotBucket>,
+}
+
+impl DepotOccupancy {
+    /// Get the most vehicles at the depot at the same time over the horizon
+    pub fn peak_at_depot(&self) -> usize {
+        self.buckets.iter().map(|b| b.at_depot).max().unwrap_or(0)
+    }
+
+    /// Get the most vehicles arriving during one bucket
+    pub fn peak_arrivals(&self) -> usize {
+        self.buckets.iter().map(|b| b.arrivals).max().unwrap_or(0)
+    }
+
+    /// The buckets needing more than `docks` vehicles at the depot at the same time
+    pub fn over_capacity(&self, docks: usize) -> impl Iterator<Item = &DepotBucket> {
+        self.buckets.iter().filter(move |b| b.at_depot > docks)
+    }
+
+    /// Print this occupancy to a Markdown table, skipping the buckets without movement
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format! {"- Peak vehicles at the depot: {}\n", self.peak_at_depot()});
+        output.push_str(&format! {"- Peak arrivals per bucket: {}\n", self.peak_arrivals()});
+
+        output.push_str("\n| Time | Departures | Arrivals | At depot |\n|---|---|---|---|\n");
+        for bucket in self
+            .buckets
+            .iter()
+            .filter(|b| b.departures > 0 || b.arrivals > 0)
+        {
+            output.push_str(&format! {
+                "| {:.0} - {:.0} | {} | {} | {} |\n",
+                bucket.start, bucket.end, bucket.departures, bucket.arrivals, bucket.at_depot
+            });
+        }
+        output
+    }
+}
+
+impl Route {
+    /// Get the times the vehicle leaves and comes back to the depot, or leaves its last customer
+    /// for open routes, `None` for empty routes.
+    ///
+    /// The [schedule](Route::schedule) leaves at 0 and waits at the customers, the vehicle can
+    /// as well stay at the depot during this waiting time, so it leaves at its
+    /// [latest departure](Route::latest_departure)
+    pub fn depot_times(&self) -> Option<(f32, f32)> {
+        let last = *self.schedule().last()?;
+
+        // Open routes end at their last customer
+        let end = if self.open {
+            last.departure
+        } else {
+            self.customers
+                .last()?
+                .cost_to(&self.warehouse, last.departure)
+        };
+
+        Some((self.latest_departure(), end))
+    }
+}
+
+impl VrpResult {
+    /// Count the vehicles leaving, coming back to and staying at the depot in every time bucket
+    /// of `bucket_size`, from time 0 to the closing of the depot or the last return.
+    ///
+    /// Every route is one vehicle, at the depot until it [leaves](Route::depot_times) and again
+    /// once it is back. Vehicles of [open](Route::open) routes never come back. Fails if
+    /// `bucket_size` is not positive or splits the horizon into more than [MAX_DEPOT_BUCKETS]
+    pub fn depot_occupancy(&self, bucket_size: f32) -> Result<DepotOccupancy, String> {
+        if !(bucket_size > 0.0 && bucket_size.is_finite()) {
+            return Err(format!("Invalid bucket size: {}", bucket_size));
+        }
+
+        let times: Vec<(f32, f32)> = self
+            .routes
+            .iter()
+            .filter_map(|route| {
+                let (departure, end) = route.depot_times()?;
+                Some((departure, if route.open { f32::INFINITY } else { end }))
+            })
+            .collect();
+
+        let horizon = self
+            .routes
+            .first()
+            .map_or(0.0, |route| route.warehouse.due_date as f32)
+            .max(
+                times
+                    .iter()
+                    .map(|t| t.1)
+                    .filter(|back| back.is_finite())
+                    .fold(0.0, f32::max),
+            );
+        let n_buckets = (horizon / bucket_size).ceil().max(1.0);
+        if n_buckets > MAX_DEPOT_BUCKETS as f32 {
+            return Err(format!(
+                "A bucket size of {} splits the horizon of {} into more than {} buckets",
+                bucket_size, horizon, MAX_DEPOT_BUCKETS
+            ));
+        }
+        let n_buckets = n_buckets as usize;
+
+        let at_depot = |time: f32| {
+            times
+                .iter()
+                .filter(|&&(departure, back)| time <= departure || time >= back)
+                .count()
+        };
+
+        let buckets = (0..n_buckets)
+            .map(|i| {
+                let start = i as f32 * bucket_size;
+                let end = start + bucket_size;
+                let within = |time: f32| start <= time && time < end;
+
+                // Vehicles only join the depot when they come back, the peak is at the start of
+                // the bucket or at an arrival
+                let peak = times
+                    .iter()
+                    .filter(|t| within(t.1))
+                    .map(|t| at_depot(t.1))
+                    .fold(at_depot(start), usize::max);
+
+                DepotBucket {
+                    start,
+                    end,
+                    departures: times.iter().filter(|t| within(t.0)).count(),
+                    arrivals: times.iter().filter(|t| within(t.1)).count(),
+                    at_depot: peak,
+                }
+            })
+            .collect();
+
+        Ok(DepotOccupancy {
+            bucket_size,
+            buckets,
+        })
+    }
+}
@@ -0,0 +1,181 @@
+use crate::location::{Location, LocationKind, Pairing};
+use crate::route::Route;
+use crate::vrp::Vrp;
+use std::collections::HashSet;
+
+/// Problems of an instance found by [diagnose](Vrp::diagnose)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagnosis {
+    /// Customers a dedicated vehicle cannot serve within their time window
+    pub unreachable: Vec<u16>,
+    /// Customers whose demand exceeds the vehicle capacity
+    pub over_capacity: Vec<u16>,
+    /// Ids used by more than one location
+    pub duplicate_ids: Vec<u16>,
+    /// Inconsistencies of the warehouse, as human readable messages
+    pub depot_issues: Vec<String>,
+    /// Lower bound on the number of vehicles needed to carry the demand, see
+    /// [min_fleet_size](Vrp::min_fleet_size)
+    pub min_vehicles: u16,
+    pub n_vehicles: u16,
+}
+
+impl Diagnosis {
+    /// Whether none of the problems rules out a feasible solution
+    pub fn is_ok(&self) -> bool {
+        self.unreachable.is_empty()
+            && self.over_capacity.is_empty()
+            && self.duplicate_ids.is_empty()
+            && self.depot_issues.is_empty()
+            && self.min_vehicles <= self.n_vehicles
+    }
+
+    /// Print this diagnosis to a Markdown string
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("# Diagnosis\n\n");
+
+        if self.is_ok() {
+            output.push_str("No problem found\n");
+        }
+
+        let list = |output: &mut String, title: &str, ids: &[u16]| {
+            if !ids.is_empty() {
+                output.push_str(&format! {"- {}: {:?}\n", title, ids});
+            }
+        };
+
+        list(&mut output, "Unreachable customers", &self.unreachable);
+        list(&mut output, "Customers over capacity", &self.over_capacity);
+        list(&mut output, "Duplicate ids", &self.duplicate_ids);
+
+        for issue in &self.depot_issues {
+            output.push_str(&format! {"- Depot: {}\n", issue});
+        }
+
+        output.push_str(&format! {
+            "- Vehicles: at least {} needed, {} available\n",
+            self.min_vehicles, self.n_vehicles
+        });
+        output
+    }
+}
+
+impl Vrp {
+    /// Whether a vehicle of one of the [depots](Vrp::depots) serving only `customer`, and its
+    /// pickup and delivery partner if it has one, respects its capacity, every time window and
+    /// the max route duration. Heuristics leave the other customers
+    /// [unassigned](crate::vrp_result::VrpResult::unassigned)
+    pub fn can_serve(&self, customer: &Location) -> bool {
+        self.depots().any(|depot| {
+            self.dedicated_route_from(customer, depot)
+                .is_valid(self.vehicle_capacity)
+        })
+    }
+
+    /// Same as [can_serve](Vrp::can_serve) for the heuristics building routes one customer at a
+    /// time, which never serve the customers of a pickup and delivery pair
+    pub(crate) fn can_serve_alone(&self, customer: &Location) -> bool {
+        customer.pairing.is_none() && self.can_serve(customer)
+    }
+
+    /// Whether some customers are pickup and delivery pairs, only the
+    /// [pair insertion heuristic](Vrp::pair_insertion_heuristic) serves them
+    pub fn has_pairs(&self) -> bool {
+        self.customers.iter().any(|c| c.pairing.is_some())
+    }
+
+    /// The route of a vehicle of the warehouse dedicated to `customer`, and to its pickup and
+    /// delivery partner if it has one
+    pub(crate) fn dedicated_route(&self, customer: &Location) -> Route {
+        self.dedicated_route_from(customer, &self.warehouse)
+    }
+
+    /// Same as [dedicated_route](Vrp::dedicated_route), for a vehicle of `depot`
+    pub(crate) fn dedicated_route_from(&self, customer: &Location, depot: &Location) -> Route {
+        let partner = |id: u16| self.customers.iter().find(|c| c.id == id).cloned();
+        let customers = match customer.pairing {
+            None => vec![customer.clone()],
+            Some(Pairing::Pickup { delivery }) => std::iter::once(customer.clone())
+                .chain(partner(delivery))
+                .collect(),
+            Some(Pairing::Delivery { pickup }) => partner(pickup)
+                .into_iter()
+                .chain(std::iter::once(customer.clone()))
+                .collect(),
+        };
+
+        Route {
+            customers,
+            ..self.empty_route_from(depot)
+        }
+    }
+
+    /// Check this instance for problems ruling out a feasible solution, before running any
+    /// heuristic
+    pub fn diagnose(&self) -> Diagnosis {
+        let mut diagnosis = Diagnosis {
+            n_vehicles: self.n_vehicles,
+            ..Default::default()
+        };
+
+        for customer in &self.customers {
+            if customer.demand > self.vehicle_capacity {
+                diagnosis.over_capacity.push(customer.id);
+            }
+
+            // A customer that cannot be served by a dedicated vehicle of any depot cannot be
+            // served at all
+            if !self.depots().any(|depot| {
+                self.dedicated_route_from(customer, depot)
+                    .is_valid(f64::INFINITY)
+            }) {
+                diagnosis.unreachable.push(customer.id);
+            }
+        }
+
+        let mut ids = HashSet::new();
+        for location in self.locations() {
+            if !ids.insert(location.id) && !diagnosis.duplicate_ids.contains(&location.id) {
+                diagnosis.duplicate_ids.push(location.id);
+            }
+        }
+
+        let warehouse = &self.warehouse;
+        if warehouse.ready_time > warehouse.due_date {
+            diagnosis.depot_issues.push(format!(
+                "opens at {} after closing at {}",
+                warehouse.ready_time, warehouse.due_date
+            ));
+        }
+        if warehouse.demand != 0.0 {
+            diagnosis
+                .depot_issues
+                .push(format!("has a demand of {}", warehouse.demand));
+        }
+        if let Some(customer) = self
+            .customers
+            .iter()
+            .find(|c| c.kind == LocationKind::Depot)
+        {
+            diagnosis.depot_issues.push(format!(
+                "customer {} is marked as a depot, only the warehouse can be one",
+                customer.id
+            ));
+        }
+        if let Some(customer) = self
+            .customers
+            .iter()
+            .find(|c| c.ready_time > warehouse.due_date)
+        {
+            diagnosis.depot_issues.push(format!(
+                "closes at {} before customer {} opens at {}",
+                warehouse.due_date, customer.id, customer.ready_time
+            ));
+        }
+
+        diagnosis.min_vehicles = self.min_fleet_size();
+
+        diagnosis
+    }
+}
@@ -0,0 +1,306 @@
+#[cfg(feature = "road")]
+pub mod road;
+
+use crate::location::{hash_f64, Location};
+use crate::vrp::Vrp;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A source of travel distances and durations between points.
+///
+/// Points are `(x, y)` pairs, road network providers read them as `(longitude, latitude)`.
+pub trait DistanceProvider {
+    /// Compute the full matrix between all `points`
+    fn matrix(&self, points: &[(f64, f64)]) -> Result<TravelMatrix, ProviderError>;
+}
+
+/// The distances and durations between every pair of points, row-major
+#[derive(Debug, Clone, PartialEq)]
+pub struct TravelMatrix {
+    pub size: usize,
+    /// Unreachable pairs are `f32::INFINITY`
+    pub distances: Vec<f32>,
+    /// Unreachable pairs are `f32::INFINITY`
+    pub durations: Vec<f32>,
+}
+
+impl TravelMatrix {
+    /// An explicit matrix from the rows of its `distances` and optional `durations`, travelling
+    /// takes as long as the distance without durations. `None` if a matrix is not square or
+    /// both do not have the same size
+    pub fn explicit(
+        distances: Vec<Vec<f32>>,
+        durations: Option<Vec<Vec<f32>>>,
+    ) -> Option<TravelMatrix> {
+        let size = distances.len();
+        let flatten = |rows: Vec<Vec<f32>>| {
+            (rows.len() == size && rows.iter().all(|row| row.len() == size))
+                .then(|| rows.into_iter().flatten().collect::<Vec<f32>>())
+        };
+
+        let distances = flatten(distances)?;
+        let durations = match durations {
+            Some(durations) => flatten(durations)?,
+            None => distances.clone(),
+        };
+
+        Some(TravelMatrix {
+            size,
+            distances,
+            durations,
+        })
+    }
+
+    pub fn distance(&self, from: usize, to: usize) -> f32 {
+        self.distances[from * self.size + to]
+    }
+
+    pub fn duration(&self, from: usize, to: usize) -> f32 {
+        self.durations[from * self.size + to]
+    }
+}
+
+/// The row and column of a [Location] in the explicit [TravelMatrix] shared by the locations of
+/// its instance, see [with_travel_matrix](Vrp::with_travel_matrix). Two indices are equal if
+/// they point into the same matrix
+#[derive(Debug, Clone)]
+pub struct MatrixIndex {
+    pub matrix: Arc<TravelMatrix>,
+    pub index: usize,
+}
+
+impl MatrixIndex {
+    /// The distance and duration from this index to `other`, `None` if they are not in the
+    /// same matrix
+    pub fn travel_to(&self, other: &MatrixIndex) -> Option<(f32, f32)> {
+        Arc::ptr_eq(&self.matrix, &other.matrix).then(|| {
+            (
+                self.matrix.distance(self.index, other.index),
+                self.matrix.duration(self.index, other.index),
+            )
+        })
+    }
+}
+
+impl PartialEq for MatrixIndex {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.matrix, &other.matrix) && self.index == other.index
+    }
+}
+
+impl Eq for MatrixIndex {}
+
+// Hashing the whole matrix for every location would be far too slow, equal indices have the
+// same hash anyway
+impl Hash for MatrixIndex {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl Hash for TravelMatrix {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        for &value in self.distances.iter().chain(&self.durations) {
+            hash_f64(f64::from(value), state);
+        }
+    }
+}
+
+/// Error raised when a [DistanceProvider] cannot compute a matrix
+#[derive(Debug)]
+pub enum ProviderError {
+    Io(std::io::Error),
+    /// The provider answered with an error
+    Request(String),
+    /// The answer of the provider cannot be understood
+    InvalidResponse(String),
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderError::Io(e) => write!(f, "Failed to reach the provider: {}", e),
+            ProviderError::Request(e) => write!(f, "The provider returned an error: {}", e),
+            ProviderError::InvalidResponse(e) => write!(f, "Invalid provider response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+impl From<std::io::Error> for ProviderError {
+    fn from(e: std::io::Error) -> Self {
+        ProviderError::Io(e)
+    }
+}
+
+/// Straight line distances, travelling one distance unit takes one time unit like in the
+/// solomon instances
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Euclidean;
+
+impl DistanceProvider for Euclidean {
+    fn matrix(&self, points: &[(f64, f64)]) -> Result<TravelMatrix, ProviderError> {
+        let distances: Vec<f32> = points
+            .iter()
+            .flat_map(|a| {
+                points
+                    .iter()
+                    .map(move |b| ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt() as f32)
+            })
+            .collect();
+
+        Ok(TravelMatrix {
+            size: points.len(),
+            durations: distances.clone(),
+            distances,
+        })
+    }
+}
+
+/// Mean radius of the earth in kilometers
+pub const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Great-circle distances in kilometers between `(longitude, latitude)` points in degrees,
+/// travelled at `speed` kilometers per time unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Haversine {
+    pub speed: f64,
+}
+
+impl Default for Haversine {
+    /// One kilometer per time unit, 60 km/h with times in minutes
+    fn default() -> Self {
+        Haversine { speed: 1.0 }
+    }
+}
+
+impl Haversine {
+    /// The great-circle distance in kilometers between two `(longitude, latitude)` points
+    pub fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+        let (lat_a, lat_b) = (a.1.to_radians(), b.1.to_radians());
+        let half_lat = (lat_b - lat_a) / 2.0;
+        let half_lon = (b.0 - a.0).to_radians() / 2.0;
+
+        let h = half_lat.sin().powi(2) + lat_a.cos() * lat_b.cos() * half_lon.sin().powi(2);
+        // Rounding may push h slightly above 1 for antipodal points
+        2.0 * EARTH_RADIUS_KM * h.sqrt().min(1.0).asin()
+    }
+}
+
+impl DistanceProvider for Haversine {
+    fn matrix(&self, points: &[(f64, f64)]) -> Result<TravelMatrix, ProviderError> {
+        let distances: Vec<f64> = points
+            .iter()
+            .flat_map(|&a| points.iter().map(move |&b| Haversine::distance(a, b)))
+            .collect();
+
+        Ok(TravelMatrix {
+            size: points.len(),
+            durations: distances.iter().map(|d| (d / self.speed) as f32).collect(),
+            distances: distances.into_iter().map(|d| d as f32).collect(),
+        })
+    }
+}
+
+/// How the coordinates of the locations are read, see
+/// [with_coordinate_mode](Vrp::with_coordinate_mode)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CoordinateMode {
+    /// Points on a plane, straight line distances travelled at one unit per time unit like in
+    /// the solomon instances
+    #[default]
+    Cartesian,
+    /// `x` is the longitude and `y` the latitude in degrees, distances are
+    /// [great-circle](Haversine) kilometers travelled at `speed` kilometers per time unit
+    Geographic { speed: f64 },
+}
+
+impl FromStr for CoordinateMode {
+    type Err = String;
+
+    /// Parse `cartesian` or `geographic`, `geographic` travels at the
+    /// [default speed](Haversine::default)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cartesian" => Ok(CoordinateMode::Cartesian),
+            "geographic" => Ok(CoordinateMode::Geographic {
+                speed: Haversine::default().speed,
+            }),
+            other => Err(format!("Unknown coordinate mode: {}", other)),
+        }
+    }
+}
+
+impl Vrp {
+    /// The coordinates of all locations, indexed like [locations](Vrp::locations) followed by
+    /// the [other depots](Vrp::other_depots), the rows of a
+    /// [travel matrix](Vrp::with_travel_matrix)
+    pub fn points(&self) -> Vec<(f64, f64)> {
+        self.locations()
+            .chain(&self.other_depots)
+            .map(|l| (l.x, l.y))
+            .collect()
+    }
+
+    /// Compute the travel matrix of this VRP with `provider`, indexed like
+    /// [points](Vrp::points)
+    pub fn travel_matrix(
+        &self,
+        provider: &impl DistanceProvider,
+    ) -> Result<TravelMatrix, ProviderError> {
+        provider.matrix(&self.points())
+    }
+
+    /// The same instance where every cost is read from `matrix` instead of computed from the
+    /// coordinates. The matrix is indexed like [locations](Vrp::locations), followed by the
+    /// [other depots](Vrp::other_depots). `None` if its size is not the number of locations
+    pub fn with_travel_matrix(&self, matrix: TravelMatrix) -> Option<Vrp> {
+        if matrix.size != self.locations().count() + self.other_depots.len() {
+            return None;
+        }
+
+        let matrix = Arc::new(matrix);
+        let mut vrp = self.clone();
+        let locations = std::iter::once(&mut vrp.warehouse)
+            .chain(&mut vrp.customers)
+            .chain(&mut vrp.other_depots);
+        for (index, location) in locations.enumerate() {
+            location.matrix_index = Some(MatrixIndex {
+                matrix: Arc::clone(&matrix),
+                index,
+            });
+        }
+
+        Some(vrp)
+    }
+
+    /// The same instance with its coordinates read as `mode`, geographic coordinates give
+    /// it a [travel matrix](Vrp::with_travel_matrix) of great-circle distances. Cartesian
+    /// coordinates leave it unchanged
+    pub fn with_coordinate_mode(&self, mode: CoordinateMode) -> Vrp {
+        match mode {
+            CoordinateMode::Cartesian => self.clone(),
+            CoordinateMode::Geographic { speed } => {
+                let matrix = Haversine { speed }
+                    .matrix(&self.points())
+                    .expect("Great-circle distances are always computed");
+                self.with_travel_matrix(matrix)
+                    .expect("The matrix has a row for every location")
+            }
+        }
+    }
+}
+
+impl Location {
+    /// The distance and duration to `other` in the explicit travel matrix of both, `None` if
+    /// they have none
+    pub(crate) fn matrix_travel_to(&self, other: &Location) -> Option<(f32, f32)> {
+        self.matrix_index
+            .as_ref()?
+            .travel_to(other.matrix_index.as_ref()?)
+    }
+}
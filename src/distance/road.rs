@@ -0,0 +1,266 @@
+use super::{DistanceProvider, ProviderError, TravelMatrix};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Road network distances (in meters) and durations (in seconds) from the table service of an
+/// [OSRM](https://project-osrm.org) server
+#[derive(Debug, Clone)]
+pub struct OsrmProvider {
+    /// Base URL of the server, only plain `http://` is supported
+    pub base_url: String,
+    /// Routing profile, usually `driving`
+    pub profile: String,
+    /// Directory where responses are cached, the same points are only fetched once
+    pub cache_dir: Option<PathBuf>,
+    pub timeout: Duration,
+}
+
+impl OsrmProvider {
+    pub fn new(base_url: &str) -> OsrmProvider {
+        OsrmProvider {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            profile: String::from("driving"),
+            cache_dir: None,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl DistanceProvider for OsrmProvider {
+    fn matrix(&self, points: &[(f64, f64)]) -> Result<TravelMatrix, ProviderError> {
+        let coordinates: Vec<String> = points
+            .iter()
+            .map(|(lon, lat)| format!("{},{}", lon, lat))
+            .collect();
+
+        let url = format!(
+            "{}/table/v1/{}/{}?annotations=duration,distance",
+            self.base_url,
+            self.profile,
+            coordinates.join(";")
+        );
+
+        let response = cached(&self.cache_dir, &url, "", || {
+            http_request("GET", &url, "", self.timeout)
+        })?;
+
+        if let Some(code) = response.get("code").and_then(Value::as_str) {
+            if code != "Ok" {
+                let message = response.get("message").and_then(Value::as_str);
+                return Err(ProviderError::Request(format!(
+                    "{}: {}",
+                    code,
+                    message.unwrap_or_default()
+                )));
+            }
+        }
+
+        let rows = |key: &str| {
+            response
+                .get(key)
+                .and_then(Value::as_array)
+                .ok_or_else(|| ProviderError::InvalidResponse(format!("Missing {}", key)))
+        };
+
+        Ok(TravelMatrix {
+            size: points.len(),
+            distances: flatten(rows("distances")?, points.len(), |v| v.as_f64())?,
+            durations: flatten(rows("durations")?, points.len(), |v| v.as_f64())?,
+        })
+    }
+}
+
+/// Road network distances (in meters) and durations (in seconds) from the matrix service of a
+/// [Valhalla](https://valhalla.github.io/valhalla) server
+#[derive(Debug, Clone)]
+pub struct ValhallaProvider {
+    /// Base URL of the server, only plain `http://` is supported
+    pub base_url: String,
+    /// Costing model, usually `auto`
+    pub costing: String,
+    /// Directory where responses are cached, the same points are only fetched once
+    pub cache_dir: Option<PathBuf>,
+    pub timeout: Duration,
+}
+
+impl ValhallaProvider {
+    pub fn new(base_url: &str) -> ValhallaProvider {
+        ValhallaProvider {
+            base_url: String::from(base_url.trim_end_matches('/')),
+            costing: String::from("auto"),
+            cache_dir: None,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+impl DistanceProvider for ValhallaProvider {
+    fn matrix(&self, points: &[(f64, f64)]) -> Result<TravelMatrix, ProviderError> {
+        let locations: Vec<Value> = points
+            .iter()
+            .map(|&(lon, lat)| json!({ "lat": lat, "lon": lon }))
+            .collect();
+
+        let body = json!({
+            "sources": locations,
+            "targets": locations,
+            "costing": self.costing,
+        })
+        .to_string();
+
+        let url = format!("{}/sources_to_targets", self.base_url);
+
+        let response = cached(&self.cache_dir, &url, &body, || {
+            http_request("POST", &url, &body, self.timeout)
+        })?;
+
+        if let Some(error) = response.get("error").and_then(Value::as_str) {
+            return Err(ProviderError::Request(String::from(error)));
+        }
+
+        let rows = response
+            .get("sources_to_targets")
+            .and_then(Value::as_array)
+            .ok_or_else(|| ProviderError::InvalidResponse(String::from("Missing matrix")))?;
+
+        Ok(TravelMatrix {
+            size: points.len(),
+            // Valhalla reports kilometers
+            distances: flatten(rows, points.len(), |v| {
+                v.get("distance")?.as_f64().map(|km| km * 1000.0)
+            })?,
+            durations: flatten(rows, points.len(), |v| v.get("time")?.as_f64())?,
+        })
+    }
+}
+
+/// Flatten a square matrix of JSON rows, cells without a value are unreachable
+fn flatten(
+    rows: &[Value],
+    size: usize,
+    value: impl Fn(&Value) -> Option<f64>,
+) -> Result<Vec<f32>, ProviderError> {
+    let invalid = || ProviderError::InvalidResponse(format!("Expected a {0}x{0} matrix", size));
+
+    if rows.len() != size {
+        return Err(invalid());
+    }
+
+    let mut matrix = Vec::with_capacity(size * size);
+    for row in rows {
+        let row = row
+            .as_array()
+            .filter(|row| row.len() == size)
+            .ok_or_else(invalid)?;
+        matrix.extend(
+            row.iter()
+                .map(|cell| value(cell).map_or(f32::INFINITY, |v| v as f32)),
+        );
+    }
+    Ok(matrix)
+}
+
+/// Return the cached response of a request, or fetch and cache it
+fn cached(
+    cache_dir: &Option<PathBuf>,
+    url: &str,
+    body: &str,
+    fetch: impl FnOnce() -> Result<String, ProviderError>,
+) -> Result<Value, ProviderError> {
+    let cache_path = cache_dir
+        .as_ref()
+        .map(|dir| dir.join(format!("{:016x}.json", fnv1a(&[url, body]))));
+
+    let content = match cache_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+    {
+        Some(content) => content,
+        None => {
+            let content = fetch()?;
+
+            if let Some(path) = &cache_path {
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(path, &content)?;
+            }
+            content
+        }
+    };
+
+    serde_json::from_str(&content).map_err(|e| ProviderError::InvalidResponse(e.to_string()))
+}
+
+/// A hash that stays the same across builds, used to name cache files
+fn fnv1a(parts: &[&str]) -> u64 {
+    parts
+        .iter()
+        .flat_map(|part| part.bytes().chain(std::iter::once(0)))
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Send a HTTP/1.0 request and return the response body, HTTP/1.0 keeps servers from using a
+/// chunked encoding
+fn http_request(
+    method: &str,
+    url: &str,
+    body: &str,
+    timeout: Duration,
+) -> Result<String, ProviderError> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| {
+        ProviderError::Request(format!("Only http:// URLs are supported: {}", url))
+    })?;
+
+    let (host, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let addr = if host.contains(':') {
+        String::from(host)
+    } else {
+        format!("{}:80", host)
+    };
+
+    let mut stream = TcpStream::connect(addr)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    write!(
+        stream,
+        "{} {} HTTP/1.0\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        host,
+        body.len(),
+        body
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, content) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| ProviderError::InvalidResponse(String::from("Missing HTTP headers")))?;
+
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or_else(|| ProviderError::InvalidResponse(String::from("Missing HTTP status")))?;
+
+    if status != 200 {
+        return Err(ProviderError::Request(format!(
+            "HTTP {}: {}",
+            status, content
+        )));
+    }
+
+    Ok(String::from(content))
+}
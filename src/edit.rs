@@ -0,0 +1,103 @@
+use crate::distance::TravelMatrix;
+use crate::location::Location;
+use crate::vrp::Vrp;
+use std::fmt;
+
+/// Error raised when an edit of a [Vrp] would make it inconsistent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// A location with this id already exists
+    DuplicateId(u16),
+    /// No customer has this id
+    UnknownCustomer(u16),
+    /// The instance reads its costs from a [travel matrix](Vrp::with_travel_matrix), which has
+    /// no row for the new customer with this id
+    NotInTravelMatrix(u16),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::DuplicateId(id) => write!(f, "A location with id {} already exists", id),
+            EditError::UnknownCustomer(id) => write!(f, "Unknown customer {}", id),
+            EditError::NotInTravelMatrix(id) => write!(
+                f,
+                "Customer {} cannot be added, the travel matrix of the instance has no row for it",
+                id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+/// Edit customers while keeping ids unique. Every edit makes the solutions built before it
+/// [stale](crate::vrp_result::VrpResult::is_stale)
+impl Vrp {
+    /// Add a customer, it gets the next location index (see [locations](Vrp::locations)).
+    ///
+    /// Instances with a [travel matrix](Vrp::with_travel_matrix) cannot tell the travel to the
+    /// new customer, add it before building the matrix
+    pub fn add_customer(&mut self, customer: Location) -> Result<(), EditError> {
+        if self.locations().any(|l| l.id == customer.id) {
+            return Err(EditError::DuplicateId(customer.id));
+        }
+        if self.warehouse.matrix_index.is_some() {
+            return Err(EditError::NotInTravelMatrix(customer.id));
+        }
+        self.customers.push(customer);
+        Ok(())
+    }
+
+    /// Remove a customer by id and return it, the indices of the following customers shift
+    /// down, in the [travel matrix](Vrp::with_travel_matrix) too
+    pub fn remove_customer(&mut self, id: u16) -> Result<Location, EditError> {
+        let index = self.customer_position(id)?;
+        let customer = self.customers.remove(index);
+        self.reindex_travel_matrix();
+        Ok(customer)
+    }
+
+    /// Replace the customer with the same id as `customer`, returns the previous version. The
+    /// customer keeps its row of the [travel matrix](Vrp::with_travel_matrix), moving it does
+    /// not change its travel times
+    pub fn update_customer(&mut self, mut customer: Location) -> Result<Location, EditError> {
+        let index = self.customer_position(customer.id)?;
+        customer.matrix_index = self.customers[index].matrix_index.clone();
+        Ok(std::mem::replace(&mut self.customers[index], customer))
+    }
+
+    /// Rebuild the travel matrix from the rows of the remaining locations, so it stays indexed
+    /// like [points](Vrp::points)
+    fn reindex_travel_matrix(&mut self) {
+        let Some(matrix) = self.warehouse.matrix_index.as_ref().map(|i| &i.matrix) else {
+            return;
+        };
+        let rows: Vec<usize> = self
+            .locations()
+            .chain(&self.other_depots)
+            .filter_map(|l| l.matrix_index.as_ref().map(|i| i.index))
+            .collect();
+        let cells = |value: fn(&TravelMatrix, usize, usize) -> f32| {
+            rows.iter()
+                .flat_map(|&from| rows.iter().map(move |&to| value(matrix, from, to)))
+                .collect()
+        };
+
+        let reindexed = TravelMatrix {
+            size: rows.len(),
+            distances: cells(TravelMatrix::distance),
+            durations: cells(TravelMatrix::duration),
+        };
+        *self = self
+            .with_travel_matrix(reindexed)
+            .expect("The matrix has a row for every location");
+    }
+
+    fn customer_position(&self, id: u16) -> Result<usize, EditError> {
+        self.customers
+            .iter()
+            .position(|c| c.id == id)
+            .ok_or(EditError::UnknownCustomer(id))
+    }
+}
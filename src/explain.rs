@@ -0,0 +1,145 @@
+use crate::vrp_result::VrpResult;
+
+/// Why a customer cannot be moved to a route
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Blocker {
+    /// The route does not have enough capacity left for the customer
+    Capacity { remaining: f64 },
+    /// Every position breaks a time window of the route
+    TimeWindow,
+}
+
+/// A position of a customer in a route
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Insertion {
+    pub position: usize,
+    /// How much serving the customer there adds to the route cost. Often 0 when the route waits
+    /// for a later time window anyway
+    pub cost: f32,
+    /// How much serving the customer there adds to the route distance
+    pub distance: f32,
+}
+
+/// The effect of moving a customer to another route
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alternative {
+    pub route: usize,
+    /// The cheapest position, or what prevents the move
+    pub outcome: Result<Insertion, Blocker>,
+}
+
+/// Why a customer is served where it is, see [explain](VrpResult::explain)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub customer: u16,
+    pub route: usize,
+    pub current: Insertion,
+    /// Every other route, feasible moves first by increasing cost
+    pub alternatives: Vec<Alternative>,
+}
+
+impl VrpResult {
+    /// Explain the placement of a customer: its cost in its route against the cheapest feasible
+    /// position of every other route, or the constraint ruling each route out. Returns `None` if
+    /// no route serves the customer
+    pub fn explain(&self, customer_id: u16) -> Option<Explanation> {
+        let (route, position) = self.routes.iter().enumerate().find_map(|(r, route)| {
+            let p = route.customers.iter().position(|c| c.id == customer_id)?;
+            Some((r, p))
+        })?;
+
+        let customer = &self.routes[route].customers[position];
+
+        let current_route = &self.routes[route];
+        let mut without = current_route.clone();
+        without.customers.remove(position);
+        let current = Insertion {
+            position,
+            cost: self.route_cost(route) - without.total_cost(),
+            distance: current_route.total_distance() - without.total_distance(),
+        };
+
+        let mut alternatives: Vec<Alternative> = self
+            .routes
+            .iter()
+            .enumerate()
+            .filter(|&(r, _)| r != route)
+            .map(|(r, other)| {
+                let remaining = (self.vehicle_capacity - other.total_demand()).max(0.0);
+
+                let outcome = if customer.demand > remaining {
+                    Err(Blocker::Capacity { remaining })
+                } else {
+                    other
+                        .cheapest_insertion_from(0, customer, self.vehicle_capacity)
+                        .map(|(p, new_cost)| {
+                            let mut with = other.clone();
+                            with.customers.insert(p, customer.clone());
+                            Insertion {
+                                position: p,
+                                cost: new_cost - self.route_cost(r),
+                                distance: with.total_distance() - other.total_distance(),
+                            }
+                        })
+                        .ok_or(Blocker::TimeWindow)
+                };
+
+                Alternative { route: r, outcome }
+            })
+            .collect();
+
+        alternatives.sort_by(|a, b| match (&a.outcome, &b.outcome) {
+            (Ok(a), Ok(b)) => (a.cost, a.distance)
+                .partial_cmp(&(b.cost, b.distance))
+                .unwrap_or(std::cmp::Ordering::Equal),
+            (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+            (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+            (Err(_), Err(_)) => std::cmp::Ordering::Equal,
+        });
+
+        Some(Explanation {
+            customer: customer_id,
+            route,
+            current,
+            alternatives,
+        })
+    }
+}
+
+impl Explanation {
+    /// Print this explanation to a Markdown string, routes and positions are counted from 1
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format! {"# Customer {}\n\n", self.customer});
+        output.push_str(&format! {
+            "- Served by route {} at position {}, {}\n",
+            self.route + 1,
+            self.current.position + 1,
+            describe(&self.current)
+        });
+
+        output.push_str("\n## Alternatives\n\n");
+        for alternative in &self.alternatives {
+            let outcome = match alternative.outcome {
+                Ok(insertion) => format!(
+                    "position {} would be best, {}",
+                    insertion.position + 1,
+                    describe(&insertion)
+                ),
+                Err(Blocker::Capacity { remaining }) => {
+                    format!("only {} capacity left", remaining)
+                }
+                Err(Blocker::TimeWindow) => String::from("every position breaks a time window"),
+            };
+            output.push_str(&format! {"- Route {}: {}\n", alternative.route + 1, outcome});
+        }
+        output
+    }
+}
+
+fn describe(insertion: &Insertion) -> String {
+    format!(
+        "adding {:.1} to the route cost and {:.1} to its distance",
+        insertion.cost, insertion.distance
+    )
+}
@@ -0,0 +1,93 @@
+use crate::vrp::Vrp;
+
+impl Vrp {
+    /// The distance between every pair of locations, indexed like [locations](Vrp::locations).
+    /// These are the exact distances the heuristics use
+    pub fn distance_matrix(&self) -> Vec<Vec<f32>> {
+        self.locations()
+            .map(|a| self.locations().map(|b| a.distance_to(b)).collect())
+            .collect()
+    }
+
+    /// Print the [distance matrix](Vrp::distance_matrix) to a CSV string, the header and first
+    /// column hold location ids
+    pub fn distance_matrix_csv(&self) -> String {
+        let ids: Vec<String> = self.locations().map(|l| l.id.to_string()).collect();
+
+        let mut output = String::new();
+        output.push_str(&format!("id,{}\n", ids.join(",")));
+
+        for (id, row) in ids.iter().zip(self.distance_matrix()) {
+            let row: Vec<String> = row.iter().map(f32::to_string).collect();
+            output.push_str(&format!("{},{}\n", id, row.join(",")));
+        }
+        output
+    }
+
+    /// Encode the [distance matrix](Vrp::distance_matrix) as a NumPy `.npy` file of `float32`,
+    /// readable with `numpy.load`
+    pub fn distance_matrix_npy(&self) -> Vec<u8> {
+        let matrix = self.distance_matrix();
+        let n = matrix.len();
+
+        let mut output = npy_header("<f4", n, n);
+        for value in matrix.iter().flatten() {
+            output.extend_from_slice(&value.to_le_bytes());
+        }
+        output
+    }
+
+    /// Print the `k` [nearest neighbors](Vrp::neighbor_lists) of every location to a CSV
+    /// string, one `location,rank,neighbor,distance` line per neighbor. Columns hold indices
+    /// like [locations](Vrp::locations)
+    pub fn neighbors_csv(&self, k: usize) -> String {
+        let mut output = String::new();
+        output.push_str("location,rank,neighbor,distance\n");
+
+        for (a, neighbors) in self.neighbor_lists(k).iter().enumerate() {
+            for (rank, &b) in neighbors.iter().enumerate() {
+                let distance = self
+                    .location(a)
+                    .unwrap()
+                    .distance_to(self.location(b).unwrap());
+                output.push_str(&format!("{},{},{},{}\n", a, rank + 1, b, distance));
+            }
+        }
+        output
+    }
+
+    /// Encode the `k` [nearest neighbors](Vrp::neighbor_lists) of every location as a NumPy
+    /// `.npy` file of `int64` with shape `(n, k)`, lists shorter than `k` are padded with `-1`
+    pub fn neighbors_npy(&self, k: usize) -> Vec<u8> {
+        let lists = self.neighbor_lists(k);
+
+        let mut output = npy_header("<i8", lists.len(), k);
+        for neighbors in &lists {
+            for rank in 0..k {
+                let value = neighbors.get(rank).map_or(-1, |&b| b as i64);
+                output.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        output
+    }
+}
+
+/// The header of a version 1.0 `.npy` file holding a C ordered 2D array
+fn npy_header(descr: &str, rows: usize, columns: usize) -> Vec<u8> {
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': ({}, {}), }}",
+        descr, rows, columns
+    );
+
+    // The data must start on a multiple of 64 bytes, the header ends with a newline
+    let prefix_len = 10;
+    let padding = 63 - (prefix_len + header.len()) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    let mut output = Vec::with_capacity(prefix_len + header.len());
+    output.extend_from_slice(b"\x93NUMPY\x01\x00");
+    output.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    output.extend_from_slice(header.as_bytes());
+    output
+}
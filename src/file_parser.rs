@@ -1,70 +1,689 @@
-use crate::location::Location;
+use crate::distance::TravelMatrix;
+use crate::location::{Location, LocationKind, Pairing};
+use crate::objective::Objective;
 use crate::vrp::Vrp;
+use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
+use std::str::FromStr;
 
-pub fn parse_solomon_vrp_file(path: &String) -> Option<(Location, Vec<Location>, u16, u16)> {
-    // Specify the file path
-    let path = Path::new(path);
+/// Error raised when an instance file cannot be parsed, lines are numbered from 1
+#[derive(Debug)]
+pub enum ParseError {
+    Io(std::io::Error),
+    /// A header, or the values or table it introduces, is missing
+    MissingHeader(String),
+    /// A value cannot be parsed or is out of range
+    BadValue {
+        line: usize,
+        value: String,
+    },
+    /// A row does not have the number of columns of its table
+    WrongColumnCount {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The instance is well formed but inconsistent, like a matrix of the wrong size
+    Invalid(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "Failed to read instance: {}", e),
+            ParseError::MissingHeader(header) => write!(f, "Missing {}", header),
+            ParseError::BadValue { line, value } => {
+                write!(f, "Invalid value {:?} on line {}", value, line)
+            }
+            ParseError::WrongColumnCount {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected {} columns on line {}, found {}",
+                expected, line, found
+            ),
+            ParseError::Invalid(e) => write!(f, "Invalid instance: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
 
+/// Parse `value`, found on `line`. Infinite and NaN numbers are rejected, they would make
+/// every schedule and cost meaningless
+fn parse_value<T: FromStr>(value: &str, line: usize) -> Result<T, ParseError> {
+    let bad_value = || ParseError::BadValue {
+        line,
+        value: String::from(value),
+    };
+
+    if value.parse::<f64>().is_ok_and(|v| !v.is_finite()) {
+        return Err(bad_value());
+    }
+    value.parse().map_err(|_| bad_value())
+}
+
+/// Split `text`, found on `line`, into its columns, there must be `expected` of them
+fn columns(text: &str, line: usize, expected: usize) -> Result<Vec<&str>, ParseError> {
+    let columns: Vec<&str> = text.split_whitespace().collect();
+    if columns.len() != expected {
+        return Err(ParseError::WrongColumnCount {
+            line,
+            expected,
+            found: columns.len(),
+        });
+    }
+    Ok(columns)
+}
+
+pub fn parse_solomon_vrp_file(
+    path: impl AsRef<Path>,
+) -> Result<(Location, Vec<Location>, u16, f64), ParseError> {
     // Open the file in read-only mode
-    let file = File::open(path).ok()?;
+    let file = File::open(path).map_err(ParseError::Io)?;
 
     // Create a buffered reader to read the file line by line
-    let reader = BufReader::new(file);
+    parse_solomon_vrp_from_reader(BufReader::new(file))
+}
+
+/// Parse a solomon VRPTW problem definition from any buffered reader, this does not touch the
+/// filesystem. The 200 to 1000 customer Gehring & Homberger instances have the same format and
+/// are read the same way. The depots of the [multi-depot extension](parse_multi_depot_solomon_from_reader)
+/// are ignored
+pub fn parse_solomon_vrp_from_reader(
+    reader: impl BufRead,
+) -> Result<(Location, Vec<Location>, u16, f64), ParseError> {
+    let (mut depots, customers, n_vehicles, vehicle_capacity) =
+        parse_multi_depot_solomon_from_reader(reader)?;
+
+    Ok((
+        depots.swap_remove(0),
+        customers,
+        n_vehicles,
+        vehicle_capacity,
+    ))
+}
+
+/// Parse a solomon VRPTW problem definition with the multi-depot extension: the customer table
+/// may be followed by a `DEPOTS` line and a table of the other depots, with the same columns.
+/// Returns every depot, the warehouse of the customer table first, then the customers
+pub fn parse_multi_depot_solomon_from_reader(
+    reader: impl BufRead,
+) -> Result<(Vec<Location>, Vec<Location>, u16, f64), ParseError> {
+    let lines: Vec<String> = reader
+        .lines()
+        .collect::<Result<_, _>>()
+        .map_err(ParseError::Io)?;
+    let missing_vehicles = || ParseError::MissingHeader(String::from("VEHICLE NUMBER CAPACITY"));
 
-    // Get lines with data, drop invalid lines
-    let mut locations = reader.lines().skip(4).filter_map(|x| x.ok());
+    // The Gehring & Homberger instances do not always have the blank lines of the solomon ones,
+    // the restrictions are the first values after the NUMBER CAPACITY header
+    let header = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with("NUMBER"))
+        .ok_or_else(missing_vehicles)?;
+    let restrictions = header
+        + 1
+        + lines[header + 1..]
+            .iter()
+            .position(|l| !l.trim().is_empty())
+            .ok_or_else(missing_vehicles)?;
+    let values = columns(&lines[restrictions], restrictions + 1, 2)?;
 
-    let mut restrictions: Vec<u16> = locations
-        .next()?
-        .split_whitespace()
-        .filter_map(|s| s.parse::<u16>().ok())
+    let n_vehicles = parse_value::<u16>(values[0], restrictions + 1)?;
+    let vehicle_capacity = parse_value::<f64>(values[1], restrictions + 1)?;
+
+    // Lines with their number, from 1
+    let lines: Vec<(usize, &String)> = lines
+        .iter()
+        .enumerate()
+        .skip(restrictions + 1)
+        .map(|(i, line)| (i + 1, line))
         .collect();
+    let (locations, other_depots) = match lines.iter().position(|(_, l)| l.trim() == "DEPOTS") {
+        Some(i) => (&lines[..i], &lines[i + 1..]),
+        None => (&lines[..], &[][..]),
+    };
 
-    let vehicle_capacity = restrictions.pop()?;
-    let n_vehicles = restrictions.pop()?;
-
-    let locations = locations.skip(4);
-    let locations = locations
-        .filter(|l| !l.trim().is_empty()) // Remove empty
-        // lines
-        .map(|line| {
-            // Unwrap each line to handle any I/O errors
-            let values: Vec<u16> = line
-                .split_whitespace()
-                .map(|s| s.parse().expect("Error on a line entry"))
-                .collect();
-
-            Location {
-                id: values[0],
-                x: values[1],
-                y: values[2],
-                demand: values[3],
-                ready_time: values[4],
-                due_date: values[5],
-                service_time: values[6],
-            }
-        })
-        .collect::<Vec<Location>>();
+    // Tables have their own headers and blank or whitespace lines, skip the lines not starting
+    // with an id
+    let rows = |lines: &[(usize, &String)]| {
+        lines
+            .iter()
+            .filter(|(_, l)| {
+                l.split_whitespace()
+                    .next()
+                    .is_some_and(|id| id.parse::<u16>().is_ok())
+            })
+            .map(|&(number, line)| parse_location_line(line, number))
+            .collect::<Result<Vec<Location>, ParseError>>()
+    };
 
-    let (warehouse, locations) = locations
+    let (warehouse, customers) = rows(locations)?
         .split_first()
-        .map(|(warehouse, customers)| (warehouse.clone(), customers.to_vec()))?;
+        .map(|(warehouse, customers)| (warehouse.clone(), customers.to_vec()))
+        .ok_or_else(|| ParseError::MissingHeader(String::from("CUSTOMER table")))?;
+
+    let depots = std::iter::once(warehouse)
+        .chain(rows(other_depots)?)
+        .map(|depot| Location {
+            kind: LocationKind::Depot,
+            ..depot
+        })
+        .collect();
+
+    Ok((depots, customers, n_vehicles, vehicle_capacity))
+}
+
+/// Parse a row of the location table of a solomon file, found on `line`
+fn parse_location_line(text: &str, line: usize) -> Result<Location, ParseError> {
+    let values = columns(text, line, 7)?;
+    let value = |i: usize| parse_value::<f64>(values[i], line);
+
+    Ok(Location {
+        id: parse_value(values[0], line)?,
+        x: value(1)?,
+        y: value(2)?,
+        demand: value(3)?,
+        ready_time: value(4)?,
+        due_date: value(5)?,
+        service_time: value(6)?,
+        ..Default::default()
+    })
+}
+
+/// Parse a pickup and delivery problem (PDPTW) in the Li & Lim benchmark format: a first line
+/// with the number of vehicles, their capacity and speed, then one line per task with its id,
+/// coordinates, demand, time window, service time and the ids of its pickup and delivery
+/// partners, 0 if none. The first task is the depot, deliveries have a negative demand
+pub fn parse_li_lim_from_reader(
+    reader: impl BufRead,
+) -> Result<(Location, Vec<Location>, u16, f64), ParseError> {
+    let lines: Vec<(usize, String)> = reader
+        .lines()
+        .enumerate()
+        .map(|(i, line)| line.map(|line| (i + 1, line)))
+        .filter(|line| line.as_ref().map_or(true, |(_, l)| !l.trim().is_empty()))
+        .collect::<Result<_, _>>()
+        .map_err(ParseError::Io)?;
+
+    let (line, restrictions) = lines.first().ok_or_else(|| {
+        ParseError::MissingHeader(String::from("vehicle number, capacity and speed"))
+    })?;
+    let restrictions = columns(restrictions, *line, 3)?;
+    let n_vehicles = parse_value::<u16>(restrictions[0], *line)?;
+    let vehicle_capacity = parse_value::<f64>(restrictions[1], *line)?;
+
+    let mut locations = lines[1..]
+        .iter()
+        .map(|(line, text)| {
+            let values = columns(text, *line, 9)?;
+            let id = |i: usize| parse_value::<u16>(values[i], *line);
+            let value = |i: usize| parse_value::<f64>(values[i], *line);
+
+            Ok(Location {
+                id: id(0)?,
+                x: value(1)?,
+                y: value(2)?,
+                demand: value(3)?.abs(),
+                ready_time: value(4)?,
+                due_date: value(5)?,
+                service_time: value(6)?,
+                pairing: match (id(7)?, id(8)?) {
+                    (0, 0) => None,
+                    (0, delivery) => Some(Pairing::Pickup { delivery }),
+                    (pickup, _) => Some(Pairing::Delivery { pickup }),
+                },
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<Location>, ParseError>>()?
+        .into_iter();
+
+    // Marked as the depot by Vrp::new
+    let warehouse = locations
+        .next()
+        .ok_or_else(|| ParseError::MissingHeader(String::from("depot")))?;
+
+    Ok((warehouse, locations.collect(), n_vehicles, vehicle_capacity))
+}
+
+/// Parse a CVRP or VRPTW problem in the TSPLIB format of CVRPLIB: `KEY : value` specification
+/// lines followed by the `NODE_COORD_SECTION`, `DEMAND_SECTION` and `DEPOT_SECTION`, with an
+/// optional `TIME_WINDOW_SECTION` (ready time and due date of every node) and
+/// `SERVICE_TIME_SECTION`, or a `SERVICE_TIME` for all customers.
+///
+/// The `EDGE_WEIGHT_TYPE` is `EUC_2D` (rounded to the nearest integer), `CEIL_2D` or
+/// `EXPLICIT` with an `EDGE_WEIGHT_SECTION` in the `FULL_MATRIX`, `UPPER_ROW`, `LOWER_ROW`,
+/// `UPPER_DIAG_ROW` or `LOWER_DIAG_ROW` format, given to the instance as its
+/// [travel matrix](Vrp::with_travel_matrix). Nodes are numbered from 0 instead of 1, the first
+/// depot is the warehouse and the others are [other depots](Vrp::other_depots). The vehicles
+/// are read from `VEHICLES`, else from the `-k<n>` suffix of the `NAME`, else there is one per
+/// customer. A `DISTANCE` bounds the duration of routes, and nodes without a time window can be
+/// served at any time
+pub fn parse_tsplib_from_reader(reader: impl BufRead) -> Result<Vrp, ParseError> {
+    // Values and lines with their number, from 1
+    let mut spec: HashMap<String, (usize, String)> = HashMap::new();
+    let mut sections: HashMap<String, Vec<(usize, String)>> = HashMap::new();
+    let mut section: Option<String> = None;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(ParseError::Io)?;
+        let (number, line) = (i + 1, line.trim());
+        if line.is_empty() {
+            continue;
+        }
+        if line == "EOF" {
+            break;
+        }
+
+        let keyword = line
+            .split(|c: char| c == ':' || c.is_whitespace())
+            .next()
+            .unwrap_or_default();
+        if keyword.ends_with("_SECTION") {
+            sections.entry(String::from(keyword)).or_default();
+            section = Some(String::from(keyword));
+        } else if let Some((key, value)) = line.split_once(':') {
+            spec.insert(
+                String::from(key.trim()),
+                (number, String::from(value.trim())),
+            );
+            section = None;
+        } else {
+            // Data lines only belong in sections
+            sections
+                .get_mut(section.as_deref().unwrap_or_default())
+                .ok_or_else(|| ParseError::BadValue {
+                    line: number,
+                    value: String::from(line),
+                })?
+                .push((number, String::from(line)));
+        }
+    }
+
+    let key = |name: &str| {
+        spec.get(name)
+            .map(|(line, value)| (*line, value.as_str()))
+            .ok_or_else(|| ParseError::MissingHeader(String::from(name)))
+    };
+    let (line, dimension) = key("DIMENSION")?;
+    let dimension: usize = parse_value(dimension, line)?;
+    // Node ids must fit the ids of locations
+    if dimension == 0 || dimension > usize::from(u16::MAX) {
+        return Err(ParseError::BadValue {
+            line,
+            value: dimension.to_string(),
+        });
+    }
+    let (line, capacity) = key("CAPACITY")?;
+    let vehicle_capacity: f64 = parse_value(capacity, line)?;
+
+    // A node id on `line`, as an index from 0
+    let node = |value: &str, line: usize| -> Result<usize, ParseError> {
+        parse_value::<usize>(value, line)?
+            .checked_sub(1)
+            .filter(|&node| node < dimension)
+            .ok_or_else(|| ParseError::BadValue {
+                line,
+                value: String::from(value),
+            })
+    };
+
+    // Values of a section of `columns` columns keyed by node, as indices from 0
+    let rows = |name: &str, columns: usize| -> Result<HashMap<usize, Vec<f64>>, ParseError> {
+        sections
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|(line, text)| {
+                let values = self::columns(text, *line, columns)?;
+                let values = values[1..]
+                    .iter()
+                    .map(|value| parse_value(value, *line))
+                    .collect::<Result<Vec<f64>, ParseError>>()?;
+                Ok((
+                    node(text.split_whitespace().next().unwrap_or_default(), *line)?,
+                    values,
+                ))
+            })
+            .collect()
+    };
 
-    Some((warehouse, locations, n_vehicles, vehicle_capacity))
+    let coordinates = match sections.contains_key("NODE_COORD_SECTION") {
+        true => rows("NODE_COORD_SECTION", 3)?,
+        false => rows("DISPLAY_DATA_SECTION", 3)?,
+    };
+    let demands = rows("DEMAND_SECTION", 2)?;
+    let time_windows = rows("TIME_WINDOW_SECTION", 3)?;
+    let service_times = rows("SERVICE_TIME_SECTION", 2)?;
+    let value = |rows: &HashMap<usize, Vec<f64>>, node: usize, i: usize| {
+        rows.get(&node).and_then(|values| values.get(i)).copied()
+    };
+
+    let mut depots: Vec<usize> = Vec::new();
+    let values = sections
+        .get("DEPOT_SECTION")
+        .into_iter()
+        .flatten()
+        .flat_map(|(line, text)| text.split_whitespace().map(move |value| (*line, value)));
+    for (line, value) in values {
+        if value == "-1" {
+            break;
+        }
+        let depot = node(value, line)?;
+        if depots.contains(&depot) {
+            return Err(ParseError::BadValue {
+                line,
+                value: String::from(value),
+            });
+        }
+        depots.push(depot);
+    }
+    if depots.is_empty() {
+        depots.push(0);
+    }
+
+    let weights = match key("EDGE_WEIGHT_TYPE")? {
+        (_, "EXPLICIT") => {
+            let values = sections
+                .get("EDGE_WEIGHT_SECTION")
+                .ok_or_else(|| ParseError::MissingHeader(String::from("EDGE_WEIGHT_SECTION")))?
+                .iter()
+                .flat_map(|(line, text)| text.split_whitespace().map(move |value| (*line, value)))
+                .map(|(line, value)| parse_value(value, line))
+                .collect::<Result<Vec<f64>, ParseError>>()?;
+            explicit_weights(key("EDGE_WEIGHT_FORMAT")?, &values, dimension)?
+        }
+        (_, kind @ ("EUC_2D" | "CEIL_2D")) => {
+            let point = |node: usize| {
+                value(&coordinates, node, 0)
+                    .zip(value(&coordinates, node, 1))
+                    .ok_or_else(|| {
+                        ParseError::Invalid(format!("Node {} has no coordinates", node + 1))
+                    })
+            };
+            let points = (0..dimension)
+                .map(point)
+                .collect::<Result<Vec<(f64, f64)>, ParseError>>()?;
+            let round = if kind == "EUC_2D" {
+                f64::round
+            } else {
+                f64::ceil
+            };
+            points
+                .iter()
+                .map(|a| {
+                    points
+                        .iter()
+                        .map(|b| round((a.0 - b.0).hypot(a.1 - b.1)) as f32)
+                        .collect()
+                })
+                .collect()
+        }
+        (line, kind) => {
+            return Err(ParseError::BadValue {
+                line,
+                value: String::from(kind),
+            })
+        }
+    };
+
+    let service_time = match spec.get("SERVICE_TIME") {
+        Some((line, value)) => Some(parse_value::<f64>(value, *line)?),
+        None => None,
+    };
+    let service_time = |node: usize| {
+        value(&service_times, node, 0)
+            .or(service_time.filter(|_| !depots.contains(&node)))
+            .unwrap_or(0.0)
+    };
+
+    // Every route of at most all customers ends by then, so it never binds
+    let horizon = weights
+        .iter()
+        .flatten()
+        .fold(0f64, |max, &w| max.max(f64::from(w)))
+        * dimension as f64
+        + (0..dimension).map(service_time).sum::<f64>();
+
+    // Ids fit as the dimension was checked
+    let location = |node: usize| Location {
+        id: node as u16,
+        x: value(&coordinates, node, 0).unwrap_or_default(),
+        y: value(&coordinates, node, 1).unwrap_or_default(),
+        demand: value(&demands, node, 0).unwrap_or_default(),
+        ready_time: value(&time_windows, node, 0).unwrap_or(0.0),
+        due_date: value(&time_windows, node, 1).unwrap_or(horizon),
+        service_time: service_time(node),
+        ..Default::default()
+    };
+
+    // Matrix rows follow the locations of the instance, then its other depots
+    let order: Vec<usize> = depots[..1]
+        .iter()
+        .copied()
+        .chain((0..dimension).filter(|node| !depots.contains(node)))
+        .chain(depots[1..].iter().copied())
+        .collect();
+    let customers: Vec<Location> = order[1..dimension - depots.len() + 1]
+        .iter()
+        .map(|&node| location(node))
+        .collect();
+    let other_depots: Vec<Location> = depots[1..].iter().map(|&node| location(node)).collect();
+
+    let n_vehicles = match spec.get("VEHICLES") {
+        Some((line, vehicles)) => parse_value(vehicles, *line)?,
+        None => spec
+            .get("NAME")
+            .and_then(|(_, name)| name.rsplit_once("-k"))
+            .and_then(|(_, k)| k.parse().ok())
+            .unwrap_or(customers.len() as u16),
+    };
+
+    let invalid_matrix =
+        || ParseError::Invalid(String::from("The edge weights do not match the nodes"));
+    let matrix = TravelMatrix::explicit(
+        order
+            .iter()
+            .map(|&from| order.iter().map(|&to| weights[from][to]).collect())
+            .collect(),
+        None,
+    )
+    .ok_or_else(invalid_matrix)?;
+
+    let mut vrp = Vrp::new(location(depots[0]), customers, n_vehicles, vehicle_capacity)
+        .with_other_depots(other_depots)
+        .with_travel_matrix(matrix)
+        .ok_or_else(invalid_matrix)?;
+    vrp.max_route_duration = match spec.get("DISTANCE") {
+        Some((line, distance)) => Some(parse_value(distance, *line)?),
+        None => None,
+    };
+
+    Ok(vrp)
+}
+
+/// The rows of an explicit TSPLIB matrix of `n` nodes from the values of its
+/// `EDGE_WEIGHT_SECTION` in `format`, found on `line`
+fn explicit_weights(
+    (line, format): (usize, &str),
+    values: &[f64],
+    n: usize,
+) -> Result<Vec<Vec<f32>>, ParseError> {
+    let cells: Vec<(usize, usize)> = match format {
+        "FULL_MATRIX" => (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).collect(),
+        "UPPER_ROW" => (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect(),
+        "LOWER_ROW" => (0..n).flat_map(|i| (0..i).map(move |j| (i, j))).collect(),
+        "UPPER_DIAG_ROW" => (0..n).flat_map(|i| (i..n).map(move |j| (i, j))).collect(),
+        "LOWER_DIAG_ROW" => (0..n).flat_map(|i| (0..=i).map(move |j| (i, j))).collect(),
+        _ => {
+            return Err(ParseError::BadValue {
+                line,
+                value: String::from(format),
+            })
+        }
+    };
+    if cells.len() != values.len() {
+        return Err(ParseError::Invalid(format!(
+            "Expected {} edge weights, found {}",
+            cells.len(),
+            values.len()
+        )));
+    }
+
+    let mut weights = vec![vec![0f32; n]; n];
+    for (&(i, j), &value) in cells.iter().zip(values) {
+        weights[i][j] = value as f32;
+        // Only full matrices may be asymmetric
+        if format != "FULL_MATRIX" {
+            weights[j][i] = value as f32;
+        }
+    }
+    Ok(weights)
 }
 
 impl Vrp {
-    pub fn from_file(path: &String) -> Option<Vrp> {
-        let (warehouse, customers, n_vehicles, vehicle_capacity) = parse_solomon_vrp_file(path)?;
+    /// Read a CVRP or VRPTW problem in the [TSPLIB format](parse_tsplib_from_reader)
+    pub fn from_tsplib_file(path: impl AsRef<Path>) -> Result<Vrp, ParseError> {
+        let file = File::open(path).map_err(ParseError::Io)?;
+        parse_tsplib_from_reader(BufReader::new(file))
+    }
+
+    /// Read a pickup and delivery problem in the [Li & Lim format](parse_li_lim_from_reader)
+    pub fn from_li_lim_file(path: impl AsRef<Path>) -> Result<Vrp, ParseError> {
+        let file = File::open(path).map_err(ParseError::Io)?;
+        let (warehouse, customers, n_vehicles, vehicle_capacity) =
+            parse_li_lim_from_reader(BufReader::new(file))?;
+
+        Ok(Vrp::new(warehouse, customers, n_vehicles, vehicle_capacity))
+    }
 
-        Some(Vrp {
+    /// Print this VRP problem in the solomon VRPTW .txt format
+    pub fn as_solomon_string(&self, name: &str) -> String {
+        let mut output = String::new();
+        output.push_str(&format!("{}\n\n", name));
+        output.push_str("VEHICLE\n");
+        output.push_str("NUMBER     CAPACITY\n");
+        output.push_str(&format!(
+            "{:>5} {:>12}\n\n",
+            self.n_vehicles, self.vehicle_capacity
+        ));
+        output.push_str("CUSTOMER\n");
+        output.push_str(
+            "CUST NO.  XCOORD.   YCOORD.    DEMAND   READY TIME  DUE DATE   SERVICE   TIME\n\n",
+        );
+
+        for l in self.locations() {
+            output.push_str(&location_line(l));
+        }
+
+        if self.is_multi_depot() {
+            output.push_str("\nDEPOTS\n");
+            output.push_str(
+                "DEPOT NO. XCOORD.   YCOORD.    DEMAND   READY TIME  DUE DATE   SERVICE   TIME\n\n",
+            );
+            for depot in &self.other_depots {
+                output.push_str(&location_line(depot));
+            }
+        }
+        output
+    }
+
+    /// Read a solomon file, with the depots of the
+    /// [multi-depot extension](parse_multi_depot_solomon_from_reader) if it has some
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Vrp, ParseError> {
+        let file = File::open(path).map_err(ParseError::Io)?;
+        Vrp::from_solomon_reader(BufReader::new(file))
+    }
+
+    /// Read a solomon instance from any buffered reader, like [from_file](Vrp::from_file)
+    /// without touching the filesystem
+    pub fn from_solomon_reader(reader: impl BufRead) -> Result<Vrp, ParseError> {
+        let (mut depots, customers, n_vehicles, vehicle_capacity) =
+            parse_multi_depot_solomon_from_reader(reader)?;
+        let warehouse = depots.remove(0);
+
+        Ok(Vrp {
             warehouse,
             customers,
             n_vehicles,
             vehicle_capacity,
+            vehicles: Vec::new(),
+            other_depots: depots,
+            open: false,
+            max_route_duration: None,
+            driver_breaks: Vec::new(),
+            objective: Objective::default(),
         })
     }
+
+    /// Read a solomon instance held in memory, like one embedded in a test or downloaded
+    pub fn from_solomon_str(text: &str) -> Result<Vrp, ParseError> {
+        Vrp::from_solomon_reader(text.as_bytes())
+    }
+}
+
+/// Print a row of the location table of a solomon file
+fn location_line(l: &Location) -> String {
+    format!(
+        "{:>5} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}\n",
+        l.id, l.x, l.y, l.demand, l.ready_time, l.due_date, l.service_time
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrp::Vrp;
+
+    /// The first and last rows of a 200 customer Gehring & Homberger instance in the layout of
+    /// C1_2_1: no blank line after the vehicle header, a whitespace line before the customer
+    /// table and ids and times past the range of the solomon instances
+    const C1_2_1_EXCERPT: &str = "C1_2_1
+
+VEHICLE
+NUMBER     CAPACITY
+  50          200
+
+CUSTOMER
+CUST NO.  XCOORD.   YCOORD.    DEMAND   READY TIME  DUE DATE   SERVICE TIME
+ \t
+    0      70         70          0          0       1351          0
+    1      33         78         20        750        809         90
+    2      59         52         20        261        320         90
+    3      10        137         30       1133       1192         90
+  198      31         20         20        488        547         90
+  199      28         11         10        617        676         90
+  200     139         54         30        958       1017         90
+";
+
+    #[test]
+    fn reads_gehring_homberger_instance() {
+        let vrp = Vrp::from_solomon_str(C1_2_1_EXCERPT).unwrap();
+
+        assert_eq!(vrp.n_vehicles, 50);
+        assert_eq!(vrp.vehicle_capacity, 200.0);
+        assert_eq!(
+            (vrp.warehouse.id, vrp.warehouse.x, vrp.warehouse.y),
+            (0, 70.0, 70.0)
+        );
+        assert_eq!(vrp.warehouse.due_date, 1351.0);
+
+        let ids: Vec<u16> = vrp.customers.iter().map(|c| c.id).collect();
+        assert_eq!(ids, [1, 2, 3, 198, 199, 200]);
+
+        let last = vrp.customers.last().unwrap();
+        assert_eq!((last.x, last.y, last.demand), (139.0, 54.0, 30.0));
+        assert_eq!(
+            (last.ready_time, last.due_date, last.service_time),
+            (958.0, 1017.0, 90.0)
+        );
+    }
 }
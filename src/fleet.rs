@@ -0,0 +1,271 @@
+use crate::heuristics::ejection::EjectionParams;
+use crate::location::{DropPenalties, Location, Priority};
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Cost of an extra vehicle for [FleetPolicy::Penalize] when none is configured, about the
+/// length of a solomon scheduling horizon
+pub const DEFAULT_VEHICLE_PENALTY: f32 = 1000.0;
+
+/// What to do with solutions using more routes than the instance has vehicles
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FleetPolicy {
+    /// Return solutions as they are
+    Ignore,
+    /// Fail if the best solution uses too many vehicles
+    Error,
+    /// Add `cost_per_vehicle` for every extra vehicle when comparing solutions, the returned
+    /// solution may still use too many vehicles
+    Penalize { cost_per_vehicle: f32 },
+    /// Merge routes with [repair_fleet](VrpResult::repair_fleet), fail if too many remain
+    #[default]
+    Repair,
+}
+
+impl FromStr for FleetPolicy {
+    type Err = String;
+
+    /// Parse `ignore`, `error`, `penalize` or `repair`, `penalize` uses the
+    /// [default penalty](DEFAULT_VEHICLE_PENALTY)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ignore" => Ok(FleetPolicy::Ignore),
+            "error" => Ok(FleetPolicy::Error),
+            "penalize" => Ok(FleetPolicy::Penalize {
+                cost_per_vehicle: DEFAULT_VEHICLE_PENALTY,
+            }),
+            "repair" => Ok(FleetPolicy::Repair),
+            other => Err(format!("Unknown fleet policy: {}", other)),
+        }
+    }
+}
+
+/// Error raised when a solution uses more vehicles than available
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FleetError {
+    pub n_routes: usize,
+    pub n_vehicles: u16,
+}
+
+impl fmt::Display for FleetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The solution needs {} vehicles, only {} are available",
+            self.n_routes, self.n_vehicles
+        )
+    }
+}
+
+impl std::error::Error for FleetError {}
+
+impl FleetPolicy {
+    /// Prepare a heuristic result before it is compared with others, this repairs it with
+    /// [Repair](FleetPolicy::Repair)
+    pub fn apply(&self, mut result: VrpResult) -> VrpResult {
+        if *self == FleetPolicy::Repair {
+            result.repair_fleet();
+        }
+        result
+    }
+
+    /// Whether `a` is a better solution than `b` under this policy and the
+    /// [objective](VrpResult::objective) of `a`. With [Error](FleetPolicy::Error) and
+    /// [Repair](FleetPolicy::Repair), solutions respecting the fleet always beat the others
+    pub fn is_better(&self, a: &VrpResult, b: &VrpResult) -> bool {
+        self.is_better_by(a, b, |_| 0.0)
+    }
+
+    /// Same as [is_better](FleetPolicy::is_better), adding `penalty` to the last value the
+    /// objective compares, e.g. a [drop penalty](VrpResult::drop_penalty)
+    pub fn is_better_by(
+        &self,
+        a: &VrpResult,
+        b: &VrpResult,
+        penalty: impl Fn(&VrpResult) -> f32,
+    ) -> bool {
+        let extra = |result: &VrpResult| match self {
+            FleetPolicy::Penalize { cost_per_vehicle } => {
+                penalty(result) + result.excess_routes() as f32 * cost_per_vehicle
+            }
+            _ => penalty(result),
+        };
+        let key = |result: &VrpResult| {
+            let (first, last) = a.objective.key(result);
+            (first, last + extra(result))
+        };
+
+        match self {
+            FleetPolicy::Error | FleetPolicy::Repair
+                if (a.excess_routes() > 0) != (b.excess_routes() > 0) =>
+            {
+                b.excess_routes() > 0
+            }
+            _ => {
+                let (key_a, key_b) = (key(a), key(b));
+                key_a
+                    .0
+                    .total_cmp(&key_b.0)
+                    .then(key_a.1.total_cmp(&key_b.1))
+                    == Ordering::Less
+            }
+        }
+    }
+
+    /// Check the solution returned to the caller
+    pub fn check(&self, result: &VrpResult) -> Result<(), FleetError> {
+        match self {
+            FleetPolicy::Error | FleetPolicy::Repair if result.excess_routes() > 0 => {
+                Err(FleetError {
+                    n_routes: result.routes.len(),
+                    n_vehicles: result.n_vehicles,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl VrpResult {
+    /// Get the number of routes exceeding the fleet
+    pub fn excess_routes(&self) -> usize {
+        self.routes.len().saturating_sub(self.n_vehicles as usize)
+    }
+
+    /// Get the total cost plus `cost_per_vehicle` for every route exceeding the fleet
+    pub fn penalized_cost(&self, cost_per_vehicle: f32) -> f32 {
+        self.total_cost() + self.excess_routes() as f32 * cost_per_vehicle
+    }
+
+    /// Remove routes until the fleet is respected, by inserting their customers at their
+    /// cheapest feasible position in the other routes, higher [priorities](Priority) and tighter
+    /// due dates first. Routes with the fewest customers are removed first, a route is kept if
+    /// one of its customers fits nowhere else. If every route is kept, routes are removed with
+    /// an [ejection chain](VrpResult::eliminate_route), again fewest customers first.
+    ///
+    /// If no route can be removed this way, customers that are not
+    /// [MustServe](Priority::MustServe) may be left [unassigned](VrpResult::unassigned): they are
+    /// dropped when they fit nowhere, or to make room for a customer of a higher tier. The route
+    /// dropping the fewest `High`, then `Low`, customers is removed.
+    ///
+    /// Returns whether the solution respects the fleet
+    pub fn repair_fleet(&mut self) -> bool {
+        while self.excess_routes() > 0 {
+            let mut order: Vec<usize> = (0..self.routes.len()).collect();
+            order.sort_by_key(|&i| self.routes[i].customers.len());
+
+            let repaired = order
+                .iter()
+                .find_map(|&i| self.without_route(i, false))
+                .or_else(|| {
+                    order.iter().find_map(|&i| {
+                        let mut result = self.clone();
+                        result
+                            .eliminate_route(i, &EjectionParams::default())
+                            .then_some((result, (0, 0)))
+                    })
+                })
+                .or_else(|| {
+                    order
+                        .iter()
+                        .filter_map(|&i| self.without_route(i, true))
+                        .min_by_key(|(_, dropped)| *dropped)
+                });
+
+            match repaired {
+                Some((repaired, _)) => *self = repaired,
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// A copy of this solution without the route at `index`, its customers inserted in the
+    /// other routes, if they all fit. With `drop`, customers of lower tiers may be left
+    /// unassigned, see [repair_fleet](VrpResult::repair_fleet). Also returns how many `High` and
+    /// `Low` customers were left unassigned
+    fn without_route(&self, index: usize, drop: bool) -> Option<(VrpResult, (usize, usize))> {
+        let mut result = self.clone();
+        let mut customers: Vec<Location> = result.remove_route(index).customers;
+        customers
+            .sort_by(|a, b| (a.priority.cmp(&b.priority)).then(a.due_date.total_cmp(&b.due_date)));
+
+        let mut dropped = (0, 0);
+        let mut count = |priority: Priority| match priority {
+            Priority::High => dropped.0 += 1,
+            _ => dropped.1 += 1,
+        };
+
+        for customer in customers {
+            // The fleet is full, the customers only go to the other routes
+            if let Some((index, position)) = result.best_insertion_in_routes(&customer) {
+                result.insert_in_route(customer, index, position);
+            } else if !drop {
+                return None;
+            } else if let Some(ejected) = result.insert_by_ejection(customer.clone()) {
+                count(ejected.priority);
+                result.unassigned.push(ejected.id);
+            } else if customer.priority != Priority::MustServe {
+                count(customer.priority);
+                result.unassigned.push(customer.id);
+            } else {
+                return None;
+            }
+        }
+
+        Some((result, dropped))
+    }
+
+    /// Insert `customer` in place of a customer of a lower tier, lowest tiers first, then at the
+    /// cheapest feasible position over all routes. Returns the removed customer
+    fn insert_by_ejection(&mut self, customer: Location) -> Option<Location> {
+        // The route index, the position of the ejected customer and the route after the swap
+        let mut best: Option<(usize, usize, Route)> = None;
+        let mut best_key = (std::cmp::Reverse(customer.priority), f32::INFINITY);
+
+        for (index, route) in self.routes.iter().enumerate() {
+            for (i, ejected) in route.customers.iter().enumerate() {
+                if ejected.priority <= customer.priority {
+                    continue;
+                }
+
+                let mut candidate = route.clone();
+                candidate.customers.remove(i);
+
+                let Some((position, cost)) =
+                    candidate.cheapest_insertion_from(0, &customer, self.vehicle_capacity)
+                else {
+                    continue;
+                };
+
+                let key = (
+                    std::cmp::Reverse(ejected.priority),
+                    cost - self.route_cost(index),
+                );
+                if key < best_key {
+                    candidate.customers.insert(position, customer.clone());
+                    best = Some((index, i, candidate));
+                    best_key = key;
+                }
+            }
+        }
+
+        let (index, i, route) = best?;
+        Some(self.replace_route(index, route).customers.remove(i))
+    }
+
+    /// Get the total [drop penalty](DropPenalties) of the unassigned customers, looked up by id
+    /// in `vrp`. Unassigned [MustServe](Priority::MustServe) customers add nothing, heuristics only
+    /// leave them out when no vehicle can serve them
+    pub fn drop_penalty(&self, vrp: &Vrp, penalties: &DropPenalties) -> f32 {
+        self.unassigned
+            .iter()
+            .filter_map(|id| vrp.customers.iter().find(|c| c.id == *id))
+            .filter_map(|c| penalties.of(c.priority))
+            .sum()
+    }
+}
@@ -0,0 +1,102 @@
+use crate::location::{Location, LocationKind};
+use crate::vrp::Vrp;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Parameters for random instance generation
+#[derive(Debug, Clone)]
+pub struct GeneratorParams {
+    /// The number of customers to generate
+    pub n_customers: u16,
+    /// The number of available vehicles
+    pub n_vehicles: u16,
+    /// The capacity of each vehicle
+    pub vehicle_capacity: f64,
+    /// Customers are placed on a `grid_size` x `grid_size` square, the warehouse at its center
+    pub grid_size: u16,
+    /// The due date of the warehouse, all time windows lie within `0..horizon`
+    pub horizon: u16,
+    /// The width range of customer time windows
+    pub time_window_width: (u16, u16),
+    /// The demand range of customers
+    pub demand: (u16, u16),
+    /// The service time of every customer
+    pub service_time: u16,
+    /// Seed of the random number generator, the same seed always generates the same instance
+    pub seed: u64,
+}
+
+impl Default for GeneratorParams {
+    fn default() -> Self {
+        GeneratorParams {
+            n_customers: 100,
+            n_vehicles: 25,
+            vehicle_capacity: 200.0,
+            grid_size: 100,
+            horizon: 1000,
+            time_window_width: (30, 300),
+            demand: (1, 40),
+            service_time: 10,
+            seed: 0,
+        }
+    }
+}
+
+impl Vrp {
+    /// Generate a random instance in the style of the solomon R instances.
+    ///
+    /// Every customer's time window starts early enough to be served by a dedicated vehicle,
+    /// so the instance is always feasible given enough vehicles.
+    pub fn generate(params: &GeneratorParams) -> Vrp {
+        let mut rng = StdRng::seed_from_u64(params.seed);
+
+        let center = params.grid_size / 2;
+
+        let warehouse = Location {
+            id: 0,
+            x: f64::from(center),
+            y: f64::from(center),
+            demand: 0.0,
+            ready_time: 0.0,
+            due_date: f64::from(params.horizon),
+            service_time: 0.0,
+            kind: LocationKind::Depot,
+            ..Default::default()
+        };
+
+        let customers = (1..=params.n_customers)
+            .map(|id| {
+                let mut customer = Location {
+                    id,
+                    x: f64::from(rng.gen_range(0..=params.grid_size)),
+                    y: f64::from(rng.gen_range(0..=params.grid_size)),
+                    demand: f64::from(rng.gen_range(params.demand.0..=params.demand.1)),
+                    service_time: f64::from(params.service_time),
+                    ..Default::default()
+                };
+
+                // Leave enough time to serve the customer and go back to the warehouse
+                let travel = warehouse.distance_to(&customer).ceil() as u16;
+                let latest_start = params
+                    .horizon
+                    .saturating_sub(travel.saturating_add(params.service_time))
+                    .max(travel);
+
+                let width = rng.gen_range(params.time_window_width.0..=params.time_window_width.1);
+
+                let ready_time = rng.gen_range(travel..=latest_start);
+                customer.ready_time = f64::from(ready_time);
+                customer.due_date = f64::from(ready_time.saturating_add(width).min(latest_start));
+
+                customer
+            })
+            .collect();
+
+        Vrp::new(
+            warehouse,
+            customers,
+            params.n_vehicles,
+            params.vehicle_capacity,
+        )
+    }
+}
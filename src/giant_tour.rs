@@ -0,0 +1,165 @@
+use crate::compact::CompactSolution;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+
+/// A solution without its route delimiters: every customer of an instance once, as location
+/// indices (see [Vrp::locations]).
+///
+/// Heuristics working on permutations, like genetic algorithms or iterated local search, can
+/// mutate giant tours freely and get back the best routes serving the customers in this order
+/// with [split](GiantTour::split)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct GiantTour {
+    pub customers: Vec<u16>,
+}
+
+impl GiantTour {
+    /// Concatenate the routes of `result` in order, returns `None` if a route visits a location
+    /// that is not part of `vrp`
+    pub fn from_result(result: &VrpResult, vrp: &Vrp) -> Option<GiantTour> {
+        Some(CompactSolution::from_result(result, vrp)?.to_giant_tour())
+    }
+
+    /// Optimally cut this tour into routes with Prins' split algorithm: the routes keep the order
+    /// of the tour, are [valid](Route::is_valid) routes of `vrp`, use at most its vehicles and
+    /// have the lowest total cost among all such cuts. On multi-depot instances each route
+    /// starts from its cheapest [depot](Vrp::depots).
+    ///
+    /// Returns `None` if no cut is feasible, like when a customer cannot be served on its own or
+    /// the tour needs too many vehicles
+    pub fn split(&self, vrp: &Vrp) -> Option<VrpResult> {
+        self.split_compact(vrp, vrp.n_vehicles as usize)?
+            .to_result(vrp)
+    }
+
+    /// Cut the tour into at most `max_routes` routes, see [split](GiantTour::split)
+    fn split_compact(&self, vrp: &Vrp, max_routes: usize) -> Option<CompactSolution> {
+        let n = self.customers.len();
+        let max_routes = max_routes.min(n);
+
+        if n == 0 {
+            return Some(CompactSolution::default());
+        }
+
+        let customers = self
+            .customers
+            .iter()
+            .map(|&i| vrp.location(i as usize).filter(|_| i != 0))
+            .collect::<Option<Vec<_>>>()?;
+
+        // The cost of every feasible route serving customers[i..j] from each depot, as
+        // (j, cost, depot) for each i. Extending a route only delays its vehicle and adds to its
+        // peak load, so the first late customer or exceeded capacity ends the extension. Every
+        // route is then checked whole, with the return to its depot, max duration, breaks, pairs
+        // and backhauls of `vrp`
+        let templates: Vec<Route> = vrp.depots().map(|d| vrp.empty_route_from(d)).collect();
+        let arcs: Vec<Vec<(usize, f32, usize)>> = (0..n)
+            .map(|i| {
+                let mut arcs = Vec::new();
+
+                for (depot, template) in templates.iter().enumerate() {
+                    let mut time = 0f32;
+                    let mut previous = &template.warehouse;
+
+                    for (j, customer) in customers.iter().enumerate().skip(i) {
+                        let route = &customers[i..=j];
+                        if previous.cost_to(customer, time) > customer.due_date as f32
+                            || Route::max_load_with(route) > vrp.vehicle_capacity
+                        {
+                            break;
+                        }
+                        time = previous.cost_to_deliver(customer, time);
+                        previous = customer;
+
+                        if template.is_valid_with(route, vrp.vehicle_capacity) {
+                            arcs.push((j + 1, template.total_cost_with(route), depot));
+                        }
+                    }
+                }
+                arcs
+            })
+            .collect();
+
+        // best[k][j] is the cheapest cut of customers[..j] into k routes, with the start and the
+        // depot of the last route to rebuild it
+        let mut best = vec![vec![(f32::INFINITY, 0, 0); n + 1]; max_routes + 1];
+        best[0][0] = (0.0, 0, 0);
+
+        for k in 0..max_routes {
+            for i in 0..n {
+                let (cost, _, _) = best[k][i];
+                if cost == f32::INFINITY {
+                    continue;
+                }
+                for &(j, route_cost, depot) in &arcs[i] {
+                    if cost + route_cost < best[k + 1][j].0 {
+                        best[k + 1][j] = (cost + route_cost, i, depot);
+                    }
+                }
+            }
+        }
+
+        let (mut k, _) = best
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row[n].0 < f32::INFINITY)
+            .min_by(|a, b| a.1[n].0.total_cmp(&b.1[n].0))?;
+
+        let mut routes = Vec::with_capacity(k);
+        let mut depots = Vec::with_capacity(k);
+        let mut j = n;
+        while k > 0 {
+            let (_, i, depot) = best[k][j];
+            routes.push(self.customers[i..j].to_vec());
+            depots.push(depot as u16);
+            j = i;
+            k -= 1;
+        }
+        routes.reverse();
+        depots.reverse();
+
+        if !vrp.is_multi_depot() {
+            depots.clear();
+        }
+        Some(CompactSolution { routes, depots })
+    }
+}
+
+impl CompactSolution {
+    /// Concatenate the routes in order
+    pub fn to_giant_tour(&self) -> GiantTour {
+        GiantTour {
+            customers: self.routes.concat(),
+        }
+    }
+}
+
+impl VrpResult {
+    /// Re-cut the routes of this solution with [split](GiantTour::split), keeping the order in
+    /// which customers are visited. Routes are concatenated in their current order.
+    ///
+    /// A solution already using more routes than there are vehicles may keep as many, the
+    /// current routes are then always one of the possible cuts and the solution only changes
+    /// when the split is better under the [objective](VrpResult::objective). On multi-depot
+    /// instances the routes may move to another depot. The new routes have no vehicle. Returns
+    /// whether the solution improved
+    pub fn resplit(&mut self, vrp: &Vrp) -> bool {
+        let max_routes = self.routes.len().max(vrp.n_vehicles as usize);
+        let Some(split) = GiantTour::from_result(self, vrp)
+            .and_then(|tour| tour.split_compact(vrp, max_routes))
+            .and_then(|split| split.to_result(vrp))
+        else {
+            return false;
+        };
+
+        if !split.is_better_than(self) {
+            return false;
+        }
+
+        self.routes = split.routes;
+        self.vehicles.clear();
+        self.refresh_cost();
+        true
+    }
+}
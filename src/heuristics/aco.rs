@@ -1,13 +1,25 @@
-use crate::location::Location;
+use crate::compact::CompactSolution;
+use crate::heuristics::checkpoint::{Checkpoint, CheckpointWriter};
+use crate::heuristics::local_search::{ImprovementOperator, PostOptimizeOptions};
+use crate::heuristics::or_opt::DEFAULT_OR_OPT_SEGMENT_LEN;
+use crate::heuristics::progress::{no_progress, IterationInfo};
+use crate::heuristics::stats::{Deadline, SolveStats, Stopwatch};
+use crate::heuristics::trace::{self, IterationTrace, SearchTrace};
+use crate::heuristics::workspace::SearchWorkspace;
+use crate::kernels;
 use crate::route::Route;
 use crate::vrp::Vrp;
 use crate::vrp_result::VrpResult;
-use rand::distributions::{Distribution, WeightedIndex};
-use rand::thread_rng;
-use std::collections::HashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::borrow::Cow;
+use std::collections::hash_map::{Entry, HashMap};
+use std::ops::ControlFlow;
+use std::str::FromStr;
+use std::time::Duration;
 
 /// Parameters for the aco heuristic
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AcoParams {
     /// The number of ants in this aco
     pub n_ants: u16,
@@ -22,8 +34,175 @@ pub struct AcoParams {
     /// The evaporation factor for pheromone
     pub rho: f32,
     /// The initial pheromone value, a good value for this is 1 / total cost of nearest neighbor
-    /// for this dataset
+    /// for this dataset. 0 computes that value, the stats of the result then include the nearest
+    /// neighbor run
     pub pheromone_amt: f32,
+    /// The number of nearest neighbors an ant considers when choosing the next
+    /// [Location](crate::location::Location), see [neighbor_lists](Vrp::neighbor_lists). Ants fall
+    /// back to all unvisited customers when no candidate is deliverable, `None` always considers
+    /// all unvisited customers
+    pub candidate_list_size: Option<usize>,
+    /// Stop iterating once this much time has passed, even if `max_iter` has not been reached
+    pub time_limit: Option<Duration>,
+    /// Periodically write the best solution found so far to disk
+    pub checkpoint: Option<Checkpoint>,
+    /// Which ants deposit pheromone after every iteration
+    pub variant: AcoVariant,
+    /// Elitist strategy: the best solution found so far also deposits `elitist_weight` times the
+    /// pheromone of an ant of the [Ant System](AcoVariant::AntSystem) every iteration, usually
+    /// about the number of customers. 0 disables it
+    pub elitist_weight: f32,
+    /// Ant Colony System (Dorigo & Gambardella, 1997): the probability, between 0 and 1, that an
+    /// ant goes to the most desirable [Location](crate::location::Location) instead of drawing it.
+    /// 0 always draws
+    pub q0: f32,
+    /// Ant Colony System local pheromone update: every edge an ant uses while building its
+    /// solution is moved by this share towards `pheromone_amt`, so the next ants of the iteration
+    /// explore other edges. 0 disables it
+    pub xi: f32,
+    /// Which ants improve their solution with a local search before the pheromone update
+    pub local_search: AcoLocalSearch,
+    /// The local search of the ants, 2-opt then or-opt by default. Its time limit applies to
+    /// every solution improved
+    pub local_search_options: PostOptimizeOptions,
+    /// Seed of the random number generator, the same seed always gives the same solution unless
+    /// the time limit stops the run first. `None` draws a seed from the system
+    pub seed: Option<u64>,
+    /// Reset every pheromone to `pheromone_amt` after this many iterations without improving
+    /// the best solution, 0 never resets. The [MAX-MIN Ant System](AcoVariant::MaxMin) resets to
+    /// `tau_max`, the first of this and its `reinit_after` reached triggers the reset. Resets are
+    /// recorded in the [restarts](VrpResult::heuristic_restarts) of the result
+    pub stagnation_limit: u16,
+    /// Keep depositing the pheromone of the best solution found so far after a reset, else the
+    /// elitist, rank-based and MAX-MIN deposits start over from the solutions found after it.
+    /// The best solution is returned either way
+    pub restart_keep_best: bool,
+    /// Collect the statistics of every iteration into the [trace](VrpResult::trace) of the
+    /// result
+    pub trace: bool,
+}
+
+/// The ants of an iteration whose solution is improved by the
+/// [local search](AcoParams::local_search_options) of the aco heuristic, so pheromone is
+/// deposited on the edges of local optima (hybrid ACO)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AcoLocalSearch {
+    #[default]
+    None,
+    /// Only the best ant of every iteration, cheap
+    IterationBest,
+    /// Every ant, much stronger but slower. Ants building the same solution share one search
+    EveryAnt,
+}
+
+impl FromStr for AcoLocalSearch {
+    type Err = String;
+
+    /// Parse `none`, `best` or `all`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(AcoLocalSearch::None),
+            "best" => Ok(AcoLocalSearch::IterationBest),
+            "all" => Ok(AcoLocalSearch::EveryAnt),
+            _ => Err(format!("Unknown aco local search: {}", s)),
+        }
+    }
+}
+
+/// How the pheromones are updated after every iteration of the aco heuristic
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AcoVariant {
+    /// Ant System: every ant deposits `rho / cost` of its solution on the edges it used
+    #[default]
+    AntSystem,
+    /// MAX-MIN Ant System (Stützle & Hoos, 2000): a single ant deposits `1 / cost` of its
+    /// solution, the pheromones are kept between bounds and reset once the search stagnates.
+    /// They start at `pheromone_amt` and are first bounded after the first iteration
+    MaxMin(MmasParams),
+    /// Rank-based Ant System (Bullnheimer et al., 1999): the best solution found so far deposits
+    /// `ranked_ants * rho / cost`, the `ranked_ants - 1` best ants of the iteration deposit
+    /// `(ranked_ants - rank) * rho / cost`, rank 1 being the best ant. The other ants deposit
+    /// nothing
+    RankBased { ranked_ants: u16 },
+}
+
+/// Ants ranked by the [Rank-based Ant System](AcoVariant::RankBased) when none is configured
+pub const DEFAULT_RANKED_ANTS: u16 = 6;
+
+impl FromStr for AcoVariant {
+    type Err = String;
+
+    /// Parse `as`, `mmas` or `rank`, `mmas` uses the [default parameters](MmasParams::default)
+    /// and `rank` [DEFAULT_RANKED_ANTS]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "as" => Ok(AcoVariant::AntSystem),
+            "mmas" => Ok(AcoVariant::MaxMin(MmasParams::default())),
+            "rank" => Ok(AcoVariant::RankBased {
+                ranked_ants: DEFAULT_RANKED_ANTS,
+            }),
+            _ => Err(format!("Unknown aco variant: {}", s)),
+        }
+    }
+}
+
+/// The ant depositing pheromone in the [MAX-MIN Ant System](AcoVariant::MaxMin)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MmasDeposit {
+    /// The best ant of the iteration, more exploration
+    #[default]
+    IterationBest,
+    /// The best solution found so far, faster convergence
+    GlobalBest,
+}
+
+impl FromStr for MmasDeposit {
+    type Err = String;
+
+    /// Parse `iteration` or `global`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "iteration" => Ok(MmasDeposit::IterationBest),
+            "global" => Ok(MmasDeposit::GlobalBest),
+            _ => Err(format!("Unknown mmas deposit: {}", s)),
+        }
+    }
+}
+
+/// Parameters of the [MAX-MIN Ant System](AcoVariant::MaxMin)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MmasParams {
+    pub deposit: MmasDeposit,
+    /// Lower pheromone bound, defaults to `tau_max / (2 * number of customers)`
+    pub tau_min: Option<f32>,
+    /// Upper pheromone bound, defaults to `1 / (rho * cost of the best solution)`, the value the
+    /// pheromone of an edge of the best solution converges to
+    pub tau_max: Option<f32>,
+    /// Reset every pheromone to `tau_max` after this many iterations without improving the best
+    /// solution, 0 never resets
+    pub reinit_after: u16,
+}
+
+impl Default for MmasParams {
+    fn default() -> Self {
+        MmasParams {
+            deposit: MmasDeposit::default(),
+            tau_min: None,
+            tau_max: None,
+            reinit_after: 50,
+        }
+    }
+}
+
+impl MmasParams {
+    /// The pheromone bounds, `(tau_min, tau_max)`, given the cost of the best solution
+    fn bounds(&self, best_cost: f32, rho: f32, n_customers: usize) -> (f32, f32) {
+        let tau_max = self.tau_max.unwrap_or(1.0 / (rho * best_cost));
+        let tau_min = self
+            .tau_min
+            .unwrap_or(tau_max / (2 * n_customers.max(1)) as f32);
+        (tau_min.min(tau_max), tau_max)
+    }
 }
 
 impl Default for AcoParams {
@@ -34,93 +213,372 @@ impl Default for AcoParams {
             alpha: 1,
             beta: 1,
             rho: 0.1,
-            pheromone_amt: 1.0 / 8000.0,
+            pheromone_amt: 0.0,
+            candidate_list_size: None,
+            time_limit: None,
+            checkpoint: None,
+            variant: AcoVariant::default(),
+            elitist_weight: 0.0,
+            q0: 0.0,
+            xi: 0.0,
+            local_search: AcoLocalSearch::default(),
+            local_search_options: PostOptimizeOptions {
+                operators: vec![
+                    ImprovementOperator::TwoOpt,
+                    ImprovementOperator::OrOpt {
+                        max_segment_len: DEFAULT_OR_OPT_SEGMENT_LEN,
+                    },
+                ],
+                ..Default::default()
+            },
+            seed: None,
+            stagnation_limit: 0,
+            restart_keep_best: true,
+            trace: false,
+        }
+    }
+}
+
+/// Pheromones on the edges between locations, in a flat matrix indexed like
+/// [locations](Vrp::locations)
+struct PheromoneMatrix {
+    size: usize,
+    values: Vec<f32>,
+    /// Index of the location of every id, to read the edges of routes
+    index_by_id: Vec<usize>,
+}
+
+impl PheromoneMatrix {
+    fn new(vrp: &Vrp, value: f32) -> PheromoneMatrix {
+        let size = vrp.customers.len() + 1;
+        let max_id = vrp.locations().map(|l| l.id).max().unwrap_or(0);
+
+        let mut index_by_id = vec![0; usize::from(max_id) + 1];
+        for (index, location) in vrp.locations().enumerate() {
+            index_by_id[usize::from(location.id)] = index;
+        }
+
+        PheromoneMatrix {
+            size,
+            values: vec![value; size * size],
+            index_by_id,
+        }
+    }
+
+    fn get(&self, from: usize, to: usize) -> f32 {
+        self.values[from * self.size + to]
+    }
+
+    /// Move the pheromone of the edge from `from` to `to` by the share `xi` towards `value`
+    fn evaporate_towards(&mut self, from: usize, to: usize, xi: f32, value: f32) {
+        let pheromone = &mut self.values[from * self.size + to];
+        *pheromone = (1.0 - xi) * *pheromone + xi * value;
+    }
+
+    /// Set every pheromone to `value`
+    fn fill(&mut self, value: f32) {
+        self.values.fill(value);
+    }
+
+    /// Add `amount` to the pheromone of every edge used by `solution`
+    fn deposit(&mut self, solution: &VrpResult, amount: f32) {
+        for route in &solution.routes {
+            for i in 0..route.len() - 1 {
+                let from = self.index_by_id[usize::from(route[i].id)];
+                let to = self.index_by_id[usize::from(route[i + 1].id)];
+                self.values[from * self.size + to] += amount;
+            }
         }
     }
 }
 
+/// The state of an ant building a route
+struct Ant {
+    /// Index of the current location, see [locations](Vrp::locations)
+    index: usize,
+    cost: f32,
+    remaining_capacity: f64,
+    /// The route built so far
+    route: Route,
+}
+
 impl Vrp {
     /// Run the aco heuritic on a Vrp instance
     pub fn aco_heuristic(&self, params: &AcoParams) -> VrpResult {
-        let mut pheromones: HashMap<(Location, Location), f32> = HashMap::new();
+        self.aco_heuristic_with_workspace(params, &mut SearchWorkspace::new())
+    }
+
+    /// Run the aco heuristic on a Vrp instance, reusing the scratch buffers of `workspace`
+    pub fn aco_heuristic_with_workspace(
+        &self,
+        params: &AcoParams,
+        workspace: &mut SearchWorkspace,
+    ) -> VrpResult {
+        self.aco_heuristic_with_progress(params, workspace, &mut no_progress)
+    }
+
+    /// Same as [aco_heuristic_with_workspace](Vrp::aco_heuristic_with_workspace), calling
+    /// `progress` after every iteration of the colony, it may stop the run
+    pub fn aco_heuristic_with_progress(
+        &self,
+        params: &AcoParams,
+        workspace: &mut SearchWorkspace,
+        progress: &mut dyn FnMut(IterationInfo) -> ControlFlow<()>,
+    ) -> VrpResult {
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats::default();
+        // A seed makes the run reproducible, else draw one from the system
+        let mut rng = params
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+
+        let params = &if params.pheromone_amt > 0.0 {
+            Cow::Borrowed(params)
+        } else {
+            let nn = self.nearest_neighbour_heuristic();
+            stats += nn.stats.unwrap_or_default();
+            Cow::Owned(AcoParams {
+                pheromone_amt: 1.0 / nn.total_cost().max(1.0),
+                ..params.clone()
+            })
+        };
 
         // Initialise pheromones
-        self.set_pheromones(params, &mut pheromones);
+        let mut pheromones = PheromoneMatrix::new(self, params.pheromone_amt);
+
+        // Compute candidate lists once
+        let neighbors = params.candidate_list_size.map(|k| self.neighbor_lists(k));
+
+        // Customers no vehicle can serve, the ants skip them
+        let unassigned: Vec<u16> = self
+            .customers
+            .iter()
+            .filter(|c| !self.can_serve_alone(c))
+            .map(|c| c.id)
+            .collect();
 
         // Store best results
         let mut best_solution = VrpResult::from_vrp(self, Vec::default(), None);
         let mut best_cost = f32::INFINITY;
         let mut best_cost_history: Vec<f32> = Vec::default();
+        let mut restarts: Vec<usize> = Vec::new();
+        let mut search_trace = params.trace.then(SearchTrace::default);
+
+        // Best solution since the last reset of the pheromones, the one depositing pheromone
+        let mut elite = best_solution.clone();
+        let mut elite_cost = f32::INFINITY;
+
+        let deadline = Deadline::from_limit(params.time_limit);
+        let mut checkpoint = params.checkpoint.as_ref().map(CheckpointWriter::new);
+
+        // Iterations since the elite solution last improved, for the resets
+        let mut stagnation: u16 = 0;
+        let reinit_after = match params.variant {
+            AcoVariant::MaxMin(mmas) => mmas.reinit_after,
+            _ => 0,
+        };
+        let stagnation_limit = [params.stagnation_limit, reinit_after]
+            .into_iter()
+            .filter(|&limit| limit > 0)
+            .min();
 
         for _ in 0..params.max_iter {
-            let solutions: Vec<Vec<Route>> = (0..params.n_ants)
-                .map(|_| self.construct_routes(params, &pheromones))
+            if deadline.is_reached() {
+                break;
+            }
+
+            stats.iterations += 1;
+
+            let mut solutions: Vec<VrpResult> = (0..params.n_ants)
+                .map(|_| {
+                    let routes = self.construct_routes(
+                        params,
+                        &mut pheromones,
+                        neighbors.as_deref(),
+                        workspace,
+                        &mut stats,
+                        &mut rng,
+                    );
+                    VrpResult::from_vrp(self, routes, None)
+                })
                 .collect();
+            stats.solutions_evaluated += solutions.len() as u64;
+
+            // Ants often build the same solutions once the pheromones converge, every distinct
+            // one is searched once and its copies take the result, as (copy, searched) indices
+            let mut copies: Vec<(usize, usize)> = Vec::new();
+            let searched: Vec<usize> = match params.local_search {
+                AcoLocalSearch::None => Vec::new(),
+                AcoLocalSearch::IterationBest => (0..solutions.len())
+                    .min_by(|&a, &b| self.objective.compare(&solutions[a], &solutions[b]))
+                    .into_iter()
+                    .collect(),
+                AcoLocalSearch::EveryAnt => {
+                    let mut first: HashMap<CompactSolution, usize> = HashMap::new();
+                    (0..solutions.len())
+                        .filter(|&i| {
+                            let compact = solutions[i]
+                                .to_compact(self)
+                                .and_then(|compact| compact.normalized(self));
+                            match compact.map(|compact| first.entry(compact)) {
+                                Some(Entry::Occupied(searched)) => {
+                                    copies.push((i, *searched.get()));
+                                    false
+                                }
+                                Some(Entry::Vacant(entry)) => {
+                                    entry.insert(i);
+                                    true
+                                }
+                                None => true,
+                            }
+                        })
+                        .collect()
+                }
+            };
+            for i in searched {
+                let search = solutions[i].post_optimize(&params.local_search_options);
+                stats.solutions_evaluated += search.solutions_evaluated;
+                stats.feasibility_checks += search.feasibility_checks;
+            }
+            for (copy, searched) in copies {
+                solutions[copy] = solutions[searched].clone();
+            }
 
-            self.update_pheromones(&solutions, params, &mut pheromones);
+            // Solutions are ranked by the objective, an infinite cost means there is no solution
+            // to beat yet
+            let iteration_best = solutions.iter().min_by(|a, b| self.objective.compare(a, b));
+            let improved = iteration_best
+                .is_some_and(|best| elite_cost == f32::INFINITY || best.is_better_than(&elite));
+            if let Some(best) = iteration_best.filter(|_| improved) {
+                elite = best.clone();
+                elite_cost = best.total_cost();
+                if best_cost == f32::INFINITY || best.is_better_than(&best_solution) {
+                    best_solution = best.clone();
+                    best_cost = elite_cost;
+                }
+            }
+            best_cost_history.push(best_cost);
 
-            for solution in solutions {
-                let solution = VrpResult::from_vrp(self, solution, None);
-                let cost: f32 = solution.total_cost();
+            if let Some(search_trace) = &mut search_trace {
+                search_trace.push(IterationTrace {
+                    iteration: stats.iterations,
+                    best_cost,
+                    n_routes: best_solution.routes.len(),
+                    average_cost: trace::average_cost(&solutions),
+                    diversity: iteration_best.map(|best| trace::diversity(&solutions, best)),
+                    acceptance_rate: None,
+                });
+            }
 
-                if cost < best_cost {
-                    best_solution = solution;
-                    best_cost = cost;
+            self.update_pheromones(&solutions, &elite, params, &mut pheromones);
+
+            stagnation = if improved { 0 } else { stagnation + 1 };
+            if stagnation_limit.is_some_and(|limit| stagnation >= limit) {
+                let value = match params.variant {
+                    AcoVariant::MaxMin(mmas) => {
+                        mmas.bounds(elite_cost, params.rho, self.customers.len()).1
+                    }
+                    _ => params.pheromone_amt,
+                };
+                pheromones.fill(value);
+                if !params.restart_keep_best {
+                    elite = VrpResult::from_vrp(self, Vec::default(), None);
+                    elite_cost = f32::INFINITY;
                 }
+                restarts.push(best_cost_history.len());
+                stagnation = 0;
+            }
+
+            if let Some(checkpoint) = &mut checkpoint {
+                checkpoint.maybe_write(|| VrpResult {
+                    heuristic_cost_history: Some(best_cost_history.clone()),
+                    heuristic_restarts: restarts.clone(),
+                    unassigned: unassigned.clone(),
+                    ..best_solution.clone()
+                });
+            }
+
+            let info = IterationInfo {
+                iteration: stats.iterations,
+                best_cost,
+                elapsed: stopwatch.elapsed(),
+            };
+            if progress(info).is_break() {
+                break;
             }
-            best_cost_history.push(best_cost);
         }
 
-        VrpResult {
+        stats.wall_time = stopwatch.elapsed();
+        let result = VrpResult {
             heuristic_cost_history: Some(best_cost_history),
+            heuristic_restarts: restarts,
+            stats: Some(stats),
+            trace: search_trace,
+            unassigned,
             ..best_solution
-        }
-    }
-
-    /// Reset or set the pheromones
-    fn set_pheromones(
-        &self,
-        params: &AcoParams,
-        pheromones: &mut HashMap<(Location, Location), f32>,
-    ) {
-        let locations: Vec<&Location> = self
-            .customers
-            .iter()
-            .chain(std::iter::once(&self.warehouse))
-            .collect();
+        };
 
-        for &a in &locations {
-            for &b in &locations {
-                if *a != *b {
-                    pheromones.insert((a.clone(), b.clone()), params.pheromone_amt);
-                }
-            }
+        if let Some(checkpoint) = &mut checkpoint {
+            checkpoint.write(&result);
         }
+
+        result
     }
 
-    /// Update the pheromones to reward the best routes
+    /// Update the pheromones to reward the best routes, `best` is the best solution found so far
     fn update_pheromones(
         &self,
-        solutions: &Vec<Vec<Route>>,
+        solutions: &[VrpResult],
+        best: &VrpResult,
         params: &AcoParams,
-        pheromones: &mut HashMap<(Location, Location), f32>,
+        pheromones: &mut PheromoneMatrix,
     ) {
-        for value in pheromones.values_mut() {
+        for value in &mut pheromones.values {
             *value *= 1.0 - params.rho;
         }
 
-        // Remove pheromones on each edge where ants passed
-        for solution in solutions {
-            let solution = VrpResult::from_vrp(self, solution.clone(), None);
-            let deposit = params.rho / solution.total_cost();
+        if params.elitist_weight > 0.0 && !best.routes.is_empty() {
+            let amount = params.elitist_weight * params.rho / best.total_cost();
+            pheromones.deposit(best, amount);
+        }
 
-            for route in solution.routes {
-                for i in 0..route.len() - 1 {
-                    let pheromone = pheromones
-                        .get_mut(&(route[i].clone(), route[i + 1].clone()))
-                        .unwrap();
+        match params.variant {
+            AcoVariant::AntSystem => {
+                for solution in solutions {
+                    pheromones.deposit(solution, params.rho / solution.total_cost());
+                }
+            }
+            AcoVariant::RankBased { ranked_ants } => {
+                let w = f32::from(ranked_ants);
+                if !best.routes.is_empty() {
+                    pheromones.deposit(best, w * params.rho / best.total_cost());
+                }
 
-                    *pheromone += deposit;
+                let mut ranked: Vec<&VrpResult> = solutions.iter().collect();
+                ranked.sort_by(|a, b| a.total_cost().total_cmp(&b.total_cost()));
+                for (rank, solution) in (1..ranked_ants).zip(ranked) {
+                    let amount = (w - f32::from(rank)) * params.rho / solution.total_cost();
+                    pheromones.deposit(solution, amount);
+                }
+            }
+            AcoVariant::MaxMin(mmas) => {
+                let depositor = match mmas.deposit {
+                    MmasDeposit::IterationBest => solutions
+                        .iter()
+                        .min_by(|a, b| a.total_cost().total_cmp(&b.total_cost())),
+                    MmasDeposit::GlobalBest => Some(best),
+                };
+                if let Some(depositor) = depositor {
+                    pheromones.deposit(depositor, 1.0 / depositor.total_cost());
+                }
+
+                // An empty best solution has no cost to derive the bounds from
+                let (tau_min, tau_max) =
+                    mmas.bounds(best.total_cost(), params.rho, self.customers.len());
+                if tau_max.is_finite() {
+                    for value in &mut pheromones.values {
+                        *value = value.clamp(tau_min, tau_max);
+                    }
                 }
             }
         }
@@ -129,47 +587,83 @@ impl Vrp {
     fn construct_routes(
         &self,
         params: &AcoParams,
-        pheromones: &HashMap<(Location, Location), f32>,
+        pheromones: &mut PheromoneMatrix,
+        neighbors: Option<&[Vec<usize>]>,
+        workspace: &mut SearchWorkspace,
+        stats: &mut SolveStats,
+        rng: &mut impl Rng,
     ) -> Vec<Route> {
         let mut solution: Vec<Route> = Vec::with_capacity(1);
-        let mut unvisited: Vec<&Location> = self.customers.iter().collect();
+        // Location indices of the customers left
+        let mut unvisited: Vec<usize> = Vec::with_capacity(self.customers.len());
+        let mut visited: Vec<bool> = vec![false; self.customers.len() + 1];
+
+        // Customers no vehicle can serve would never be picked, they are left unassigned
+        for (i, customer) in self.customers.iter().enumerate() {
+            if self.can_serve_alone(customer) {
+                unvisited.push(i + 1);
+            } else {
+                visited[i + 1] = true;
+            }
+        }
+        let mut candidates: Vec<usize> = Vec::new();
 
         while !unvisited.is_empty() {
-            let mut total_demand = 0;
-            let mut current_cost: f32 = 0f32;
-
-            let mut current = &self.warehouse;
-
-            let mut new_route = Route {
-                warehouse: self.warehouse.clone(),
-                ..Default::default()
+            let mut ant = Ant {
+                index: 0,
+                cost: 0f32,
+                remaining_capacity: self.vehicle_capacity,
+                route: self.empty_route(),
             };
 
             loop {
-                let next_loc = select_next_location(
-                    current,
-                    unvisited.clone(),
-                    current_cost,
-                    self.vehicle_capacity - total_demand,
-                    params,
-                    pheromones,
-                );
-
-                let next_loc = if let Some(val) = next_loc {
-                    val
-                } else {
+                // Prefer the candidate list of the current location, if any
+                let mut next_index = None;
+
+                if let Some(lists) = neighbors {
+                    candidates.clear();
+                    candidates.extend(lists[ant.index].iter().filter(|&&i| !visited[i]));
+                    stats.feasibility_checks += candidates.len() as u64;
+
+                    next_index = self.select_next_location(
+                        &ant,
+                        &candidates,
+                        params,
+                        pheromones,
+                        workspace,
+                        rng,
+                    );
+                }
+
+                if next_index.is_none() {
+                    stats.feasibility_checks += unvisited.len() as u64;
+                    next_index = self
+                        .select_next_location(&ant, &unvisited, params, pheromones, workspace, rng);
+                }
+
+                let Some(next_index) = next_index else {
                     break;
                 };
-                new_route.customers.push(next_loc.clone());
+                if params.xi > 0.0 {
+                    pheromones.evaporate_towards(
+                        ant.index,
+                        next_index,
+                        params.xi,
+                        params.pheromone_amt,
+                    );
+                }
+                let current = self.location(ant.index).expect("Invalid location index");
+                let next_loc = &self.customers[next_index - 1];
+                ant.route.customers.push(next_loc.clone());
 
                 // Add to total cost
-                current_cost += current.cost_to_deliver(next_loc, current_cost);
+                ant.cost += current.cost_to_deliver(next_loc, ant.cost);
 
                 // Add demand to total route demand
-                total_demand += next_loc.demand;
+                ant.remaining_capacity -= next_loc.demand;
 
                 // Remove next_loc from unvisited
-                if let Some(index) = unvisited.iter().position(|&x| x == next_loc) {
+                if let Some(index) = unvisited.iter().position(|&x| x == next_index) {
                     // Remove the element at the found index
                     unvisited.remove(index);
                 } else {
@@ -177,56 +671,131 @@ impl Vrp {
                 }
 
                 // Set current to next customer
-                current = next_loc;
+                ant.index = next_index;
+                visited[next_index] = true;
             }
-            solution.push(new_route);
+            if params.xi > 0.0 {
+                pheromones.evaporate_towards(ant.index, 0, params.xi, params.pheromone_amt);
+            }
+            solution.push(ant.route);
         }
         solution
     }
-}
-
-fn select_next_location<'a>(
-    current: &Location,
-    unvisited: Vec<&'a Location>,
-    current_cost: f32,
-    remaining_capacity: u16,
-    params: &AcoParams,
-    pheromones: &HashMap<(Location, Location), f32>,
-) -> Option<&'a Location> {
-    // Create a random number generator
-    let mut rng = thread_rng();
-
-    let reachable_customers = current.find_deliverable(unvisited, current_cost, remaining_capacity);
-
-    if reachable_customers.is_empty() {
-        return None;
-    }
-
-    let probabilities: Vec<f32> = reachable_customers
-        .iter()
-        .map(|&next| {
-            let pheromone = pheromones
-                .get(&(current.clone(), next.clone()))
-                .copied()
-                .expect("Failed to get pheromone value");
-
-            let cost = current.cost_to_deliver(next, current_cost) - current_cost;
 
-            let desirability = 1f32 / cost;
+    /// Draw the next customer of `ant` among `candidates`, location indices, weighting the
+    /// feasible ones by pheromone and cost, or with probability `q0` take the one with the
+    /// highest weight. Returns its location index
+    fn select_next_location(
+        &self,
+        ant: &Ant,
+        candidates: &[usize],
+        params: &AcoParams,
+        pheromones: &PheromoneMatrix,
+        workspace: &mut SearchWorkspace,
+        rng: &mut impl Rng,
+    ) -> Option<usize> {
+        workspace.clear();
+
+        let current = self.location(ant.index).expect("Invalid location index");
+        let customer = |i: usize| &self.customers[candidates[i] - 1];
+
+        // Compute all distances in one batch, the kernel only knows straight lines so travel
+        // matrices are read one by one
+        if current.matrix_index.is_some() {
+            workspace
+                .distances
+                .extend((0..candidates.len()).map(|i| current.travel_time_to(customer(i))));
+        } else {
+            workspace
+                .xs
+                .extend((0..candidates.len()).map(|i| customer(i).x as f32));
+            workspace
+                .ys
+                .extend((0..candidates.len()).map(|i| customer(i).y as f32));
+            workspace.distances.resize(candidates.len(), 0f32);
+
+            kernels::distances_from(
+                current.x as f32,
+                current.y as f32,
+                &workspace.xs,
+                &workspace.ys,
+                &mut workspace.distances,
+            );
+        }
 
-            f32::powi(pheromone, params.alpha as i32) * f32::powi(desirability, params.beta as i32)
-                + 1e-6
-        })
-        .collect();
+        // Keep the customers whose delivery window is reachable and whose demand fits, same as
+        // find_deliverable, and after which the route can end within its max duration and
+        // breaks, no delivery following a backhaul
+        let distances = &workspace.distances;
+        workspace
+            .candidates
+            .extend((0..candidates.len()).filter(|&i| {
+                customer(i).due_date as f32 >= ant.cost + distances[i]
+                    && customer(i).demand <= ant.remaining_capacity
+                    && customer(i).may_follow(current)
+                    && (self.max_route_duration.is_none()
+                        || self.fits_max_duration(
+                            customer(i),
+                            current.cost_to_deliver(customer(i), ant.cost),
+                        ))
+                    && self.fits_breaks(&ant.route, customer(i))
+            }));
+
+        // Serve the highest priority tier first, there is none if no candidate is feasible
+        let priority = workspace
+            .candidates
+            .iter()
+            .map(|&i| customer(i).priority)
+            .min()?;
+        workspace
+            .candidates
+            .retain(|&i| customer(i).priority == priority);
+
+        workspace
+            .probabilities
+            .extend(workspace.candidates.iter().map(|&i| {
+                let next = customer(i);
+                let distance = distances[i];
+
+                let pheromone = pheromones.get(ant.index, candidates[i]);
+
+                // Same as cost_to_deliver, using the precomputed distance
+                let arrival = ant.cost + distance;
+                let cost = arrival
+                    + (next.ready_time as f32 - arrival).max(0f32)
+                    + next.service_time as f32
+                    - ant.cost;
+
+                let desirability = 1f32 / cost;
+
+                f32::powi(pheromone, params.alpha as i32)
+                    * f32::powi(desirability, params.beta as i32)
+                    + 1e-6
+            }));
+
+        // Pseudo-random proportional rule of the Ant Colony System
+        if params.q0 > 0.0 && rng.gen::<f32>() < params.q0 {
+            return workspace
+                .candidates
+                .iter()
+                .zip(&workspace.probabilities)
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(&i, _)| candidates[i]);
+        }
 
-    let total: f32 = probabilities.iter().sum();
+        let total: f32 = workspace.probabilities.iter().sum();
 
-    let normalized_probabilities: Vec<f32> = probabilities.iter().map(|&p| (p / total)).collect();
+        // Select a random candidate based on the weighted distribution
+        let mut threshold = rng.gen::<f32>() * total;
 
-    // Create a WeightedIndex using the probabilities
-    let dist =
-        WeightedIndex::new(&normalized_probabilities).expect("Failed to generate WeightedIndex");
+        for (&i, &p) in workspace.candidates.iter().zip(&workspace.probabilities) {
+            if threshold < p {
+                return Some(candidates[i]);
+            }
+            threshold -= p;
+        }
 
-    // Select a random element based on the weighted distribution
-    reachable_customers.get(dist.sample(&mut rng)).copied()
+        // Rounding errors may leave a tiny remainder, fall back to the last candidate
+        workspace.candidates.last().map(|&i| candidates[i])
+    }
 }
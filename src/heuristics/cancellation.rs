@@ -0,0 +1,39 @@
+use crate::heuristics::progress::IterationInfo;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A flag shared between threads to interrupt a solve, see
+/// [solve_cancellable](crate::heuristics::registry::Heuristic::solve_cancellable). Clones share
+/// the same flag, keep one to cancel and give the other to the solver
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Ask the solvers holding a clone of this token to stop, they return the best solution
+    /// found so far after their current iteration
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// A progress callback stopping the solver once this token is cancelled
+    pub fn progress(&self) -> impl FnMut(IterationInfo) -> ControlFlow<()> + '_ {
+        |_| {
+            if self.is_cancelled() {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::vrp_result::VrpResult;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Periodically save the best solution found so far during a long run, so a crash or
+/// cancellation does not lose the computation
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    /// The solution and its cost history are written to this file as JSON
+    pub path: PathBuf,
+    /// The minimum time between two writes
+    pub interval: Duration,
+    /// The first write that failed, shared by the clones of this checkpoint
+    error: Arc<Mutex<Option<io::Error>>>,
+}
+
+impl Checkpoint {
+    pub fn new(path: impl Into<PathBuf>, interval: Duration) -> Checkpoint {
+        Checkpoint {
+            path: path.into(),
+            interval,
+            error: Arc::default(),
+        }
+    }
+
+    /// Take the error of the first write that failed since the last call, `None` if every write
+    /// succeeded. A failed write does not stop the run, the next writes are still attempted
+    pub fn take_error(&self) -> Option<io::Error> {
+        self.error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
+
+/// Tracks when the last checkpoint was written during a run
+#[derive(Debug)]
+pub(crate) struct CheckpointWriter<'a> {
+    checkpoint: &'a Checkpoint,
+    last_write: Instant,
+}
+
+impl<'a> CheckpointWriter<'a> {
+    pub(crate) fn new(checkpoint: &'a Checkpoint) -> CheckpointWriter<'a> {
+        CheckpointWriter {
+            checkpoint,
+            last_write: Instant::now(),
+        }
+    }
+
+    /// Write the result built by `result` if the interval has elapsed since the last write
+    pub(crate) fn maybe_write(&mut self, result: impl FnOnce() -> VrpResult) {
+        if self.last_write.elapsed() >= self.checkpoint.interval {
+            self.write(&result());
+        }
+    }
+
+    /// Write `result` now, keeping the error for [take_error](Checkpoint::take_error) if it
+    /// fails
+    pub(crate) fn write(&mut self, result: &VrpResult) {
+        // Write next to the target first so an interrupted write never leaves a truncated file
+        let tmp_path = self.checkpoint.path.with_extension("tmp");
+
+        let written = fs::write(&tmp_path, result.as_json_string())
+            .and_then(|_| fs::rename(&tmp_path, &self.checkpoint.path));
+
+        if let Err(e) = written {
+            self.checkpoint
+                .error
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_insert(e);
+        }
+
+        self.last_write = Instant::now();
+    }
+}
@@ -0,0 +1,394 @@
+use crate::heuristics::local_search::{Move, Operator, MIN_IMPROVEMENT};
+use crate::location::{Location, LocationKind};
+use crate::route::Route;
+use crate::vrp_result::VrpResult;
+
+/// Longest segment exchanged when no length is configured
+pub const DEFAULT_MAX_SEGMENT_LEN: usize = 3;
+
+/// A CROSS-exchange: the segments of customers of two routes, as (route index, first position,
+/// length), trade places. One segment may be empty, the move then relocates the other one. See
+/// [best_cross_exchange](VrpResult::best_cross_exchange)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrossExchange {
+    pub first: (usize, usize, usize),
+    pub second: (usize, usize, usize),
+    /// Change of the total cost, negative for an improvement
+    pub delta: f32,
+}
+
+/// Schedule data of a route answering in constant time how a route continues after a changed
+/// part, so a move is checked in the length of the segments instead of the length of the route
+struct RouteTimes<'a> {
+    /// The warehouse, the customers and the warehouse again
+    nodes: Vec<&'a Location>,
+    /// Departure time from each node, leaving the warehouse at 0
+    departures: Vec<f32>,
+    /// Demand of the first `k` customers
+    loads: Vec<f64>,
+    /// Latest arrival at each node keeping the rest of the route feasible
+    latest: Vec<f32>,
+    /// The return time of the rest of the route is `max(arrival + duration, earliest)` for an
+    /// arrival at a node, waiting included
+    durations: Vec<f32>,
+    earliest: Vec<f32>,
+    /// The route serves backhauls or pickup and delivery pairs: its peak load is not its total
+    /// demand, and the order of its customers is constrained
+    mixed: bool,
+    /// The route ends at its last customer
+    open: bool,
+}
+
+/// Travel time from `from` to `to`, the return to the warehouse at the `end` of open routes
+/// takes no time
+fn times_travel(open: bool, end: bool, from: &Location, to: &Location) -> f32 {
+    if open && end {
+        0.0
+    } else {
+        from.travel_time_to(to)
+    }
+}
+
+impl<'a> RouteTimes<'a> {
+    fn new(route: &'a Route) -> RouteTimes<'a> {
+        let nodes: Vec<&Location> = std::iter::once(&route.warehouse)
+            .chain(&route.customers)
+            .chain(std::iter::once(&route.warehouse))
+            .collect();
+        let n = nodes.len();
+
+        let mut departures = vec![0.0; n];
+        for k in 1..n - 1 {
+            departures[k] = nodes[k - 1].cost_to_deliver(nodes[k], departures[k - 1]);
+        }
+
+        let mut loads = vec![0.0; n - 1];
+        for k in 1..n - 1 {
+            loads[k] = loads[k - 1] + nodes[k].demand;
+        }
+
+        // Open routes end leaving their last customer, the warehouse bounds nothing
+        let warehouse = nodes[n - 1];
+        let (mut latest, mut durations, mut earliest) = if route.open {
+            (vec![f32::INFINITY; n], vec![0.0; n], vec![0.0; n])
+        } else {
+            (
+                vec![warehouse.due_date as f32; n],
+                vec![warehouse.service_time as f32; n],
+                vec![(warehouse.ready_time + warehouse.service_time) as f32; n],
+            )
+        };
+        for k in (1..n - 1).rev() {
+            let (node, next) = (nodes[k], nodes[k + 1]);
+            let travel =
+                node.service_time as f32 + times_travel(route.open, k + 1 == n - 1, node, next);
+
+            latest[k] = (node.due_date as f32).min(latest[k + 1] - travel);
+            durations[k] = travel + durations[k + 1];
+            earliest[k] = (node.ready_time as f32 + durations[k]).max(earliest[k + 1]);
+        }
+
+        let mixed = route
+            .customers
+            .iter()
+            .any(|c| c.kind == LocationKind::Backhaul || c.pairing.is_some());
+
+        RouteTimes {
+            nodes,
+            open: route.open,
+            departures,
+            loads,
+            latest,
+            durations,
+            earliest,
+            mixed,
+        }
+    }
+
+    /// Number of customers
+    fn len(&self) -> usize {
+        self.nodes.len() - 2
+    }
+
+    /// Demand of the `len` customers from position `start`
+    fn segment_load(&self, start: usize, len: usize) -> f64 {
+        self.loads[start + len] - self.loads[start]
+    }
+
+    /// The customers from position `start`
+    fn segment(&self, start: usize, len: usize) -> &[&'a Location] {
+        &self.nodes[start + 1..start + 1 + len]
+    }
+
+    /// The cost of this route with the `removed` customers from position `start` replaced by
+    /// `inserted`, `None` if this breaks a time window. Capacity is not checked
+    fn cost_with(&self, start: usize, removed: usize, inserted: &[&Location]) -> Option<f32> {
+        if start == 0 && removed == self.len() && inserted.is_empty() {
+            return Some(0.0);
+        }
+
+        let mut previous = self.nodes[start];
+        let mut departure = self.departures[start];
+        for &customer in inserted {
+            let arrival = previous.cost_to(customer, departure);
+            if arrival > customer.due_date as f32 {
+                return None;
+            }
+            departure = previous.cost_to_deliver(customer, departure);
+            previous = customer;
+        }
+
+        let next = start + removed + 1;
+        let to_end = next == self.nodes.len() - 1;
+        let arrival = departure + times_travel(self.open, to_end, previous, self.nodes[next]);
+        if arrival > self.latest[next] {
+            return None;
+        }
+        Some((arrival + self.durations[next]).max(self.earliest[next]))
+    }
+}
+
+impl VrpResult {
+    /// Find the feasible CROSS-exchange of segments of at most `max_segment_len` customers
+    /// lowering the total cost the most, `None` if no exchange lowers it.
+    ///
+    /// The time windows of a move are checked in the length of its segments, from schedule
+    /// data computed once per route: the latest arrival keeping the rest of a route feasible
+    /// and its return time as a function of the arrival
+    pub fn best_cross_exchange(&self, max_segment_len: usize) -> Option<CrossExchange> {
+        let times: Vec<RouteTimes> = self.routes.iter().map(RouteTimes::new).collect();
+
+        (0..self.routes.len())
+            .flat_map(|a| (a + 1..self.routes.len()).map(move |b| (a, b)))
+            .filter_map(|(a, b)| self.best_cross_exchange_between(&times, a, b, max_segment_len))
+            .min_by(|x, y| x.delta.total_cmp(&y.delta))
+    }
+
+    /// Same as [best_cross_exchange](VrpResult::best_cross_exchange), only exchanging the
+    /// segments of the routes at `a` and `b`
+    fn best_cross_exchange_between(
+        &self,
+        times: &[RouteTimes],
+        a: usize,
+        b: usize,
+        max_segment_len: usize,
+    ) -> Option<CrossExchange> {
+        let (first, second) = (&times[a], &times[b]);
+        let old_cost = self.route_cost(a) + self.route_cost(b);
+        let capacity = self.vehicle_capacity;
+        let (load_a, load_b) = (first.loads[first.len()], second.loads[second.len()]);
+        let mixed = first.mixed || second.mixed;
+
+        let mut best: Option<CrossExchange> = None;
+        for i in 0..=first.len() {
+            for len_a in 0..=max_segment_len.min(first.len() - i) {
+                let segment_a = first.segment(i, len_a);
+                let demand_a = first.segment_load(i, len_a);
+
+                for j in 0..=second.len() {
+                    for len_b in 0..=max_segment_len.min(second.len() - j) {
+                        if len_a == 0 && len_b == 0 {
+                            continue;
+                        }
+
+                        // The total demand bounds the peak load, it is only the load of routes
+                        // without backhauls nor pairs
+                        let demand_b = second.segment_load(j, len_b);
+                        if !mixed
+                            && (load_a - demand_a + demand_b > capacity
+                                || load_b - demand_b + demand_a > capacity)
+                        {
+                            continue;
+                        }
+
+                        let segment_b = second.segment(j, len_b);
+                        let Some(cost_a) = first.cost_with(i, len_a, segment_b) else {
+                            continue;
+                        };
+                        let Some(cost_b) = second.cost_with(j, len_b, segment_a) else {
+                            continue;
+                        };
+
+                        let mut exchange = CrossExchange {
+                            first: (a, i, len_a),
+                            second: (b, j, len_b),
+                            delta: cost_a + cost_b - old_cost,
+                        };
+                        let threshold = best.map_or(-MIN_IMPROVEMENT, |best| best.delta);
+                        if exchange.delta >= threshold {
+                            continue;
+                        }
+                        // The schedule data does not account for breaks, the max duration, the
+                        // peak load nor the order of backhauls and pairs, check the exchanged
+                        // routes in full
+                        if mixed
+                            || !self.routes[a].breaks.is_empty()
+                            || self.routes[a].max_duration.is_some()
+                        {
+                            let (route_a, route_b) = self.exchanged_routes(&exchange);
+                            if !route_a.is_valid(capacity) || !route_b.is_valid(capacity) {
+                                continue;
+                            }
+                            exchange.delta = route_a.total_cost() + route_b.total_cost() - old_cost;
+                            if exchange.delta >= threshold {
+                                continue;
+                            }
+                        }
+                        best = Some(exchange);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Exchange the segments of `exchange`, as returned by
+    /// [best_cross_exchange](VrpResult::best_cross_exchange). Routes left empty are removed
+    pub fn apply_cross_exchange(&mut self, exchange: &CrossExchange) {
+        let (a, b) = (exchange.first.0, exchange.second.0);
+        let (first, second) = self.exchanged_routes(exchange);
+
+        self.replace_route(a, first);
+        self.replace_route(b, second);
+
+        // Remove from the back so the other index stays valid
+        for index in [b, a] {
+            if self.routes[index].is_empty() {
+                self.remove_route(index);
+            }
+        }
+    }
+
+    /// The two routes of `exchange` after exchanging their segments
+    fn exchanged_routes(&self, exchange: &CrossExchange) -> (Route, Route) {
+        let ((a, i, len_a), (b, j, len_b)) = (exchange.first, exchange.second);
+        let mut first = self.routes[a].clone();
+        let mut second = self.routes[b].clone();
+
+        let segment_a: Vec<Location> = first.customers.drain(i..i + len_a).collect();
+        let segment_b: Vec<Location> = second.customers.drain(j..j + len_b).collect();
+        first.customers.splice(i..i, segment_b);
+        second.customers.splice(j..j, segment_a);
+        (first, second)
+    }
+
+    /// Apply the [best CROSS-exchange](VrpResult::best_cross_exchange) until no exchange
+    /// lowers the cost, returns whether the solution changed
+    pub fn improve_cross_exchange(&mut self, max_segment_len: usize) -> bool {
+        let mut n = self.routes.len();
+
+        // Best exchange of every pair of routes, only the pairs of the routes of an exchange
+        // change
+        let mut pairs: Vec<Option<CrossExchange>> = vec![None; n * n];
+        {
+            let times: Vec<RouteTimes> = self.routes.iter().map(RouteTimes::new).collect();
+            for a in 0..n {
+                for b in a + 1..n {
+                    pairs[a * n + b] =
+                        self.best_cross_exchange_between(&times, a, b, max_segment_len);
+                }
+            }
+        }
+
+        let mut changed = false;
+        while let Some(exchange) = pairs
+            .iter()
+            .flatten()
+            .min_by(|x, y| x.delta.total_cmp(&y.delta))
+            .copied()
+        {
+            // A route giving its whole segment and getting nothing back is removed, at most
+            // one as the other gets a segment
+            let ((a, _, len_a), (b, _, len_b)) = (exchange.first, exchange.second);
+            let emptied = [(a, len_a, len_b), (b, len_b, len_a)]
+                .into_iter()
+                .find(|&(route, given, taken)| {
+                    taken == 0 && given == self.routes[route].customers.len()
+                })
+                .map(|(route, _, _)| route);
+
+            self.apply_cross_exchange(&exchange);
+            changed = true;
+
+            let mut routes = vec![a, b];
+            if let Some(removed) = emptied {
+                // Drop the pairs of the removed route and shift the later indices
+                let index = |i: usize| if i > removed { i - 1 } else { i };
+                routes.retain(|&route| route != removed);
+                routes.iter_mut().for_each(|route| *route = index(*route));
+
+                let m = n - 1;
+                let mut remapped: Vec<Option<CrossExchange>> = vec![None; m * m];
+                for a in (0..n).filter(|&a| a != removed) {
+                    for b in (a + 1..n).filter(|&b| b != removed) {
+                        remapped[index(a) * m + index(b)] = pairs[a * n + b].map(|mut e| {
+                            e.first.0 = index(e.first.0);
+                            e.second.0 = index(e.second.0);
+                            e
+                        });
+                    }
+                }
+                pairs = remapped;
+                n = m;
+            }
+
+            let times: Vec<RouteTimes> = self.routes.iter().map(RouteTimes::new).collect();
+            for route in routes {
+                for other in (0..n).filter(|&other| other != route) {
+                    let (a, b) = (route.min(other), route.max(other));
+                    pairs[a * n + b] =
+                        self.best_cross_exchange_between(&times, a, b, max_segment_len);
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// [best_cross_exchange](VrpResult::best_cross_exchange) as a
+/// [local search](crate::heuristics::local_search::LocalSearch) operator, the best exchange of
+/// every pair of routes is a move
+pub struct CrossExchangeOperator {
+    pub max_segment_len: usize,
+}
+
+impl Default for CrossExchangeOperator {
+    fn default() -> Self {
+        CrossExchangeOperator {
+            max_segment_len: DEFAULT_MAX_SEGMENT_LEN,
+        }
+    }
+}
+
+impl Operator for CrossExchangeOperator {
+    fn name(&self) -> &'static str {
+        "cross"
+    }
+
+    fn improving_moves(&self, result: &VrpResult, visit: &mut dyn FnMut(Move) -> bool) -> u64 {
+        let times: Vec<RouteTimes> = result.routes.iter().map(RouteTimes::new).collect();
+        let k = self.max_segment_len as u64 + 1;
+
+        let mut checks = 0;
+        for a in 0..result.routes.len() {
+            for b in a + 1..result.routes.len() {
+                checks += (times[a].len() as u64 + 1) * (times[b].len() as u64 + 1) * k * k;
+
+                let Some(exchange) =
+                    result.best_cross_exchange_between(&times, a, b, self.max_segment_len)
+                else {
+                    continue;
+                };
+                let (first, second) = result.exchanged_routes(&exchange);
+                let mv = Move {
+                    routes: vec![(a, first), (b, second)],
+                    delta: exchange.delta,
+                };
+                if !visit(mv) {
+                    return checks;
+                }
+            }
+        }
+        checks
+    }
+}
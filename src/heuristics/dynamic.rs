@@ -0,0 +1,179 @@
+use crate::heuristics::local_search::MIN_IMPROVEMENT;
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+
+impl VrpResult {
+    /// Insert an order that arrived at `current_time` into this plan, returns the index of the
+    /// route serving it or `None` if it cannot be served.
+    ///
+    /// Customers a vehicle is already committed to (see
+    /// [fixed_prefix_len](Route::fixed_prefix_len)) keep their place, the order goes to the
+    /// cheapest feasible position after them. The vehicle heads to the next customer once done
+    /// with the committed ones, not before `current_time`. If no route can take the order and a
+    /// vehicle is still available, a new route leaving a depot of `vrp` at `current_time` is
+    /// opened.
+    pub fn insert_dynamic(
+        &mut self,
+        customer: Location,
+        current_time: f32,
+        vrp: &Vrp,
+    ) -> Option<usize> {
+        let (index, position, _) = self.best_insertion_with(
+            &customer,
+            |route| route.cheapest_free_insertion(&customer, current_time, self.vehicle_capacity),
+            vrp,
+            current_time,
+        )?;
+
+        if index == self.routes.len() {
+            let route = self.new_route(&customer, vrp, current_time)?;
+            self.push_route(route);
+        } else {
+            self.insert_in_route(customer, index, position);
+        }
+        Some(index)
+    }
+
+    /// Take the vehicle of the route at `index` out of service at `current_time` (breakdown,
+    /// driver absence) and re-assign its remaining customers to the other routes with
+    /// [insert_dynamic](VrpResult::insert_dynamic), tightest due dates first.
+    ///
+    /// Customers the vehicle is committed to are kept as a route, returns the repaired plan and the
+    /// customers that could not be re-assigned. These are also added to the
+    /// [unassigned](VrpResult::unassigned) customers of the plan.
+    pub fn remove_route_and_repair(
+        &self,
+        index: usize,
+        current_time: f32,
+        vrp: &Vrp,
+    ) -> (VrpResult, Vec<Location>) {
+        let mut result = self.clone();
+        let vehicle = result.vehicle_of(index).cloned();
+        let mut route = result.remove_route(index);
+
+        let fixed = route.fixed_prefix_len(current_time);
+        let mut remaining = route.customers.split_off(fixed);
+        remaining.sort_by(|a, b| a.due_date.total_cmp(&b.due_date));
+
+        // The broken vehicle counts against the fleet, as a kept route or by shrinking the fleet
+        // while re-inserting
+        let n_vehicles = result.n_vehicles;
+        if route.customers.is_empty() {
+            result.n_vehicles = n_vehicles.saturating_sub(1);
+        } else {
+            result.routes.insert(index, route);
+            if let Some(vehicle) = vehicle {
+                result.vehicles.insert(index, vehicle);
+            }
+            result.refresh_cost();
+        }
+
+        let mut unassigned = Vec::new();
+        for customer in remaining {
+            if result
+                .insert_dynamic(customer.clone(), current_time, vrp)
+                .is_none()
+            {
+                result.unassigned.push(customer.id);
+                unassigned.push(customer);
+            }
+        }
+        result.n_vehicles = n_vehicles;
+
+        (result, unassigned)
+    }
+
+    /// Re-optimize the customers of the route at `index` that are not committed at
+    /// `current_time`, by moving them within the route while this lowers its cost. Call this
+    /// after [insert_dynamic](VrpResult::insert_dynamic), returns the cost saved
+    pub fn reoptimize_route(&mut self, index: usize, current_time: f32) -> f32 {
+        let mut route = self.routes[index].clone();
+        let fixed = route.fixed_prefix_len(current_time);
+
+        let initial_cost = self.route_cost(index);
+        let mut cost = initial_cost;
+
+        let mut improved = true;
+        while improved {
+            improved = false;
+
+            for from in fixed..route.customers.len() {
+                let mut candidate = route.clone();
+                let customer = candidate.customers.remove(from);
+
+                if let Some((to, new_cost)) = candidate.cheapest_free_insertion(
+                    &customer,
+                    current_time,
+                    self.vehicle_capacity,
+                ) {
+                    if new_cost < cost - MIN_IMPROVEMENT {
+                        candidate.customers.insert(to, customer);
+                        route = candidate;
+                        cost = new_cost;
+                        improved = true;
+                    }
+                }
+            }
+        }
+
+        if cost < initial_cost {
+            self.replace_route(index, route);
+        }
+        initial_cost - cost
+    }
+}
+
+impl Route {
+    // -- Find the cheapest feasible position for a customer after the committed prefix --
+    // The vehicle leaves the last committed stop at its departure or at `current_time`,
+    // whichever is later: the first free customer is held until the vehicle can reach it from
+    // there. Returns the position and the cost of the route after insertion
+    fn cheapest_free_insertion(
+        &self,
+        customer: &Location,
+        current_time: f32,
+        capacity: f64,
+    ) -> Option<(usize, f32)> {
+        if !self.can_possibly_insert(customer, capacity) {
+            return None;
+        }
+
+        let fixed = self.fixed_prefix_len(current_time);
+        let customers: Vec<&Location> = self.customers.iter().collect();
+        let previous = match fixed {
+            0 => &self.warehouse,
+            _ => customers[fixed - 1],
+        };
+        let hold = |c: &Location| Location {
+            ready_time: c
+                .ready_time
+                .max((current_time + previous.travel_time_to(c)) as f64),
+            ..c.clone()
+        };
+
+        (fixed..=customers.len())
+            .filter_map(|i| {
+                let first = hold(if i == fixed {
+                    customer
+                } else {
+                    customers[fixed]
+                });
+                let new_customers: Vec<&Location> = customers[..fixed]
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(&first))
+                    .chain(customers[fixed..i].iter().copied().skip(1))
+                    .chain((i > fixed).then_some(customer))
+                    .chain(customers[i..].iter().copied())
+                    .collect();
+
+                if !self.is_valid_with(&new_customers, capacity) {
+                    return None;
+                }
+                Some((i, self.total_cost_with(&new_customers)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
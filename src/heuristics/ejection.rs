@@ -0,0 +1,186 @@
+use crate::heuristics::stats::Deadline;
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp_result::VrpResult;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Parameters of the ejection chains removing routes, see
+/// [eliminate_route](VrpResult::eliminate_route)
+#[derive(Debug, Clone)]
+pub struct EjectionParams {
+    /// Most customers ejected from a route to make room for one customer
+    pub max_ejected: usize,
+    /// Customers taken from the ejection pool before giving up on a route
+    pub max_iterations: u32,
+    /// Give up on a route once this much time has passed, even if `max_iterations` has not been
+    /// reached
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for EjectionParams {
+    fn default() -> Self {
+        EjectionParams {
+            max_ejected: 2,
+            max_iterations: 1000,
+            time_limit: None,
+        }
+    }
+}
+
+/// Insertion of a customer ejecting others from a route: the route index, the route after the
+/// insertion and the ejected customers
+type Ejection = (usize, Route, Vec<Location>);
+
+impl VrpResult {
+    /// Remove the route at `index` and fit its customers in the other routes with an ejection
+    /// chain (Nagata & Bräysy, 2009), without opening a route.
+    ///
+    /// The customers of the route go to an ejection pool. The last customer of the pool is
+    /// inserted at its cheapest feasible position, or if it fits nowhere in place of up to
+    /// `max_ejected` customers of a route, which join the pool. Every time a customer fits
+    /// nowhere its penalty grows, the customers ejected are the ones with the lowest total
+    /// penalty, so customers that are hard to insert end up being inserted first.
+    ///
+    /// Returns whether the pool was emptied, the solution is left unchanged otherwise
+    pub fn eliminate_route(&mut self, index: usize, params: &EjectionParams) -> bool {
+        let mut result = self.clone();
+        let mut pool: Vec<Location> = result.remove_route(index).customers;
+        let mut penalties: HashMap<u16, u32> = HashMap::new();
+
+        let deadline = Deadline::from_limit(params.time_limit);
+
+        let mut iterations = 0;
+        while let Some(customer) = pool.pop() {
+            if iterations >= params.max_iterations || deadline.is_reached() {
+                return false;
+            }
+            iterations += 1;
+
+            if let Some((index, position)) = result.best_insertion_in_routes(&customer) {
+                result.insert_in_route(customer, index, position);
+                continue;
+            }
+
+            *penalties.entry(customer.id).or_default() += 1;
+            let Some((index, route, ejected)) =
+                result.best_ejection(&customer, &penalties, params.max_ejected)
+            else {
+                return false;
+            };
+            result.replace_route(index, route);
+            pool.extend(ejected);
+        }
+
+        *self = result;
+        true
+    }
+
+    /// Apply [eliminate_route](VrpResult::eliminate_route) to the route with the fewest
+    /// customers until it fails, returns the number of routes removed
+    pub fn minimize_routes(&mut self, params: &EjectionParams) -> usize {
+        let mut removed = 0;
+        while self.routes.len() > 1 {
+            let index = (0..self.routes.len())
+                .min_by_key(|&i| self.routes[i].customers.len())
+                .unwrap_or(0);
+            if !self.eliminate_route(index, params) {
+                break;
+            }
+            removed += 1;
+        }
+        removed
+    }
+
+    /// The cheapest feasible insertion of `customer` in the existing routes, as the route index
+    /// and the position
+    pub(crate) fn best_insertion_in_routes(&self, customer: &Location) -> Option<(usize, usize)> {
+        self.routes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, route)| {
+                let (position, cost) =
+                    route.cheapest_insertion_from(0, customer, self.vehicle_capacity)?;
+                Some((index, position, cost - self.route_cost(index)))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))
+            .map(|(index, position, _)| (index, position))
+    }
+
+    /// The insertion of `customer` ejecting at most `max_ejected` customers of a route with the
+    /// lowest total penalty, then the lowest cost
+    fn best_ejection(
+        &self,
+        customer: &Location,
+        penalties: &HashMap<u16, u32>,
+        max_ejected: usize,
+    ) -> Option<Ejection> {
+        let penalty = |c: &Location| penalties.get(&c.id).copied().unwrap_or(0);
+
+        let mut best: Option<Ejection> = None;
+        let mut best_key = (u32::MAX, f32::INFINITY);
+
+        for (index, route) in self.routes.iter().enumerate() {
+            for ejected in subsets(route.customers.len(), max_ejected) {
+                // Penalties are checked first, they rule out most subsets without a route
+                let total: u32 = ejected.iter().map(|&i| penalty(&route.customers[i])).sum();
+                if total > best_key.0 {
+                    continue;
+                }
+
+                let mut candidate = Route {
+                    warehouse: route.warehouse.clone(),
+                    customers: route
+                        .customers
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| !ejected.contains(i))
+                        .map(|(_, c)| c.clone())
+                        .collect(),
+                    open: route.open,
+                    max_duration: route.max_duration,
+                    breaks: route.breaks.clone(),
+                };
+                let Some((position, cost)) =
+                    candidate.cheapest_insertion_from(0, customer, self.vehicle_capacity)
+                else {
+                    continue;
+                };
+
+                let key = (total, cost - self.route_cost(index));
+                if key < best_key {
+                    candidate.customers.insert(position, customer.clone());
+                    let ejected = ejected
+                        .iter()
+                        .map(|&i| route.customers[i].clone())
+                        .collect();
+                    best = Some((index, candidate, ejected));
+                    best_key = key;
+                }
+            }
+        }
+        best
+    }
+}
+
+/// The sets of 1 to `k` positions among `len`, as increasing positions
+fn subsets(len: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut subsets: Vec<Vec<usize>> = Vec::new();
+    let mut last: Vec<Vec<usize>> = vec![Vec::new()];
+
+    for _ in 0..k.min(len) {
+        last = last
+            .iter()
+            .flat_map(|subset| {
+                let first = subset.last().map_or(0, |&i| i + 1);
+                (first..len).map(move |i| {
+                    let mut next = subset.clone();
+                    next.push(i);
+                    next
+                })
+            })
+            .collect();
+        subsets.extend(last.iter().cloned());
+    }
+    subsets
+}
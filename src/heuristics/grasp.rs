@@ -0,0 +1,245 @@
+use crate::compact::CompactSolution;
+use crate::heuristics::checkpoint::{Checkpoint, CheckpointWriter};
+use crate::heuristics::local_search::{LocalSearch, Strategy};
+use crate::heuristics::progress::{no_progress, IterationInfo};
+use crate::heuristics::relocate::RelocateOperator;
+use crate::heuristics::stats::{Deadline, SolveStats, Stopwatch};
+use crate::heuristics::trace::{IterationTrace, SearchTrace};
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+/// Parameters for the GRASP heuristic
+#[derive(Debug, Clone)]
+pub struct GraspParams {
+    /// The number of constructions, each followed by a local search
+    pub iterations: u32,
+    /// Greediness of the construction, between 0 and 1: the next customer is drawn among the
+    /// deliverable ones costing at most `min + alpha * (max - min)`. 0 is the nearest neighbor
+    /// heuristic, 1 a random feasible customer
+    pub alpha: f32,
+    /// Run the local search after every construction
+    pub local_search: bool,
+    /// Stop iterating once this much time has passed, even if `iterations` has not been reached
+    pub time_limit: Option<Duration>,
+    /// Periodically write the best solution found so far to disk
+    pub checkpoint: Option<Checkpoint>,
+    /// Seed of the random number generator, the same seed always gives the same solution unless
+    /// the time limit stops the run first. `None` draws a seed from the system
+    pub seed: Option<u64>,
+    /// Collect the statistics of every iteration into the [trace](VrpResult::trace) of the
+    /// result
+    pub trace: bool,
+}
+
+impl Default for GraspParams {
+    fn default() -> Self {
+        GraspParams {
+            iterations: 50,
+            alpha: 0.2,
+            local_search: true,
+            time_limit: None,
+            checkpoint: None,
+            seed: None,
+            trace: false,
+        }
+    }
+}
+
+impl Vrp {
+    /// Run the GRASP heuristic (greedy randomized adaptive search procedure): repeatedly build a
+    /// solution like the nearest neighbor heuristic, drawing every next customer at random from a
+    /// restricted candidate list of the cheapest deliverable ones, then improve it with a local
+    /// search. Returns the best solution, with the best cost after every iteration as its
+    /// [cost history](VrpResult::heuristic_cost_history)
+    pub fn grasp_heuristic(&self, params: &GraspParams) -> VrpResult {
+        self.grasp_heuristic_with_progress(params, &mut no_progress)
+    }
+
+    /// Same as [grasp_heuristic](Vrp::grasp_heuristic), calling `progress` after every
+    /// construction and local search, it may stop the run
+    pub fn grasp_heuristic_with_progress(
+        &self,
+        params: &GraspParams,
+        progress: &mut dyn FnMut(IterationInfo) -> ControlFlow<()>,
+    ) -> VrpResult {
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats::default();
+        // A seed makes the run reproducible, else draw one from the system
+        let mut rng = params
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+
+        // Customers no vehicle can serve would never be picked, leave them unassigned
+        let (servable, unassigned): (Vec<&Location>, Vec<&Location>) =
+            self.customers.iter().partition(|c| self.can_serve_alone(c));
+
+        let local_search =
+            LocalSearch::new(Strategy::FirstImprovement).with(RelocateOperator::default());
+
+        let mut best: Option<VrpResult> = None;
+        let mut best_cost_history: Vec<f32> = Vec::new();
+        let mut search_trace = params.trace.then(SearchTrace::default);
+
+        let deadline = Deadline::from_limit(params.time_limit);
+        let mut checkpoint = params.checkpoint.as_ref().map(CheckpointWriter::new);
+        // Constructions equal to an earlier one once normalized would end in the same local
+        // search, they are not searched again
+        let mut constructed: HashSet<CompactSolution> = HashSet::new();
+
+        for _ in 0..params.iterations {
+            if deadline.is_reached() {
+                break;
+            }
+            stats.iterations += 1;
+            stats.solutions_evaluated += 1;
+
+            let (routes, stranded) =
+                self.randomized_construction(&servable, params.alpha, &mut rng, &mut stats);
+            let mut result = VrpResult {
+                unassigned: unassigned.iter().map(|c| c.id).chain(stranded).collect(),
+                ..VrpResult::from_vrp(self, routes, None)
+            };
+            if params.local_search
+                && result
+                    .to_compact(self)
+                    .and_then(|compact| compact.normalized(self))
+                    .is_none_or(|compact| constructed.insert(compact))
+            {
+                stats.feasibility_checks += local_search.run(&mut result).feasibility_checks;
+            }
+
+            let cost = result.total_cost();
+            if best.as_ref().is_none_or(|best| result.is_better_than(best)) {
+                best = Some(result);
+            }
+            if let Some(best) = &best {
+                best_cost_history.push(best.total_cost());
+                if let Some(search_trace) = &mut search_trace {
+                    search_trace.push(IterationTrace {
+                        iteration: stats.iterations,
+                        best_cost: best.total_cost(),
+                        n_routes: best.routes.len(),
+                        average_cost: Some(cost),
+                        diversity: None,
+                        acceptance_rate: None,
+                    });
+                }
+            }
+
+            if let (Some(checkpoint), Some(best)) = (&mut checkpoint, &best) {
+                checkpoint.maybe_write(|| VrpResult {
+                    heuristic_cost_history: Some(best_cost_history.clone()),
+                    ..best.clone()
+                });
+            }
+
+            let info = IterationInfo {
+                iteration: stats.iterations,
+                best_cost: best.as_ref().map_or(f32::INFINITY, VrpResult::total_cost),
+                elapsed: stopwatch.elapsed(),
+            };
+            if progress(info).is_break() {
+                break;
+            }
+        }
+
+        stats.wall_time = stopwatch.elapsed();
+        let result = VrpResult {
+            heuristic_cost_history: Some(best_cost_history),
+            stats: Some(stats),
+            trace: search_trace,
+            ..best.unwrap_or_else(|| VrpResult {
+                unassigned: unassigned.iter().map(|c| c.id).collect(),
+                ..VrpResult::from_vrp(self, Vec::new(), None)
+            })
+        };
+
+        if let Some(checkpoint) = &mut checkpoint {
+            checkpoint.write(&result);
+        }
+
+        result
+    }
+
+    /// Build routes one at a time, appending a random customer of the restricted candidate list
+    /// until none is deliverable. Returns the routes and the ids of the customers no fresh route
+    /// could take
+    fn randomized_construction(
+        &self,
+        servable: &[&Location],
+        alpha: f32,
+        rng: &mut impl Rng,
+        stats: &mut SolveStats,
+    ) -> (Vec<Route>, Vec<u16>) {
+        let mut unvisited: Vec<&Location> = servable.to_vec();
+        let mut routes: Vec<Route> = Vec::new();
+
+        while !unvisited.is_empty() {
+            let mut route = self.empty_route();
+            let mut current = &self.warehouse;
+            let mut cost = 0f32;
+            let mut demand = 0.0;
+
+            loop {
+                stats.feasibility_checks += unvisited.len() as u64;
+
+                // Deliverable customers the vehicle can still come back from in time, and end
+                // the route within its max duration and breaks, no delivery following a
+                // backhaul
+                let candidates: Vec<(&Location, f32)> = current
+                    .find_deliverable(
+                        unvisited.clone(),
+                        cost,
+                        (self.vehicle_capacity - demand).max(0.0),
+                    )
+                    .into_iter()
+                    .map(|c| (c, current.cost_to_deliver(c, cost)))
+                    .filter(|&(c, done)| {
+                        (self.open
+                            || c.cost_to(&self.warehouse, done) <= self.warehouse.due_date as f32)
+                            && self.fits_max_duration(c, done)
+                            && c.may_follow(current)
+                            && self.fits_breaks(&route, c)
+                    })
+                    .collect();
+
+                let (min, max) = candidates.iter().fold(
+                    (f32::INFINITY, f32::NEG_INFINITY),
+                    |(min, max), &(_, cost)| (min.min(cost), max.max(cost)),
+                );
+                let threshold = min + alpha * (max - min);
+                let restricted: Vec<&(&Location, f32)> = candidates
+                    .iter()
+                    .filter(|&&(_, cost)| cost <= threshold)
+                    .collect();
+                if restricted.is_empty() {
+                    break;
+                }
+
+                let &(next, done) = restricted[rng.gen_range(0..restricted.len())];
+                unvisited.retain(|&c| c != next);
+
+                current = next;
+                cost = done;
+                demand += next.demand;
+                route.customers.push(next.clone());
+            }
+
+            // None of the unvisited customers fit even an empty route, stop instead of opening
+            // empty routes forever
+            if route.customers.is_empty() {
+                return (routes, unvisited.iter().map(|c| c.id).collect());
+            }
+
+            routes.push(route);
+        }
+        (routes, Vec::new())
+    }
+}
@@ -0,0 +1,255 @@
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+
+impl VrpResult {
+    /// Find the cheapest feasible insertion of `customer` over all routes, as the route index,
+    /// the position in the route and the cost increase of the solution.
+    ///
+    /// If no route can take the customer and a vehicle is still available, a new route from
+    /// the cheapest depot of `vrp` able to serve it is proposed: its index is the number of
+    /// routes, the position 0 and the cost increase the cost of the new route. Apply the result
+    /// with [insert_customer](VrpResult::insert_customer)
+    pub fn best_insertion(&self, customer: &Location, vrp: &Vrp) -> Option<(usize, usize, f32)> {
+        self.best_insertion_with(
+            customer,
+            |route| route.cheapest_insertion_from(0, customer, self.vehicle_capacity),
+            vrp,
+            0.0,
+        )
+    }
+
+    /// Insert `customer` at `position` of the route at `index`, as returned by
+    /// [best_insertion](VrpResult::best_insertion). An index equal to the number of routes opens
+    /// a new route from the depot of `vrp` proposed by best_insertion, or from the warehouse if
+    /// no depot can serve the customer
+    pub fn insert_customer(
+        &mut self,
+        customer: Location,
+        index: usize,
+        position: usize,
+        vrp: &Vrp,
+    ) {
+        if index == self.routes.len() {
+            let route = self
+                .new_route(&customer, vrp, 0.0)
+                .unwrap_or_else(|| Route {
+                    customers: vec![customer],
+                    ..vrp.empty_route()
+                });
+            self.push_route(route);
+        } else {
+            self.insert_in_route(customer, index, position);
+        }
+    }
+
+    /// Insert `customer` at `position` of the existing route at `index`
+    pub(crate) fn insert_in_route(&mut self, customer: Location, index: usize, position: usize) {
+        let mut route = self.routes[index].clone();
+        route.customers.insert(position, customer);
+        self.replace_route(index, route);
+    }
+
+    /// Same as [best_insertion](VrpResult::best_insertion), with the position and cost of the
+    /// cheapest insertion in a route given by `insertion` and opening new routes that leave
+    /// their depot at `departure`
+    pub(crate) fn best_insertion_with(
+        &self,
+        customer: &Location,
+        insertion: impl Fn(&Route) -> Option<(usize, f32)>,
+        vrp: &Vrp,
+        departure: f32,
+    ) -> Option<(usize, usize, f32)> {
+        let best = self
+            .routes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, route)| {
+                let (position, cost) = insertion(route)?;
+                Some((index, position, cost - self.route_cost(index)))
+            })
+            .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
+
+        if best.is_some() {
+            return best;
+        }
+
+        if self.routes.len() >= self.n_vehicles as usize {
+            return None;
+        }
+
+        let route = self.new_route(customer, vrp, departure)?;
+        Some((self.routes.len(), 0, route.total_cost()))
+    }
+
+    /// The cheapest valid route serving only `customer` from a depot of `vrp`, leaving the
+    /// depot at `departure`
+    pub(crate) fn new_route(
+        &self,
+        customer: &Location,
+        vrp: &Vrp,
+        departure: f32,
+    ) -> Option<Route> {
+        vrp.depots()
+            .filter_map(|depot| {
+                let reached = departure + depot.travel_time_to(customer);
+                if reached > customer.due_date as f32 {
+                    return None;
+                }
+                // Holding the customer until the vehicle can reach it checks the route as if it
+                // left at the departure
+                let held = Location {
+                    ready_time: customer.ready_time.max(reached as f64),
+                    ..customer.clone()
+                };
+                let route = Route {
+                    customers: vec![held],
+                    ..vrp.empty_route_from(depot)
+                };
+                route.is_valid(self.vehicle_capacity).then(|| Route {
+                    customers: vec![customer.clone()],
+                    ..route
+                })
+            })
+            .min_by(|a, b| a.total_cost().total_cmp(&b.total_cost()))
+    }
+}
+
+/// Slack on the pre-screen bounds, so rounding never rules out a feasible insertion
+const PRE_SCREEN_TOLERANCE: f32 = 1e-3;
+
+/// An arc of a route a customer can be inserted on, with the schedule around it, see
+/// [insertion_arcs](Route::insertion_arcs)
+pub(crate) struct InsertionArc<'a> {
+    pub from: &'a Location,
+    /// Departure time from `from`, 0 from the warehouse
+    pub departure: f32,
+    pub to: &'a Location,
+    /// Arrival time at `to`, the return time for the warehouse
+    pub arrival: f32,
+    /// Delay the arrival at `to` can take without breaking a later time window, waiting times
+    /// absorb part of a delay
+    pub slack: f32,
+}
+
+impl InsertionArc<'_> {
+    /// The arrival time at `to` after a detour through `customer`, `None` if the vehicle
+    /// reaches `customer` after its due date or the delay breaks a later time window. Capacity
+    /// is not checked
+    pub(crate) fn arrival_through(&self, customer: &Location) -> Option<f32> {
+        let at_customer = self.from.cost_to(customer, self.departure);
+        let arrival =
+            customer.cost_to(self.to, self.from.cost_to_deliver(customer, self.departure));
+
+        (at_customer <= customer.due_date as f32 + PRE_SCREEN_TOLERANCE
+            && arrival - self.arrival <= self.slack + PRE_SCREEN_TOLERANCE)
+            .then_some(arrival)
+    }
+}
+
+impl Route {
+    // -- Get the arcs a customer can be inserted on --
+    // From the warehouse to the first customer up to the last customer back to the warehouse.
+    // The slack of an arc is the delay the arrival at its end can take, the minimum of the time
+    // left before the due date there and of the waiting time plus the slack of the next arc
+    pub(crate) fn insertion_arcs(&self) -> Vec<InsertionArc<'_>> {
+        let schedule = self.schedule();
+        let back = match (self.customers.last(), schedule.last()) {
+            (Some(last), Some(stop)) => last.cost_to(&self.warehouse, stop.departure),
+            _ => 0.0,
+        };
+
+        // Open routes end at their last customer, the return is only a bound of the arrivals.
+        // The max duration bounds the return of closed routes, the end of open ones is only
+        // checked once the customer is inserted
+        let end_slack = if self.open {
+            f32::INFINITY
+        } else {
+            let due = self.max_duration.map_or(self.warehouse.due_date, |max| {
+                max.min(self.warehouse.due_date)
+            });
+            due as f32 - back
+        };
+        let mut slack = vec![end_slack; self.customers.len() + 1];
+        for (k, (c, stop)) in self.customers.iter().zip(&schedule).enumerate().rev() {
+            slack[k] = (c.due_date as f32 - stop.arrival).min(stop.waiting_time + slack[k + 1]);
+        }
+
+        let previous = std::iter::once((&self.warehouse, 0.0)).chain(
+            self.customers
+                .iter()
+                .zip(&schedule)
+                .map(|(c, stop)| (c, stop.departure)),
+        );
+        let next = self
+            .customers
+            .iter()
+            .zip(schedule.iter().map(|stop| stop.arrival))
+            .chain(std::iter::once((&self.warehouse, back)));
+
+        previous
+            .zip(next)
+            .zip(slack)
+            .map(|(((from, departure), (to, arrival)), slack)| InsertionArc {
+                from,
+                departure,
+                to,
+                arrival,
+                slack,
+            })
+            .collect()
+    }
+
+    // -- Cheaply rule out inserting a customer --
+    // False when no position can take `customer`: its least peak load exceeds the capacity, or
+    // on every arc the vehicle reaches the customer after its due date or the detour delays the
+    // rest of the route more than its slack allows, see insertion_arcs.
+    // True does not promise a feasible position. This is linear in the length of the route,
+    // call it before scanning the positions, which is quadratic
+    pub fn can_possibly_insert(&self, customer: &Location, capacity: f64) -> bool {
+        if self.min_load_with(&[customer]) > capacity {
+            return false;
+        }
+        // The arcs do not account for breaks, which an insertion may move
+        if !self.breaks.is_empty() {
+            return true;
+        }
+
+        self.insertion_arcs()
+            .iter()
+            .any(|arc| arc.arrival_through(customer).is_some())
+    }
+
+    // -- Find the cheapest feasible position for a customer, at or after `first_position` --
+    // Unlike try_insert, this also tries inserting the customer last. Returns the position and
+    // the cost of the route after insertion
+    pub(crate) fn cheapest_insertion_from(
+        &self,
+        first_position: usize,
+        customer: &Location,
+        capacity: f64,
+    ) -> Option<(usize, f32)> {
+        if !self.can_possibly_insert(customer, capacity) {
+            return None;
+        }
+
+        let customers: Vec<&Location> = self.customers.iter().collect();
+
+        (first_position..=customers.len())
+            .filter_map(|i| {
+                let new_customers: Vec<&Location> = customers[..i]
+                    .iter()
+                    .copied()
+                    .chain(std::iter::once(customer))
+                    .chain(customers[i..].iter().copied())
+                    .collect();
+
+                if !self.is_valid_with(&new_customers, capacity) {
+                    return None;
+                }
+                Some((i, self.total_cost_with(&new_customers)))
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
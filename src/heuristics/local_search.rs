@@ -0,0 +1,289 @@
+use crate::heuristics::cross_exchange::{CrossExchangeOperator, DEFAULT_MAX_SEGMENT_LEN};
+use crate::heuristics::or_opt::{OrOptOperator, DEFAULT_OR_OPT_SEGMENT_LEN};
+use crate::heuristics::relocate::RelocateOperator;
+use crate::heuristics::stats::{Deadline, SolveStats, Stopwatch};
+use crate::heuristics::swap::SwapOperator;
+use crate::heuristics::two_opt::TwoOptOperator;
+use crate::route::Route;
+use crate::vrp_result::VrpResult;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Minimum cost decrease of an improving move, so rounding errors never make the search cycle
+pub(crate) const MIN_IMPROVEMENT: f32 = 1e-3;
+
+/// A change of a solution: the new routes by route index, an index equal to the number of routes
+/// opens a route. Routes left empty are removed when the move is applied
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub routes: Vec<(usize, Route)>,
+    /// Change of the total cost, negative for an improvement
+    pub delta: f32,
+}
+
+impl Move {
+    /// The move replacing routes of `result` by `routes`, with its cost change
+    pub fn new(result: &VrpResult, routes: Vec<(usize, Route)>) -> Move {
+        let delta = routes
+            .iter()
+            .map(|(index, route)| {
+                let cost = if route.customers.is_empty() {
+                    0.0
+                } else {
+                    route.total_cost()
+                };
+                let old = if *index < result.routes.len() {
+                    result.route_cost(*index)
+                } else {
+                    0.0
+                };
+                cost - old
+            })
+            .sum();
+        Move { routes, delta }
+    }
+
+    /// Whether the move lowers the cost by more than rounding errors
+    pub fn is_improving(&self) -> bool {
+        self.delta < -MIN_IMPROVEMENT
+    }
+
+    /// Replace the routes of `result`, then remove the emptied ones
+    pub fn apply(self, result: &mut VrpResult) {
+        let mut emptied = Vec::new();
+        for (index, route) in self.routes {
+            if route.customers.is_empty() {
+                emptied.push(index);
+            } else if index < result.routes.len() {
+                result.replace_route(index, route);
+            } else {
+                result.push_route(route);
+            }
+        }
+
+        // Remove from the back so the other indices stay valid
+        emptied.sort_unstable();
+        for index in emptied.into_iter().rev() {
+            result.remove_route(index);
+        }
+    }
+}
+
+/// A neighborhood of a [LocalSearch]: generates the moves of a solution
+pub trait Operator {
+    /// Name of the operator, for reports
+    fn name(&self) -> &'static str;
+
+    /// Pass the feasible [improving](Move::is_improving) moves of `result` to `visit`, stopping
+    /// as soon as it returns false. Returns the number of candidate moves checked against
+    /// capacity and time windows
+    fn improving_moves(&self, result: &VrpResult, visit: &mut dyn FnMut(Move) -> bool) -> u64;
+}
+
+/// Which improving move a [LocalSearch] applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// The first one found, cheap steps
+    #[default]
+    FirstImprovement,
+    /// The one lowering the cost the most, fewer steps
+    BestImprovement,
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    /// Parse `first` or `best`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first" => Ok(Strategy::FirstImprovement),
+            "best" => Ok(Strategy::BestImprovement),
+            _ => Err(format!("Unknown local search strategy: {}", s)),
+        }
+    }
+}
+
+/// A descent chaining [operators](Operator): the first operator is used until it finds no
+/// improving move, then the next one. After an improvement the search starts over from the first
+/// operator, it stops once no operator improves the solution (variable neighborhood descent)
+pub struct LocalSearch {
+    operators: Vec<Box<dyn Operator>>,
+    pub strategy: Strategy,
+    /// Stop once this much time has passed, even if the solution can still be improved
+    pub time_limit: Option<Duration>,
+}
+
+impl LocalSearch {
+    /// A search without operators, add them with [with](LocalSearch::with)
+    pub fn new(strategy: Strategy) -> LocalSearch {
+        LocalSearch {
+            operators: Vec::new(),
+            strategy,
+            time_limit: None,
+        }
+    }
+
+    /// Add `operator` after the current ones
+    pub fn with(mut self, operator: impl Operator + 'static) -> LocalSearch {
+        self.operators.push(Box::new(operator));
+        self
+    }
+
+    /// Names of the operators, in order
+    pub fn operator_names(&self) -> Vec<&'static str> {
+        self.operators.iter().map(|o| o.name()).collect()
+    }
+
+    /// Improve `result` until no operator finds an improving move. The returned stats count
+    /// every applied move as an iteration and every improving move found as a solution
+    /// evaluated
+    pub fn run(&self, result: &mut VrpResult) -> SolveStats {
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats::default();
+
+        let deadline = Deadline::from_limit(self.time_limit);
+
+        let mut k = 0;
+        while k < self.operators.len() {
+            if deadline.is_reached() {
+                break;
+            }
+
+            let mut best: Option<Move> = None;
+            let mut found = 0;
+            stats.feasibility_checks += self.operators[k].improving_moves(result, &mut |mv| {
+                found += 1;
+                if best.as_ref().is_none_or(|best| mv.delta < best.delta) {
+                    best = Some(mv);
+                }
+                self.strategy == Strategy::BestImprovement
+            });
+            stats.solutions_evaluated += found;
+
+            match best {
+                Some(mv) => {
+                    mv.apply(result);
+                    stats.iterations += 1;
+                    k = 0;
+                }
+                None => k += 1,
+            }
+        }
+
+        stats.wall_time = stopwatch.elapsed();
+        stats
+    }
+}
+
+/// The operators of the crate, for [post_optimize](VrpResult::post_optimize)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImprovementOperator {
+    TwoOpt,
+    /// Segments of up to `max_segment_len` customers
+    OrOpt {
+        max_segment_len: usize,
+    },
+    Relocate,
+    Swap,
+    /// CROSS-exchange of segments of up to `max_segment_len` customers
+    Cross {
+        max_segment_len: usize,
+    },
+}
+
+impl ImprovementOperator {
+    fn boxed(self) -> Box<dyn Operator> {
+        match self {
+            ImprovementOperator::TwoOpt => Box::new(TwoOptOperator),
+            ImprovementOperator::OrOpt { max_segment_len } => {
+                Box::new(OrOptOperator::new(max_segment_len))
+            }
+            ImprovementOperator::Relocate => Box::new(RelocateOperator::default()),
+            ImprovementOperator::Swap => Box::new(SwapOperator),
+            ImprovementOperator::Cross { max_segment_len } => {
+                Box::new(CrossExchangeOperator { max_segment_len })
+            }
+        }
+    }
+}
+
+impl fmt::Display for ImprovementOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImprovementOperator::TwoOpt => write!(f, "2opt"),
+            ImprovementOperator::OrOpt { .. } => write!(f, "oropt"),
+            ImprovementOperator::Relocate => write!(f, "relocate"),
+            ImprovementOperator::Swap => write!(f, "swap"),
+            ImprovementOperator::Cross { .. } => write!(f, "cross"),
+        }
+    }
+}
+
+impl FromStr for ImprovementOperator {
+    type Err = String;
+
+    /// Parse `2opt`, `oropt`, `relocate`, `swap` or `cross`, segments use the default lengths
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "2opt" => Ok(ImprovementOperator::TwoOpt),
+            "oropt" => Ok(ImprovementOperator::OrOpt {
+                max_segment_len: DEFAULT_OR_OPT_SEGMENT_LEN,
+            }),
+            "relocate" => Ok(ImprovementOperator::Relocate),
+            "swap" => Ok(ImprovementOperator::Swap),
+            "cross" => Ok(ImprovementOperator::Cross {
+                max_segment_len: DEFAULT_MAX_SEGMENT_LEN,
+            }),
+            _ => Err(format!("Unknown improvement operator: {}", s)),
+        }
+    }
+}
+
+/// Settings of [post_optimize](VrpResult::post_optimize)
+#[derive(Debug, Clone)]
+pub struct PostOptimizeOptions {
+    /// Operators of the descent, in order
+    pub operators: Vec<ImprovementOperator>,
+    pub strategy: Strategy,
+    /// Stop once this much time has passed, even if the solution can still be improved
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for PostOptimizeOptions {
+    /// 2-opt, or-opt and relocate, first improvement
+    fn default() -> Self {
+        PostOptimizeOptions {
+            operators: vec![
+                ImprovementOperator::TwoOpt,
+                ImprovementOperator::OrOpt {
+                    max_segment_len: DEFAULT_OR_OPT_SEGMENT_LEN,
+                },
+                ImprovementOperator::Relocate,
+            ],
+            strategy: Strategy::default(),
+            time_limit: None,
+        }
+    }
+}
+
+impl VrpResult {
+    /// Polish this solution with a [local search](LocalSearch) chaining the operators of `opts`,
+    /// whichever heuristic built it. The cost cache is refreshed and the stats of the search are
+    /// added to the [stats](VrpResult::stats) of the solution, which are returned
+    pub fn post_optimize(&mut self, opts: &PostOptimizeOptions) -> SolveStats {
+        let local_search = LocalSearch {
+            operators: opts.operators.iter().map(|o| o.boxed()).collect(),
+            strategy: opts.strategy,
+            time_limit: opts.time_limit,
+        };
+
+        let stats = local_search.run(self);
+        self.refresh_cost();
+        match &mut self.stats {
+            Some(total) => *total += stats,
+            None => self.stats = Some(stats),
+        }
+        stats
+    }
+}
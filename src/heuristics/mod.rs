@@ -1,2 +1,26 @@
 pub mod aco;
+pub mod cancellation;
+pub mod checkpoint;
+pub mod cross_exchange;
+pub mod dynamic;
+pub mod ejection;
+pub mod grasp;
+pub mod insertion;
+pub mod local_search;
+pub mod multi_depot;
+pub mod multi_start;
 pub mod nearest_neighbor;
+pub mod or_opt;
+pub mod pickup_delivery;
+pub mod portfolio;
+pub mod progress;
+pub mod registry;
+pub mod relocate;
+pub mod simulated_annealing;
+pub mod solomon_i1;
+pub mod stats;
+pub mod swap;
+pub mod sweep;
+pub mod trace;
+pub mod two_opt;
+pub mod workspace;
@@ -0,0 +1,70 @@
+use crate::heuristics::registry::Heuristic;
+use crate::heuristics::stats::SolveStats;
+use crate::location::Location;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+
+impl Vrp {
+    /// Assign every customer to its nearest [depot](Vrp::depots), the first one on ties.
+    /// Returns a single depot instance per depot, in the order of [depots](Vrp::depots), each
+    /// with the whole fleet
+    pub fn nearest_depot_clusters(&self) -> Vec<Vrp> {
+        let depots: Vec<&Location> = self.depots().collect();
+        let mut clusters: Vec<Vec<Location>> = vec![Vec::new(); depots.len()];
+
+        for customer in &self.customers {
+            let nearest = depots
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    a.distance_to(customer).total_cmp(&b.distance_to(customer))
+                })
+                .map_or(0, |(i, _)| i);
+            clusters[nearest].push(customer.clone());
+        }
+
+        depots
+            .into_iter()
+            .zip(clusters)
+            .map(|(depot, customers)| Vrp {
+                warehouse: depot.clone(),
+                customers,
+                other_depots: Vec::new(),
+                ..self.clone()
+            })
+            .collect()
+    }
+
+    /// Solve a multi-depot instance cluster first: solve the
+    /// [nearest depot clusters](Vrp::nearest_depot_clusters) with `heuristic` and put their
+    /// routes together, every route starts and ends at the depot of its cluster. The stats sum
+    /// those of every cluster, the cost histories are dropped. Single depot instances are
+    /// solved as is
+    pub fn solve_multi_depot(&self, heuristic: &dyn Heuristic) -> VrpResult {
+        if !self.is_multi_depot() {
+            return heuristic.solve(self);
+        }
+
+        let mut routes = Vec::new();
+        let mut unassigned = Vec::new();
+        let mut stats: Option<SolveStats> = None;
+
+        for cluster in self.nearest_depot_clusters() {
+            if cluster.customers.is_empty() {
+                continue;
+            }
+            let result = heuristic.solve(&cluster);
+            routes.extend(result.routes.into_iter().filter(|r| !r.is_empty()));
+            unassigned.extend(result.unassigned);
+            if let Some(cluster_stats) = result.stats {
+                *stats.get_or_insert_with(SolveStats::default) += cluster_stats;
+            }
+        }
+
+        VrpResult {
+            unassigned,
+            stats,
+            ..VrpResult::from_vrp(self, routes, None)
+        }
+    }
+}
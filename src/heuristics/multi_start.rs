@@ -0,0 +1,174 @@
+use crate::heuristics::registry::Stochastic;
+use crate::heuristics::stats::SolveStats;
+use crate::objective::Objective;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::thread;
+
+/// Outcome of one run of a [multi-start](Vrp::multi_start)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunSummary {
+    pub seed: u64,
+    pub cost: f32,
+    pub n_routes: usize,
+    /// [Key](Objective::key) of the solution of the run under its
+    /// [objective](VrpResult::objective)
+    pub key: (f32, f32),
+    pub stats: Option<SolveStats>,
+}
+
+/// The best solution of a [multi-start](Vrp::multi_start), with a summary of every run in run
+/// order
+#[derive(Debug, Clone)]
+pub struct MultiStartResult {
+    pub best: VrpResult,
+    pub runs: Vec<RunSummary>,
+}
+
+impl MultiStartResult {
+    /// Index of the run that found [best](MultiStartResult::best)
+    pub fn best_run(&self) -> Option<usize> {
+        self.runs
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| Objective::compare_keys(a.key, b.key))
+            .map(|(i, _)| i)
+    }
+
+    /// Print the runs to a Markdown table
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("| Run | Seed | Cost | Routes | Wall time (s) |\n");
+        output.push_str("|---|---|---|---|---|\n");
+        for (i, run) in self.runs.iter().enumerate() {
+            output.push_str(&format!(
+                "| {} | {} | {:.2} | {} | {:.3} |\n",
+                i + 1,
+                run.seed,
+                run.cost,
+                run.n_routes,
+                run.stats.map_or(0.0, |stats| stats.wall_time.as_secs_f64())
+            ));
+        }
+        output
+    }
+}
+
+/// Seeds of `n` runs derived from `master_seed` with SplitMix64, the same master seed always
+/// gives the same seeds and close master seeds give unrelated ones
+pub fn derive_seeds(master_seed: u64, n: usize) -> Vec<u64> {
+    let mut state = master_seed;
+    (0..n)
+        .map(|_| {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        })
+        .collect()
+}
+
+impl Vrp {
+    /// Run `heuristic` `n_runs` times and keep the best solution under the objective of the
+    /// instance. Run `i` is seeded with `seeds[i]`, runs without a seed draw one from the system.
+    /// Runs are spread over the available cores if `parallel`, the result is the same as the
+    /// sequential one for the same seeds unless a time limit stops the runs. The stats of the
+    /// best solution sum those of every run
+    pub fn multi_start<H: Stochastic>(
+        &self,
+        heuristic: &H,
+        n_runs: usize,
+        seeds: &[u64],
+        parallel: bool,
+    ) -> MultiStartResult {
+        let seeds: Vec<u64> = (0..n_runs)
+            .map(|i| seeds.get(i).copied().unwrap_or_else(rand::random))
+            .collect();
+
+        let run = |seed: u64| heuristic.with_seed(seed).solve(self);
+
+        let results: Vec<VrpResult> = if parallel && n_runs > 1 {
+            let workers = thread::available_parallelism()
+                .map_or(1, |n| n.get())
+                .min(n_runs);
+            // Worker w does the runs w, w + workers, ... so every worker gets about as many
+            let by_worker: Vec<Vec<(usize, VrpResult)>> = thread::scope(|scope| {
+                let handles: Vec<_> = (0..workers)
+                    .map(|w| {
+                        let seeds = &seeds;
+                        let run = &run;
+                        scope.spawn(move || {
+                            (w..n_runs)
+                                .step_by(workers)
+                                .map(|i| (i, run(seeds[i])))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("multi-start run panicked"))
+                    .collect()
+            });
+            let mut results: Vec<(usize, VrpResult)> = by_worker.into_iter().flatten().collect();
+            results.sort_by_key(|(i, _)| *i);
+            results.into_iter().map(|(_, result)| result).collect()
+        } else {
+            seeds.iter().map(|&seed| run(seed)).collect()
+        };
+
+        let runs: Vec<RunSummary> = results
+            .iter()
+            .zip(&seeds)
+            .map(|(result, &seed)| RunSummary {
+                seed,
+                cost: result.total_cost(),
+                n_routes: result.routes.len(),
+                key: result.objective.key(result),
+                stats: result.stats,
+            })
+            .collect();
+
+        let mut total = SolveStats::default();
+        for stats in runs.iter().filter_map(|run| run.stats) {
+            total += stats;
+        }
+
+        // The first of the best runs under the objective, as in run order
+        let best = results
+            .into_iter()
+            .reduce(|best, result| {
+                if result.is_better_than(&best) {
+                    result
+                } else {
+                    best
+                }
+            })
+            .map(|best| VrpResult {
+                stats: Some(total),
+                ..best
+            })
+            .unwrap_or_else(|| VrpResult::from_vrp(self, Vec::new(), None));
+
+        MultiStartResult { best, runs }
+    }
+
+    /// [Multi-start](Vrp::multi_start) whose runs are seeded by [derive_seeds] from
+    /// `master_seed`: the solutions are bit-for-bit the same for the same master seed, run in
+    /// parallel or not, unless a time limit stops the runs. Only the wall times differ
+    pub fn multi_start_deterministic<H: Stochastic>(
+        &self,
+        heuristic: &H,
+        n_runs: usize,
+        master_seed: u64,
+        parallel: bool,
+    ) -> MultiStartResult {
+        self.multi_start(
+            heuristic,
+            n_runs,
+            &derive_seeds(master_seed, n_runs),
+            parallel,
+        )
+    }
+}
@@ -1,47 +1,76 @@
+use crate::heuristics::stats::{SolveStats, Stopwatch};
 use crate::location::Location;
 use crate::route::Route;
+use crate::spatial_index::SpatialIndex;
 use crate::vrp::Vrp;
 use crate::vrp_result::VrpResult;
 
 impl Vrp {
     pub fn nearest_neighbour_heuristic(&self) -> VrpResult {
-        let mut customers: Vec<&Location> = self.customers.iter().collect();
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats {
+            solutions_evaluated: 1,
+            ..Default::default()
+        };
+
+        // Customers no vehicle can serve would never be picked, leave them unassigned
+        let (servable, mut unassigned): (Vec<&Location>, Vec<&Location>) =
+            self.customers.iter().partition(|c| self.can_serve_alone(c));
+        let mut customers = SpatialIndex::from_locations(servable);
 
         let mut routes: Vec<Route> = Vec::new();
 
         while !customers.is_empty() {
-            let mut route = Route {
-                warehouse: self.warehouse.clone(),
-                ..Default::default()
-            };
+            stats.iterations += 1;
+
+            let mut route = self.empty_route();
 
             let mut current = &route.warehouse;
 
             let mut cost = 0f32;
             let mut demand = current.demand;
-            let mut additional_cost: f32;
 
             loop {
-                (current, additional_cost, customers) = if let Some(a) = current
-                    .find_cheapest_deliverable(
-                        customers.clone(),
-                        cost,
-                        self.vehicle_capacity.saturating_sub(demand),
-                    ) {
-                    a
-                } else {
+                // Only the customers close enough to be reachable are checked
+                let mut nearby = customers.within(current, customers.max_due_date() as f32 - cost);
+                stats.feasibility_checks += nearby.len() as u64;
+                nearby.retain(|c| c.may_follow(current));
+                if self.max_route_duration.is_some() {
+                    nearby.retain(|c| self.fits_max_duration(c, current.cost_to_deliver(c, cost)));
+                }
+                nearby.retain(|c| self.fits_breaks(&route, c));
+
+                let Some((next, additional_cost)) = current.find_cheapest_deliverable_among(
+                    nearby,
+                    cost,
+                    (self.vehicle_capacity - demand).max(0.0),
+                ) else {
                     break;
                 };
 
+                customers.remove(next);
+
+                current = next;
                 cost += additional_cost;
                 demand += current.demand;
                 route.customers.push(current.clone());
             }
 
-            let route = route;
+            // A fresh route that takes no customer means none of the remaining ones can be
+            // served, stop instead of opening empty routes forever
+            if route.customers.is_empty() {
+                unassigned.extend(customers.remaining());
+                break;
+            }
 
             routes.push(route);
         }
-        VrpResult::from_vrp(self, routes, None)
+
+        stats.wall_time = stopwatch.elapsed();
+        VrpResult {
+            stats: Some(stats),
+            unassigned: unassigned.iter().map(|c| c.id).collect(),
+            ..VrpResult::from_vrp(self, routes, None)
+        }
     }
 }
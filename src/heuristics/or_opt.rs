@@ -0,0 +1,155 @@
+use crate::heuristics::local_search::{Move, Operator, MIN_IMPROVEMENT};
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp_result::VrpResult;
+use std::cell::Cell;
+
+/// Longest segment moved when no length is configured
+pub const DEFAULT_OR_OPT_SEGMENT_LEN: usize = 3;
+
+/// Move a segment of consecutive customers, in the same order, to its cheapest feasible position
+/// in its route or in another one (Or, 1976), as a
+/// [local search](crate::heuristics::local_search::LocalSearch) operator. Routes left empty are
+/// removed.
+///
+/// Like for [relocate](crate::heuristics::relocate::RelocateOperator), each scan resumes after
+/// the segment of the last move found
+#[derive(Debug)]
+pub struct OrOptOperator {
+    /// Most customers of a segment
+    pub max_segment_len: usize,
+    /// Position of the first customer of the next segment to try, counting the customers of all
+    /// routes
+    next: Cell<usize>,
+}
+
+impl OrOptOperator {
+    pub fn new(max_segment_len: usize) -> OrOptOperator {
+        OrOptOperator {
+            max_segment_len,
+            next: Cell::new(0),
+        }
+    }
+}
+
+impl Default for OrOptOperator {
+    fn default() -> Self {
+        OrOptOperator::new(DEFAULT_OR_OPT_SEGMENT_LEN)
+    }
+}
+
+impl Operator for OrOptOperator {
+    fn name(&self) -> &'static str {
+        "oropt"
+    }
+
+    fn improving_moves(&self, result: &VrpResult, visit: &mut dyn FnMut(Move) -> bool) -> u64 {
+        let mut checks = 0;
+
+        let starts: Vec<(usize, usize)> = result
+            .routes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, route)| (0..route.customers.len()).map(move |p| (index, p)))
+            .collect();
+        let first = self.next.get() % starts.len().max(1);
+
+        for k in (first..starts.len()).chain(0..first) {
+            let (index, start) = starts[k];
+            let route = &result.routes[index];
+
+            for len in 1..=self.max_segment_len.min(route.customers.len() - start) {
+                let mut source = route.clone();
+                let segment: Vec<Location> = source.customers.drain(start..start + len).collect();
+                let segment: Vec<&Location> = segment.iter().collect();
+
+                // Cost change of taking the segment out, an emptied route is removed
+                let removal = if source.customers.is_empty() {
+                    0.0
+                } else {
+                    source.total_cost()
+                } - result.route_cost(index);
+
+                // The cheapest route to take the segment, with the position and cost change
+                let best = result
+                    .routes
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(target, route)| {
+                        checks += 1;
+                        if target == index {
+                            let (at, cost) = cheapest_segment_insertion(
+                                &source,
+                                &segment,
+                                result.vehicle_capacity,
+                            )?;
+                            return Some((target, at, cost - result.route_cost(index)));
+                        }
+                        let (at, cost) =
+                            cheapest_segment_insertion(route, &segment, result.vehicle_capacity)?;
+                        Some((target, at, cost - result.route_cost(target) + removal))
+                    })
+                    .min_by(|a, b| a.2.total_cmp(&b.2));
+
+                let Some((target, at, delta)) = best else {
+                    continue;
+                };
+                if delta >= -MIN_IMPROVEMENT {
+                    continue;
+                }
+
+                let segment: Vec<Location> = segment.into_iter().cloned().collect();
+                let mv = if target == index {
+                    source.customers.splice(at..at, segment);
+                    Move {
+                        routes: vec![(index, source)],
+                        delta,
+                    }
+                } else {
+                    let mut route = result.routes[target].clone();
+                    route.customers.splice(at..at, segment);
+                    Move {
+                        routes: vec![(index, source), (target, route)],
+                        delta,
+                    }
+                };
+
+                self.next.set(k + 1);
+                if !visit(mv) {
+                    return checks;
+                }
+            }
+        }
+        checks
+    }
+}
+
+/// The cheapest feasible position of `segment` in `route`, with the cost of the route after
+/// insertion
+fn cheapest_segment_insertion(
+    route: &Route,
+    segment: &[&Location],
+    capacity: f64,
+) -> Option<(usize, f32)> {
+    // A position for the segment is also one for its first customer
+    if route.min_load_with(segment) > capacity || !route.can_possibly_insert(segment[0], capacity) {
+        return None;
+    }
+
+    let customers: Vec<&Location> = route.customers.iter().collect();
+    (0..=customers.len())
+        .filter_map(|i| {
+            let new_customers: Vec<&Location> = customers[..i]
+                .iter()
+                .chain(segment)
+                .chain(&customers[i..])
+                .copied()
+                .collect();
+
+            if !route.is_valid_with(&new_customers, capacity) {
+                return None;
+            }
+            Some((i, route.total_cost_with(&new_customers)))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
@@ -0,0 +1,109 @@
+use crate::heuristics::stats::{SolveStats, Stopwatch};
+use crate::location::{Location, Pairing};
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+
+impl Vrp {
+    /// Run the pair insertion heuristic for pickup and delivery problems (PDPTW).
+    ///
+    /// Requests, a pickup with its delivery or a customer without a pair, are inserted by
+    /// increasing due date of their first customer. A pair goes to the cheapest feasible pair of
+    /// positions of a single route, the pickup before the delivery, a new route is started when
+    /// no route can take the request. Customers a dedicated vehicle cannot serve with their
+    /// partner are left unassigned
+    pub fn pair_insertion_heuristic(&self) -> VrpResult {
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats {
+            solutions_evaluated: 1,
+            ..Default::default()
+        };
+
+        // Pairs are found from their pickup, deliveries are only served along with it
+        let mut requests: Vec<Vec<Location>> = self
+            .customers
+            .iter()
+            .filter(|c| !matches!(c.pairing, Some(Pairing::Delivery { .. })))
+            .map(|c| self.dedicated_route(c))
+            .filter(|route| route.is_valid(self.vehicle_capacity))
+            .map(|route| route.customers)
+            .collect();
+        requests.sort_by(|a, b| a[0].due_date.total_cmp(&b[0].due_date));
+
+        let unassigned: Vec<u16> = self
+            .customers
+            .iter()
+            .filter(|c| !requests.iter().flatten().any(|r| r.id == c.id))
+            .map(|c| c.id)
+            .collect();
+
+        let mut routes: Vec<Route> = Vec::new();
+
+        for request in requests {
+            stats.iterations += 1;
+
+            let best = routes
+                .iter()
+                .enumerate()
+                .filter_map(|(index, route)| {
+                    let (customers, cost) =
+                        self.cheapest_request_insertion(route, &request, &mut stats)?;
+                    Some((index, customers, cost - route.total_cost()))
+                })
+                .min_by(|a, b| a.2.total_cmp(&b.2));
+
+            match best {
+                Some((index, customers, _)) => routes[index].customers = customers,
+                None => routes.push(Route {
+                    customers: request,
+                    ..self.empty_route()
+                }),
+            }
+        }
+
+        stats.wall_time = stopwatch.elapsed();
+        VrpResult {
+            stats: Some(stats),
+            unassigned,
+            ..VrpResult::from_vrp(self, routes, None)
+        }
+    }
+
+    /// The cheapest feasible customers of `route` once `request` is inserted, keeping the order
+    /// of the request, with the cost of the route
+    fn cheapest_request_insertion(
+        &self,
+        route: &Route,
+        request: &[Location],
+        stats: &mut SolveStats,
+    ) -> Option<(Vec<Location>, f32)> {
+        let n = route.customers.len();
+        // Position of the first customer of the request, then of the second one once the
+        // first is inserted
+        let positions: Vec<(usize, Option<usize>)> = match request {
+            [_] => (0..=n).map(|i| (i, None)).collect(),
+            _ => (0..=n)
+                .flat_map(|i| (i + 1..=n + 1).map(move |j| (i, Some(j))))
+                .collect(),
+        };
+        stats.feasibility_checks += positions.len() as u64;
+
+        positions
+            .into_iter()
+            .filter_map(|(i, j)| {
+                let mut customers: Vec<&Location> = route.customers.iter().collect();
+                customers.insert(i, &request[0]);
+                if let Some(j) = j {
+                    customers.insert(j, &request[1]);
+                }
+
+                if !route.is_valid_with(&customers, self.vehicle_capacity) {
+                    return None;
+                }
+                let cost = route.total_cost_with(&customers);
+                Some((customers, cost))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(customers, cost)| (customers.into_iter().cloned().collect(), cost))
+    }
+}
@@ -0,0 +1,120 @@
+use crate::heuristics::registry::Heuristic;
+use crate::heuristics::stats::Stopwatch;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::thread;
+use std::time::Duration;
+
+/// Outcome of one heuristic of a [portfolio](Vrp::portfolio)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioEntry {
+    /// [Name](Heuristic::name) of the heuristic
+    pub name: &'static str,
+    pub cost: f32,
+    pub n_routes: usize,
+    /// Customers left unassigned by the heuristic
+    pub n_unassigned: usize,
+    /// Time the heuristic took, always zero on wasm where no clock is available
+    pub wall_time: Duration,
+}
+
+/// The best solution of a [portfolio](Vrp::portfolio), with an entry per heuristic in the order
+/// they were given
+#[derive(Debug, Clone)]
+pub struct PortfolioResult {
+    pub best: VrpResult,
+    /// Index of the heuristic that found [best](PortfolioResult::best), `None` for an empty
+    /// portfolio
+    pub best_index: Option<usize>,
+    pub entries: Vec<PortfolioEntry>,
+}
+
+impl PortfolioResult {
+    /// Print the comparison of the heuristics to a Markdown table, the best one in bold
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("| Heuristic | Cost | Routes | Unassigned | Wall time (s) |\n");
+        output.push_str("|---|---|---|---|---|\n");
+        for (i, entry) in self.entries.iter().enumerate() {
+            let name = if Some(i) == self.best_index {
+                format!("**{}**", entry.name)
+            } else {
+                entry.name.to_string()
+            };
+            output.push_str(&format!(
+                "| {} | {:.2} | {} | {} | {:.3} |\n",
+                name,
+                entry.cost,
+                entry.n_routes,
+                entry.n_unassigned,
+                entry.wall_time.as_secs_f64()
+            ));
+        }
+        output
+    }
+}
+
+impl Vrp {
+    /// Solve this instance with every heuristic of `heuristics` and keep the best solution:
+    /// the one leaving the fewest customers unassigned, then the best under the
+    /// [objective](Vrp::objective), then the first given. The heuristics run on a thread each if
+    /// `parallel`
+    pub fn portfolio(&self, heuristics: &[Box<dyn Heuristic>], parallel: bool) -> PortfolioResult {
+        let run = |heuristic: &dyn Heuristic| {
+            let stopwatch = Stopwatch::start();
+            let result = heuristic.solve(self);
+            (result, stopwatch.elapsed())
+        };
+
+        let results: Vec<(VrpResult, Duration)> = if parallel {
+            thread::scope(|scope| {
+                let handles: Vec<_> = heuristics
+                    .iter()
+                    .map(|heuristic| scope.spawn(|| run(heuristic.as_ref())))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().expect("portfolio heuristic panicked"))
+                    .collect()
+            })
+        } else {
+            heuristics
+                .iter()
+                .map(|heuristic| run(heuristic.as_ref()))
+                .collect()
+        };
+
+        let entries: Vec<PortfolioEntry> = heuristics
+            .iter()
+            .zip(&results)
+            .map(|(heuristic, (result, wall_time))| PortfolioEntry {
+                name: heuristic.name(),
+                cost: result.total_cost(),
+                n_routes: result.routes.len(),
+                n_unassigned: result.unassigned.len(),
+                wall_time: *wall_time,
+            })
+            .collect();
+
+        let best_index = entries
+            .iter()
+            .enumerate()
+            .min_by(|(i, a), (j, b)| {
+                a.n_unassigned
+                    .cmp(&b.n_unassigned)
+                    .then_with(|| self.objective.compare(&results[*i].0, &results[*j].0))
+            })
+            .map(|(i, _)| i);
+
+        let best = best_index
+            .and_then(|i| results.into_iter().nth(i))
+            .map(|(result, _)| result)
+            .unwrap_or_else(|| VrpResult::from_vrp(self, Vec::new(), None));
+
+        PortfolioResult {
+            best,
+            best_index,
+            entries,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+/// State of a solver after an iteration of its main loop, passed to the progress callback of the
+/// `_with_progress` variants of the heuristics, e.g.
+/// [aco_heuristic_with_progress](crate::vrp::Vrp::aco_heuristic_with_progress). Returning
+/// `ControlFlow::Break(())` from the callback stops the solver, which returns the best solution
+/// found so far
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterationInfo {
+    /// Iterations done, from 1
+    pub iteration: u64,
+    /// Cost of the best solution found so far, infinite before the first one
+    pub best_cost: f32,
+    /// Time since the solver started, always zero on wasm where no clock is available
+    pub elapsed: Duration,
+}
+
+/// A progress callback never stopping the solver
+pub(crate) fn no_progress(_: IterationInfo) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+}
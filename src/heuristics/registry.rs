@@ -0,0 +1,261 @@
+use crate::fleet::{FleetError, FleetPolicy};
+use crate::heuristics::aco::AcoParams;
+use crate::heuristics::cancellation::CancellationToken;
+use crate::heuristics::grasp::GraspParams;
+use crate::heuristics::progress::{no_progress, IterationInfo};
+use crate::heuristics::simulated_annealing::SaParams;
+use crate::heuristics::solomon_i1::I1Params;
+use crate::heuristics::workspace::SearchWorkspace;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::fmt;
+use std::ops::ControlFlow;
+use std::str::FromStr;
+
+/// A solver building a solution of an instance from scratch, so heuristics can be compared and
+/// picked at runtime. The parameters of a heuristic are the implementing value, shareable
+/// between threads
+pub trait Heuristic: Send + Sync {
+    /// Name of the heuristic, the one parsed by [HeuristicKind]
+    fn name(&self) -> &'static str;
+
+    fn solve(&self, vrp: &Vrp) -> VrpResult;
+
+    /// Same as [solve](Heuristic::solve), calling `progress` after every iteration of the main
+    /// loop of the heuristic, it may stop the run. Heuristics without a main loop never call it
+    fn solve_with_progress(
+        &self,
+        vrp: &Vrp,
+        progress: &mut dyn FnMut(IterationInfo) -> ControlFlow<()>,
+    ) -> VrpResult {
+        let _ = progress;
+        self.solve(vrp)
+    }
+
+    /// Same as [solve](Heuristic::solve), stopping after the current iteration of the main loop
+    /// once `token` is cancelled from another thread, returning the best solution found so far.
+    /// Heuristics without a main loop always run to the end
+    fn solve_cancellable(&self, vrp: &Vrp, token: &CancellationToken) -> VrpResult {
+        self.solve_with_progress(vrp, &mut token.progress())
+    }
+
+    /// Same as [solve](Heuristic::solve), keeping the solution within the vehicles of `vrp`
+    /// under `policy`, heuristics open a new route whenever no customer fits the current ones
+    fn solve_within_fleet(&self, vrp: &Vrp, policy: FleetPolicy) -> Result<VrpResult, FleetError> {
+        let result = policy.apply(self.solve(vrp));
+        policy.check(&result)?;
+        Ok(result)
+    }
+}
+
+/// A [Heuristic] drawing random numbers, whose runs differ unless seeded
+pub trait Stochastic: Heuristic {
+    /// The same heuristic, seeded with `seed`
+    fn with_seed(&self, seed: u64) -> Self
+    where
+        Self: Sized;
+}
+
+/// The [nearest neighbor heuristic](Vrp::nearest_neighbour_heuristic)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NearestNeighbor;
+
+impl Heuristic for NearestNeighbor {
+    fn name(&self) -> &'static str {
+        "nn"
+    }
+
+    fn solve(&self, vrp: &Vrp) -> VrpResult {
+        vrp.nearest_neighbour_heuristic()
+    }
+}
+
+/// The [pair insertion heuristic](Vrp::pair_insertion_heuristic)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PairInsertion;
+
+impl Heuristic for PairInsertion {
+    fn name(&self) -> &'static str {
+        "pdp"
+    }
+
+    fn solve(&self, vrp: &Vrp) -> VrpResult {
+        vrp.pair_insertion_heuristic()
+    }
+}
+
+/// The [sweep heuristic](Vrp::sweep_heuristic)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sweep;
+
+impl Heuristic for Sweep {
+    fn name(&self) -> &'static str {
+        "sweep"
+    }
+
+    fn solve(&self, vrp: &Vrp) -> VrpResult {
+        vrp.sweep_heuristic()
+    }
+}
+
+impl Heuristic for I1Params {
+    fn name(&self) -> &'static str {
+        "i1"
+    }
+
+    fn solve(&self, vrp: &Vrp) -> VrpResult {
+        vrp.solomon_i1_heuristic(self)
+    }
+}
+
+impl Heuristic for GraspParams {
+    fn name(&self) -> &'static str {
+        "grasp"
+    }
+
+    fn solve(&self, vrp: &Vrp) -> VrpResult {
+        vrp.grasp_heuristic(self)
+    }
+
+    fn solve_with_progress(
+        &self,
+        vrp: &Vrp,
+        progress: &mut dyn FnMut(IterationInfo) -> ControlFlow<()>,
+    ) -> VrpResult {
+        vrp.grasp_heuristic_with_progress(self, progress)
+    }
+}
+
+impl Heuristic for AcoParams {
+    fn name(&self) -> &'static str {
+        "aco"
+    }
+
+    fn solve(&self, vrp: &Vrp) -> VrpResult {
+        vrp.aco_heuristic(self)
+    }
+
+    fn solve_with_progress(
+        &self,
+        vrp: &Vrp,
+        progress: &mut dyn FnMut(IterationInfo) -> ControlFlow<()>,
+    ) -> VrpResult {
+        vrp.aco_heuristic_with_progress(self, &mut SearchWorkspace::new(), progress)
+    }
+}
+
+impl Heuristic for SaParams {
+    fn name(&self) -> &'static str {
+        "sa"
+    }
+
+    /// Anneal the nearest neighbor solution, the stats include its construction
+    fn solve(&self, vrp: &Vrp) -> VrpResult {
+        self.solve_with_progress(vrp, &mut no_progress)
+    }
+
+    fn solve_with_progress(
+        &self,
+        vrp: &Vrp,
+        progress: &mut dyn FnMut(IterationInfo) -> ControlFlow<()>,
+    ) -> VrpResult {
+        let initial = vrp.nearest_neighbour_heuristic();
+        let mut result = vrp.simulated_annealing_with_progress(&initial, self, progress);
+        if let (Some(total), Some(construction)) = (&mut result.stats, initial.stats) {
+            *total += construction;
+        }
+        result
+    }
+}
+
+impl Stochastic for GraspParams {
+    fn with_seed(&self, seed: u64) -> Self {
+        GraspParams {
+            seed: Some(seed),
+            ..self.clone()
+        }
+    }
+}
+
+impl Stochastic for AcoParams {
+    fn with_seed(&self, seed: u64) -> Self {
+        AcoParams {
+            seed: Some(seed),
+            ..self.clone()
+        }
+    }
+}
+
+impl Stochastic for SaParams {
+    fn with_seed(&self, seed: u64) -> Self {
+        SaParams {
+            seed: Some(seed),
+            ..self.clone()
+        }
+    }
+}
+
+/// Every [Heuristic] of the crate, by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeuristicKind {
+    NearestNeighbor,
+    SolomonI1,
+    Sweep,
+    Grasp,
+    Aco,
+    SimulatedAnnealing,
+    PairInsertion,
+}
+
+impl HeuristicKind {
+    pub const ALL: [HeuristicKind; 7] = [
+        HeuristicKind::NearestNeighbor,
+        HeuristicKind::SolomonI1,
+        HeuristicKind::Sweep,
+        HeuristicKind::Grasp,
+        HeuristicKind::Aco,
+        HeuristicKind::SimulatedAnnealing,
+        HeuristicKind::PairInsertion,
+    ];
+
+    /// The heuristic with its default parameters
+    pub fn with_defaults(self) -> Box<dyn Heuristic> {
+        match self {
+            HeuristicKind::NearestNeighbor => Box::new(NearestNeighbor),
+            HeuristicKind::SolomonI1 => Box::new(I1Params::default()),
+            HeuristicKind::Sweep => Box::new(Sweep),
+            HeuristicKind::Grasp => Box::new(GraspParams::default()),
+            HeuristicKind::Aco => Box::new(AcoParams::default()),
+            HeuristicKind::SimulatedAnnealing => Box::new(SaParams::default()),
+            HeuristicKind::PairInsertion => Box::new(PairInsertion),
+        }
+    }
+}
+
+impl fmt::Display for HeuristicKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HeuristicKind::NearestNeighbor => "nn",
+            HeuristicKind::SolomonI1 => "i1",
+            HeuristicKind::Sweep => "sweep",
+            HeuristicKind::Grasp => "grasp",
+            HeuristicKind::Aco => "aco",
+            HeuristicKind::SimulatedAnnealing => "sa",
+            HeuristicKind::PairInsertion => "pdp",
+        })
+    }
+}
+
+impl FromStr for HeuristicKind {
+    type Err = String;
+
+    /// Parse `nn`, `i1`, `sweep`, `grasp`, `aco`, `sa` or `pdp`. These are also the construction
+    /// steps of a [RunConfig](crate::config::RunConfig) pipeline, but for `sa`: that step anneals
+    /// the best result of the previous steps instead of the nearest neighbor solution
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HeuristicKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| format!("Unknown heuristic: {}", s))
+    }
+}
@@ -0,0 +1,94 @@
+use crate::heuristics::local_search::{Move, Operator};
+use crate::vrp_result::VrpResult;
+use std::cell::Cell;
+
+/// Move a single customer to its cheapest feasible position, in its route or in another one, as
+/// a [local search](crate::heuristics::local_search::LocalSearch) operator. Routes left empty are
+/// removed.
+///
+/// The customers are tried in order of their routes, each scan resumes after the customer of the
+/// last move found, so a first improvement search does not keep trying the first routes
+#[derive(Debug, Default)]
+pub struct RelocateOperator {
+    /// Position of the next customer to try, counting the customers of all routes
+    next: Cell<usize>,
+}
+
+impl Operator for RelocateOperator {
+    fn name(&self) -> &'static str {
+        "relocate"
+    }
+
+    fn improving_moves(&self, result: &VrpResult, visit: &mut dyn FnMut(Move) -> bool) -> u64 {
+        let mut checks = 0;
+
+        let customers: Vec<(usize, usize)> = result
+            .routes
+            .iter()
+            .enumerate()
+            .flat_map(|(index, route)| (0..route.customers.len()).map(move |p| (index, p)))
+            .collect();
+        let start = self.next.get() % customers.len().max(1);
+
+        for k in (start..customers.len()).chain(0..start) {
+            let (index, position) = customers[k];
+            let mut source = result.routes[index].clone();
+            let customer = source.customers.remove(position);
+            checks += result.routes.len() as u64;
+
+            // Cost change of taking the customer out, an emptied route is removed
+            let removal = if source.customers.is_empty() {
+                0.0
+            } else {
+                source.total_cost()
+            } - result.route_cost(index);
+
+            // The cheapest route to take the customer, with the position and cost change
+            let best = result
+                .routes
+                .iter()
+                .enumerate()
+                .filter_map(|(target, route)| {
+                    if target == index {
+                        let (at, cost) = source.cheapest_insertion_from(
+                            0,
+                            &customer,
+                            result.vehicle_capacity,
+                        )?;
+                        return Some((target, at, cost - result.route_cost(index)));
+                    }
+                    let (at, cost) =
+                        route.cheapest_insertion_from(0, &customer, result.vehicle_capacity)?;
+                    Some((target, at, cost - result.route_cost(target) + removal))
+                })
+                .min_by(|a, b| a.2.total_cmp(&b.2));
+
+            let Some((target, at, delta)) = best else {
+                continue;
+            };
+            let mv = if target == index {
+                source.customers.insert(at, customer);
+                Move {
+                    routes: vec![(index, source)],
+                    delta,
+                }
+            } else {
+                let mut route = result.routes[target].clone();
+                route.customers.insert(at, customer);
+                Move {
+                    routes: vec![(index, source), (target, route)],
+                    delta,
+                }
+            };
+
+            if !mv.is_improving() {
+                continue;
+            }
+            self.next.set(k + 1);
+            if !visit(mv) {
+                return checks;
+            }
+        }
+        checks
+    }
+}
@@ -0,0 +1,270 @@
+use crate::heuristics::checkpoint::{Checkpoint, CheckpointWriter};
+use crate::heuristics::local_search::Move;
+use crate::heuristics::progress::{no_progress, IterationInfo};
+use crate::heuristics::stats::{Deadline, SolveStats, Stopwatch};
+use crate::heuristics::trace::{IterationTrace, SearchTrace};
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+/// How the temperature of the [simulated annealing](Vrp::simulated_annealing) decreases after
+/// every temperature step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoolingSchedule {
+    /// Multiply the temperature by `factor`, between 0 and 1
+    Geometric { factor: f32 },
+    /// Subtract `step` from the temperature, positive
+    Linear { step: f32 },
+}
+
+impl CoolingSchedule {
+    fn cool(&self, temperature: f32) -> f32 {
+        match *self {
+            CoolingSchedule::Geometric { factor } => temperature * factor,
+            CoolingSchedule::Linear { step } => temperature - step,
+        }
+    }
+}
+
+/// Moves of the simulated annealing neighborhood, one is drawn at random for every candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaMove {
+    /// Move a customer to another position, in its route or in another one
+    Relocate,
+    /// Exchange two customers of different routes
+    Swap,
+    /// Reverse a segment of a route
+    TwoOpt,
+}
+
+/// Parameters for the simulated annealing heuristic
+#[derive(Debug, Clone)]
+pub struct SaParams {
+    /// Temperature of the first step, in cost units: a move costing this much more is accepted
+    /// with probability 1/e
+    pub initial_temperature: f32,
+    /// Stop once the temperature falls below this
+    pub final_temperature: f32,
+    pub cooling: CoolingSchedule,
+    /// Candidate moves tried at every temperature
+    pub iterations_per_temperature: u32,
+    /// Moves to draw from, uniformly. An empty list leaves the solution unchanged
+    pub moves: Vec<SaMove>,
+    /// Stop iterating once this much time has passed, even if the final temperature has not
+    /// been reached
+    pub time_limit: Option<Duration>,
+    /// Periodically write the best solution found so far to disk
+    pub checkpoint: Option<Checkpoint>,
+    /// Seed of the random number generator, the same seed always gives the same solution unless
+    /// the time limit stops the run first. `None` draws a seed from the system
+    pub seed: Option<u64>,
+    /// Collect the statistics of every iteration into the [trace](VrpResult::trace) of the
+    /// result
+    pub trace: bool,
+}
+
+impl Default for SaParams {
+    fn default() -> Self {
+        SaParams {
+            initial_temperature: 50.0,
+            final_temperature: 0.1,
+            cooling: CoolingSchedule::Geometric { factor: 0.95 },
+            iterations_per_temperature: 1000,
+            moves: vec![SaMove::Relocate, SaMove::Swap, SaMove::TwoOpt],
+            time_limit: None,
+            checkpoint: None,
+            seed: None,
+            trace: false,
+        }
+    }
+}
+
+impl Vrp {
+    /// Improve `initial` with simulated annealing: random moves are accepted if they lower the
+    /// cost, else with probability `exp(-delta / temperature)`, so the search escapes local
+    /// optima while the temperature is high. Moves breaking a constraint are never accepted.
+    ///
+    /// Returns the best solution found, with the best cost after every temperature step as its
+    /// [cost history](VrpResult::heuristic_cost_history). Routes emptied by moves are removed
+    pub fn simulated_annealing(&self, initial: &VrpResult, params: &SaParams) -> VrpResult {
+        self.simulated_annealing_with_progress(initial, params, &mut no_progress)
+    }
+
+    /// Same as [simulated_annealing](Vrp::simulated_annealing), calling `progress` after every
+    /// temperature step, it may stop the run
+    pub fn simulated_annealing_with_progress(
+        &self,
+        initial: &VrpResult,
+        params: &SaParams,
+        progress: &mut dyn FnMut(IterationInfo) -> ControlFlow<()>,
+    ) -> VrpResult {
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats::default();
+        // A seed makes the run reproducible, else draw one from the system
+        let mut rng = params
+            .seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+
+        let mut current = initial.clone();
+        current.refresh_cost();
+        let mut best = current.clone();
+        let mut best_cost_history: Vec<f32> = Vec::new();
+        let mut search_trace = params.trace.then(SearchTrace::default);
+
+        let deadline = Deadline::from_limit(params.time_limit);
+        let mut checkpoint = params.checkpoint.as_ref().map(CheckpointWriter::new);
+
+        let mut temperature = params.initial_temperature;
+        while temperature > params.final_temperature && !params.moves.is_empty() {
+            if deadline.is_reached() {
+                break;
+            }
+            stats.iterations += 1;
+            let (mut n_candidates, mut n_accepted) = (0_u32, 0_u32);
+
+            for _ in 0..params.iterations_per_temperature {
+                let mv = params.moves[rng.gen_range(0..params.moves.len())];
+                let Some(candidate) = random_neighbor(&current, mv, &mut rng) else {
+                    continue;
+                };
+                stats.solutions_evaluated += 1;
+                stats.feasibility_checks += candidate.routes.len() as u64;
+                n_candidates += 1;
+
+                if !candidate
+                    .routes
+                    .iter()
+                    .all(|(_, route)| route.is_valid(self.vehicle_capacity))
+                {
+                    continue;
+                }
+
+                let delta = candidate.delta;
+                if delta < 0.0 || rng.gen::<f32>() < (-delta / temperature).exp() {
+                    candidate.apply(&mut current);
+                    n_accepted += 1;
+
+                    if current.is_better_than(&best) {
+                        best = current.clone();
+                    }
+                }
+            }
+
+            best_cost_history.push(best.total_cost());
+
+            if let Some(search_trace) = &mut search_trace {
+                search_trace.push(IterationTrace {
+                    iteration: stats.iterations,
+                    best_cost: best.total_cost(),
+                    n_routes: best.routes.len(),
+                    average_cost: Some(current.total_cost()),
+                    diversity: None,
+                    acceptance_rate: (n_candidates > 0)
+                        .then(|| n_accepted as f32 / n_candidates as f32),
+                });
+            }
+
+            if let Some(checkpoint) = &mut checkpoint {
+                checkpoint.maybe_write(|| VrpResult {
+                    heuristic_cost_history: Some(best_cost_history.clone()),
+                    ..best.clone()
+                });
+            }
+
+            let info = IterationInfo {
+                iteration: stats.iterations,
+                best_cost: best.total_cost(),
+                elapsed: stopwatch.elapsed(),
+            };
+            if progress(info).is_break() {
+                break;
+            }
+
+            // A schedule that does not cool would never stop
+            let next = params.cooling.cool(temperature);
+            if next >= temperature {
+                break;
+            }
+            temperature = next;
+        }
+
+        stats.wall_time = stopwatch.elapsed();
+        best.refresh_cost();
+        let result = VrpResult {
+            heuristic_cost_history: Some(best_cost_history),
+            stats: Some(stats),
+            trace: search_trace,
+            ..best
+        };
+
+        if let Some(checkpoint) = &mut checkpoint {
+            checkpoint.write(&result);
+        }
+
+        result
+    }
+}
+
+/// Draw a random move of kind `mv` on `result`, `None` if the solution is too small for it.
+/// The move may break a constraint
+fn random_neighbor(result: &VrpResult, mv: SaMove, rng: &mut impl Rng) -> Option<Move> {
+    let routes = &result.routes;
+    if routes.is_empty() {
+        return None;
+    }
+    let a = rng.gen_range(0..routes.len());
+
+    match mv {
+        SaMove::Relocate => {
+            if routes[a].customers.is_empty() {
+                return None;
+            }
+            let b = rng.gen_range(0..routes.len());
+            let from = rng.gen_range(0..routes[a].customers.len());
+
+            let mut source = routes[a].clone();
+            let customer = source.customers.remove(from);
+
+            if a == b {
+                let to = rng.gen_range(0..=source.customers.len());
+                if to == from {
+                    return None;
+                }
+                source.customers.insert(to, customer);
+                return Some(Move::new(result, vec![(a, source)]));
+            }
+
+            let mut target = routes[b].clone();
+            let to = rng.gen_range(0..=target.customers.len());
+            target.customers.insert(to, customer);
+            Some(Move::new(result, vec![(a, source), (b, target)]))
+        }
+        SaMove::Swap => {
+            let b = rng.gen_range(0..routes.len());
+            if a == b || routes[a].customers.is_empty() || routes[b].customers.is_empty() {
+                return None;
+            }
+            let (mut first, mut second) = (routes[a].clone(), routes[b].clone());
+            let i = rng.gen_range(0..first.customers.len());
+            let j = rng.gen_range(0..second.customers.len());
+            std::mem::swap(&mut first.customers[i], &mut second.customers[j]);
+
+            Some(Move::new(result, vec![(a, first), (b, second)]))
+        }
+        SaMove::TwoOpt => {
+            let n = routes[a].customers.len();
+            if n < 2 {
+                return None;
+            }
+            let i = rng.gen_range(0..n - 1);
+            let j = rng.gen_range(i + 1..n);
+
+            let mut route = routes[a].clone();
+            route.customers[i..=j].reverse();
+            Some(Move::new(result, vec![(a, route)]))
+        }
+    }
+}
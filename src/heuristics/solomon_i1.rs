@@ -0,0 +1,169 @@
+use crate::heuristics::stats::{SolveStats, Stopwatch};
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+
+/// How the [I1 heuristic](Vrp::solomon_i1_heuristic) picks the first customer of a new route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SeedCriterion {
+    /// The unrouted customer farthest from the warehouse
+    #[default]
+    Farthest,
+    /// The unrouted customer with the earliest due date
+    EarliestDeadline,
+}
+
+/// Parameters of the Solomon I1 insertion heuristic.
+///
+/// The best position of a customer `u` between `i` and `j` minimizes
+/// `c1 = alpha1 * (d(i, u) + d(u, j) - mu * d(i, j)) + alpha2 * (push forward of the start of
+/// service at j)`, the customer inserted is the one maximizing `c2 = lambda * d(0, u) - c1`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct I1Params {
+    /// Weight of the distance saved by not going straight from `i` to `j`
+    pub mu: f32,
+    /// Preference for customers far from the warehouse, which are hard to insert later
+    pub lambda: f32,
+    /// Weight of the detour in `c1`
+    pub alpha1: f32,
+    /// Weight of the delay of the next customer in `c1`
+    pub alpha2: f32,
+    pub seed: SeedCriterion,
+}
+
+impl Default for I1Params {
+    /// One of the parameter sets of Solomon (1987)
+    fn default() -> Self {
+        I1Params {
+            mu: 1.0,
+            lambda: 1.0,
+            alpha1: 1.0,
+            alpha2: 0.0,
+            seed: SeedCriterion::Farthest,
+        }
+    }
+}
+
+impl Vrp {
+    /// Run the Solomon I1 sequential insertion heuristic, see [I1Params].
+    ///
+    /// Routes are built one at a time from a seed customer, inserting the best customer at its
+    /// best feasible position until no unrouted customer fits, then a new route is started
+    pub fn solomon_i1_heuristic(&self, params: &I1Params) -> VrpResult {
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats {
+            solutions_evaluated: 1,
+            ..Default::default()
+        };
+
+        // Customers no vehicle can serve would never be inserted, leave them unassigned
+        let (mut unrouted, unassigned): (Vec<&Location>, Vec<&Location>) =
+            self.customers.iter().partition(|c| self.can_serve_alone(c));
+
+        let mut routes: Vec<Route> = Vec::new();
+
+        while !unrouted.is_empty() {
+            stats.iterations += 1;
+
+            let seed = match params.seed {
+                SeedCriterion::Farthest => unrouted
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| {
+                        self.warehouse
+                            .distance_to(a.1)
+                            .total_cmp(&self.warehouse.distance_to(b.1))
+                    })
+                    .map(|(i, _)| i),
+                SeedCriterion::EarliestDeadline => unrouted
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, a), (_, b)| a.due_date.total_cmp(&b.due_date))
+                    .map(|(i, _)| i),
+            }
+            .expect("Unrouted customers are left");
+
+            let mut route = Route {
+                customers: vec![unrouted.swap_remove(seed).clone()],
+                ..self.empty_route()
+            };
+
+            // Best customer as its index in unrouted, its position in the route and c2
+            while let Some((index, position, _)) =
+                self.best_i1_insertion(&route, &unrouted, params, &mut stats)
+            {
+                route
+                    .customers
+                    .insert(position, unrouted.swap_remove(index).clone());
+            }
+
+            routes.push(route);
+        }
+
+        stats.wall_time = stopwatch.elapsed();
+        VrpResult {
+            stats: Some(stats),
+            unassigned: unassigned.iter().map(|c| c.id).collect(),
+            ..VrpResult::from_vrp(self, routes, None)
+        }
+    }
+
+    /// The unrouted customer with the largest `c2` at its position with the smallest `c1`, as
+    /// its index in `unrouted`, its position in `route` and `c2`
+    fn best_i1_insertion(
+        &self,
+        route: &Route,
+        unrouted: &[&Location],
+        params: &I1Params,
+        stats: &mut SolveStats,
+    ) -> Option<(usize, usize, f32)> {
+        let arcs = route.insertion_arcs();
+        unrouted
+            .iter()
+            .enumerate()
+            .filter(|(_, u)| route.min_load_with(&[u]) <= self.vehicle_capacity)
+            .filter_map(|(index, u)| {
+                stats.feasibility_checks += arcs.len() as u64;
+
+                let (position, c1) = arcs
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(position, arc)| {
+                        if !u.may_follow(arc.from) || !arc.to.may_follow(u) {
+                            return None;
+                        }
+                        let arrival = arc.arrival_through(u)?;
+                        // The arcs do not bound the end of open routes nor account for breaks,
+                        // check them once inserted
+                        if (route.open && route.max_duration.is_some()) || !route.breaks.is_empty()
+                        {
+                            let mut customers: Vec<&Location> = route.customers.iter().collect();
+                            customers.insert(position, u);
+                            if !route.is_valid_with(&customers, self.vehicle_capacity) {
+                                return None;
+                            }
+                        }
+
+                        let detour = arc.from.distance_to(u) + u.distance_to(arc.to)
+                            - params.mu * arc.from.distance_to(arc.to);
+                        // Push forward of the start of service at the end of the arc
+                        let ready = arc.to.ready_time as f32;
+                        let push_forward = arrival.max(ready) - arc.arrival.max(ready);
+
+                        Some((
+                            position,
+                            params.alpha1 * detour + params.alpha2 * push_forward,
+                        ))
+                    })
+                    .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+                Some((
+                    index,
+                    position,
+                    params.lambda * self.warehouse.distance_to(u) - c1,
+                ))
+            })
+            .max_by(|a, b| a.2.total_cmp(&b.2))
+    }
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::ops::AddAssign;
+use std::time::{Duration, Instant};
+
+/// The effort a solver spent to build a [VrpResult](crate::vrp_result::VrpResult)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolveStats {
+    /// Time between the start and the end of the solver, always zero on wasm where no clock is
+    /// available. In seconds in JSON
+    #[serde(with = "seconds")]
+    pub wall_time: Duration,
+    /// Iterations of the main loop of the solver: the routes opened by nearest neighbor, the
+    /// colony iterations of aco
+    pub iterations: u64,
+    /// Complete solutions built and costed
+    pub solutions_evaluated: u64,
+    /// Times a candidate customer was checked against capacity and time windows
+    pub feasibility_checks: u64,
+}
+
+impl AddAssign for SolveStats {
+    /// Sum every counter, for runs chaining several solvers
+    fn add_assign(&mut self, other: SolveStats) {
+        self.wall_time += other.wall_time;
+        self.iterations += other.iterations;
+        self.solutions_evaluated += other.solutions_evaluated;
+        self.feasibility_checks += other.feasibility_checks;
+    }
+}
+
+impl SolveStats {
+    /// Print these stats to a Markdown list
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format! {"- Wall time: {:.3} s\n", self.wall_time.as_secs_f64()});
+        output.push_str(&format! {"- Iterations: {}\n", self.iterations});
+        output.push_str(&format! {"- Solutions evaluated: {}\n", self.solutions_evaluated});
+        output.push_str(&format! {"- Feasibility checks: {}\n", self.feasibility_checks});
+        output
+    }
+}
+
+/// Write a [Duration] as a number of seconds, the way [SolveStats] are exported
+mod seconds {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+/// Measures the wall time of a solver, without reading the clock where `Instant` is not
+/// available (wasm)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    start: Instant,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Stopwatch {
+        Stopwatch {
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            start: Instant::now(),
+        }
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        return self.start.elapsed();
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        return Duration::ZERO;
+    }
+}
+
+/// The end of the time limit of a solver. The clock is only read with a limit, `Instant` is not
+/// available everywhere (wasm)
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// Start the time limit `limit` now, none is never reached
+    pub(crate) fn from_limit(limit: Option<Duration>) -> Deadline {
+        Deadline(limit.map(|limit| Instant::now() + limit))
+    }
+
+    pub(crate) fn is_reached(&self) -> bool {
+        self.0.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
@@ -0,0 +1,150 @@
+use crate::heuristics::local_search::{Move, Operator, MIN_IMPROVEMENT};
+use crate::location::Location;
+use crate::vrp_result::VrpResult;
+
+/// A 1-1 exchange: the customers at `first` and `second`, as (route index, position), trade
+/// places. See [best_swap](VrpResult::best_swap)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Swap {
+    pub first: (usize, usize),
+    pub second: (usize, usize),
+    /// Change of the total cost, negative for an improvement
+    pub delta: f32,
+}
+
+impl VrpResult {
+    /// Find the feasible exchange of two customers of different routes lowering the total cost
+    /// the most, `None` if no exchange lowers it
+    pub fn best_swap(&self) -> Option<Swap> {
+        (0..self.routes.len())
+            .flat_map(|a| (a + 1..self.routes.len()).map(move |b| (a, b)))
+            .filter_map(|(a, b)| self.best_swap_between(a, b))
+            .min_by(|x, y| x.delta.total_cmp(&y.delta))
+    }
+
+    /// Same as [best_swap](VrpResult::best_swap), only exchanging customers of the routes at
+    /// `a` and `b`
+    fn best_swap_between(&self, a: usize, b: usize) -> Option<Swap> {
+        let mut best: Option<Swap> = None;
+
+        let (first, second) = (&self.routes[a], &self.routes[b]);
+        let old_cost = self.route_cost(a) + self.route_cost(b);
+        let (demand_a, demand_b) = (first.total_demand(), second.total_demand());
+
+        let mut customers_a: Vec<&Location> = first.customers.iter().collect();
+        let mut customers_b: Vec<&Location> = second.customers.iter().collect();
+
+        for i in 0..customers_a.len() {
+            for j in 0..customers_b.len() {
+                let (u, v) = (customers_a[i], customers_b[j]);
+                // Capacity first, it rules out a swap without building the routes
+                if demand_a - u.demand + v.demand > self.vehicle_capacity
+                    || demand_b - v.demand + u.demand > self.vehicle_capacity
+                {
+                    continue;
+                }
+
+                customers_a[i] = v;
+                customers_b[j] = u;
+
+                let delta = first.total_cost_with(&customers_a)
+                    + second.total_cost_with(&customers_b)
+                    - old_cost;
+                if delta < best.map_or(-MIN_IMPROVEMENT, |best| best.delta)
+                    && first.is_valid_with(&customers_a, self.vehicle_capacity)
+                    && second.is_valid_with(&customers_b, self.vehicle_capacity)
+                {
+                    best = Some(Swap {
+                        first: (a, i),
+                        second: (b, j),
+                        delta,
+                    });
+                }
+
+                customers_a[i] = u;
+                customers_b[j] = v;
+            }
+        }
+        best
+    }
+
+    /// Exchange the customers of `swap`, as returned by [best_swap](VrpResult::best_swap)
+    pub fn apply_swap(&mut self, swap: &Swap) {
+        let ((a, i), (b, j)) = (swap.first, swap.second);
+        let mut first = self.routes[a].clone();
+        let mut second = self.routes[b].clone();
+        std::mem::swap(&mut first.customers[i], &mut second.customers[j]);
+
+        self.replace_route(a, first);
+        self.replace_route(b, second);
+    }
+
+    /// Apply the [best swap](VrpResult::best_swap) until no exchange lowers the cost, returns
+    /// whether the solution changed
+    pub fn improve_swap(&mut self) -> bool {
+        let n = self.routes.len();
+
+        // Best swap of every pair of routes, only the pairs of the routes of a swap change
+        let mut pairs: Vec<Option<Swap>> = vec![None; n * n];
+        for a in 0..n {
+            for b in a + 1..n {
+                pairs[a * n + b] = self.best_swap_between(a, b);
+            }
+        }
+
+        let mut changed = false;
+        while let Some(swap) = pairs
+            .iter()
+            .flatten()
+            .min_by(|x, y| x.delta.total_cmp(&y.delta))
+            .copied()
+        {
+            self.apply_swap(&swap);
+            changed = true;
+
+            for route in [swap.first.0, swap.second.0] {
+                for other in (0..n).filter(|&other| other != route) {
+                    let (a, b) = (route.min(other), route.max(other));
+                    pairs[a * n + b] = self.best_swap_between(a, b);
+                }
+            }
+        }
+        changed
+    }
+}
+
+/// [best_swap](VrpResult::best_swap) as a [local search](crate::heuristics::local_search::LocalSearch)
+/// operator, the best exchange of every pair of routes is a move
+pub struct SwapOperator;
+
+impl Operator for SwapOperator {
+    fn name(&self) -> &'static str {
+        "swap"
+    }
+
+    fn improving_moves(&self, result: &VrpResult, visit: &mut dyn FnMut(Move) -> bool) -> u64 {
+        let mut checks = 0;
+        for a in 0..result.routes.len() {
+            for b in a + 1..result.routes.len() {
+                checks +=
+                    (result.routes[a].customers.len() * result.routes[b].customers.len()) as u64;
+
+                let Some(swap) = result.best_swap_between(a, b) else {
+                    continue;
+                };
+                let ((a, i), (b, j)) = (swap.first, swap.second);
+                let (mut first, mut second) = (result.routes[a].clone(), result.routes[b].clone());
+                std::mem::swap(&mut first.customers[i], &mut second.customers[j]);
+
+                let mv = Move {
+                    routes: vec![(a, first), (b, second)],
+                    delta: swap.delta,
+                };
+                if !visit(mv) {
+                    return checks;
+                }
+            }
+        }
+        checks
+    }
+}
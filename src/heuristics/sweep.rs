@@ -0,0 +1,101 @@
+use crate::heuristics::stats::{SolveStats, Stopwatch};
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+impl Vrp {
+    /// Run the sweep heuristic, cluster first and route second.
+    ///
+    /// Customers are sorted by their polar angle around the warehouse, starting after the
+    /// widest empty angle so no natural cluster is cut in two. A ray sweeping them fills a
+    /// cluster until the next customer no longer fits in the vehicle, then the cluster is
+    /// sequenced by inserting its customers by due date at their cheapest feasible position.
+    /// Customers the time windows keep out of the route start the next cluster
+    pub fn sweep_heuristic(&self) -> VrpResult {
+        let stopwatch = Stopwatch::start();
+        let mut stats = SolveStats {
+            solutions_evaluated: 1,
+            ..Default::default()
+        };
+
+        // Customers no vehicle can serve could not be sequenced, leave them unassigned
+        let (mut servable, unassigned): (Vec<&Location>, Vec<&Location>) =
+            self.customers.iter().partition(|c| self.can_serve_alone(c));
+
+        let angle = |c: &Location| {
+            let angle = ((c.y as f32) - (self.warehouse.y as f32))
+                .atan2((c.x as f32) - (self.warehouse.x as f32));
+            angle.rem_euclid(TAU)
+        };
+        servable.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+
+        // Start the sweep after the widest gap between consecutive angles
+        let start = (0..servable.len())
+            .max_by(|&a, &b| {
+                let gap = |i: usize| {
+                    let previous = servable[(i + servable.len() - 1) % servable.len()];
+                    (angle(servable[i]) - angle(previous)).rem_euclid(TAU)
+                };
+                gap(a).total_cmp(&gap(b))
+            })
+            .unwrap_or(0);
+        let mut sweep: VecDeque<&Location> = servable[start..]
+            .iter()
+            .chain(&servable[..start])
+            .copied()
+            .collect();
+
+        let mut routes: Vec<Route> = Vec::new();
+
+        while !sweep.is_empty() {
+            stats.iterations += 1;
+
+            // -- Cluster first --
+            let mut cluster: Vec<&Location> = Vec::new();
+            let mut demand = 0.0;
+            while let Some(&next) = sweep.front() {
+                if !cluster.is_empty() && demand + next.demand > self.vehicle_capacity {
+                    break;
+                }
+                demand += next.demand;
+                cluster.push(next);
+                sweep.pop_front();
+            }
+
+            // -- Route second --
+            // Keep the sweep order of the rejected customers, they start the next cluster
+            let mut rejected: Vec<(usize, &Location)> = Vec::new();
+            let mut by_due_date: Vec<(usize, &Location)> =
+                cluster.iter().copied().enumerate().collect();
+            by_due_date.sort_by(|(_, a), (_, b)| a.due_date.total_cmp(&b.due_date));
+
+            // The first customer always fits, every customer can be served alone, so each cluster
+            // routes at least one customer
+            let mut route = self.empty_route();
+            for (order, customer) in by_due_date {
+                stats.feasibility_checks += route.customers.len() as u64 + 1;
+                match route.cheapest_insertion_from(0, customer, self.vehicle_capacity) {
+                    Some((position, _)) => route.customers.insert(position, customer.clone()),
+                    None => rejected.push((order, customer)),
+                }
+            }
+
+            rejected.sort_by_key(|&(order, _)| order);
+            for (_, customer) in rejected.into_iter().rev() {
+                sweep.push_front(customer);
+            }
+
+            routes.push(route);
+        }
+
+        stats.wall_time = stopwatch.elapsed();
+        VrpResult {
+            stats: Some(stats),
+            unassigned: unassigned.iter().map(|c| c.id).collect(),
+            ..VrpResult::from_vrp(self, routes, None)
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::vrp_result::VrpResult;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// What a solver did at every iteration of its main loop, collected when its params ask for it,
+/// to analyze the behavior of the search beyond the
+/// [cost history](VrpResult::heuristic_cost_history). Exported as the array of its iterations
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct SearchTrace {
+    pub iterations: Vec<IterationTrace>,
+}
+
+/// One iteration of a [SearchTrace], the statistics a solver does not have are `None`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct IterationTrace {
+    /// Iterations done, from 1
+    pub iteration: u64,
+    /// Cost of the best solution found so far
+    pub best_cost: f32,
+    /// Routes of the best solution found so far
+    pub n_routes: usize,
+    /// Average cost of the solutions of the iteration: the ants of aco, the improved
+    /// construction of grasp, the current solution of simulated annealing at the end of the
+    /// temperature step
+    pub average_cost: Option<f32>,
+    /// Share of the edges of the solutions of the iteration that the best of them does not use,
+    /// 0 when every solution is the same. Only for population based solvers like aco
+    pub diversity: Option<f32>,
+    /// Share of the candidate moves that were accepted, for simulated annealing
+    pub acceptance_rate: Option<f32>,
+}
+
+impl SearchTrace {
+    pub fn push(&mut self, iteration: IterationTrace) {
+        self.iterations.push(iteration);
+    }
+
+    /// Print this trace to a CSV string, with a header and empty cells for missing statistics
+    pub fn as_csv_string(&self) -> String {
+        let cell = |value: Option<f32>| value.map_or_else(String::new, |v| v.to_string());
+
+        let mut output =
+            String::from("iteration,best_cost,n_routes,average_cost,diversity,acceptance_rate\n");
+        for it in &self.iterations {
+            output.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                it.iteration,
+                it.best_cost,
+                it.n_routes,
+                cell(it.average_cost),
+                cell(it.diversity),
+                cell(it.acceptance_rate)
+            ));
+        }
+        output
+    }
+}
+
+/// Average cost of `solutions`, `None` if there are none
+pub(crate) fn average_cost(solutions: &[VrpResult]) -> Option<f32> {
+    if solutions.is_empty() {
+        return None;
+    }
+    Some(solutions.iter().map(VrpResult::total_cost).sum::<f32>() / solutions.len() as f32)
+}
+
+/// Share of the edges of `solutions` not used by `reference`, see
+/// [diversity](IterationTrace::diversity)
+pub(crate) fn diversity(solutions: &[VrpResult], reference: &VrpResult) -> f32 {
+    let reference_edges: HashSet<(u16, u16)> = edges(reference).collect();
+
+    let (mut n_edges, mut n_different) = (0_usize, 0_usize);
+    for solution in solutions {
+        for edge in edges(solution) {
+            n_edges += 1;
+            if !reference_edges.contains(&edge) {
+                n_different += 1;
+            }
+        }
+    }
+
+    if n_edges == 0 {
+        0.0
+    } else {
+        n_different as f32 / n_edges as f32
+    }
+}
+
+/// The edges between consecutive locations of the routes of `solution`, by id
+fn edges(solution: &VrpResult) -> impl Iterator<Item = (u16, u16)> + '_ {
+    solution.routes.iter().flat_map(|route| {
+        (0..route.len().saturating_sub(1)).map(|i| (route[i].id, route[i + 1].id))
+    })
+}
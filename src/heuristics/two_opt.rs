@@ -0,0 +1,86 @@
+use crate::heuristics::local_search::{Move, Operator, MIN_IMPROVEMENT};
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp_result::VrpResult;
+
+impl Route {
+    // -- Improve the route by reversing segments --
+    // Reverse the segment between two customers whenever it lowers the cost of the route and
+    // keeps it feasible, until no reversal does (first improvement). Reversing visits the
+    // customers in the other direction, so time windows usually rule out long segments.
+    // Returns whether the route changed
+    pub fn two_opt(&mut self, capacity: f64) -> bool {
+        let mut customers: Vec<&Location> = self.customers.iter().collect();
+        let mut cost = self.total_cost_with(&customers);
+        let mut changed = false;
+
+        let mut improved = true;
+        while improved {
+            improved = false;
+
+            for i in 0..customers.len() {
+                for j in i + 1..customers.len() {
+                    customers[i..=j].reverse();
+
+                    let new_cost = self.total_cost_with(&customers);
+                    if new_cost < cost - MIN_IMPROVEMENT && self.is_valid_with(&customers, capacity)
+                    {
+                        cost = new_cost;
+                        improved = true;
+                    } else {
+                        customers[i..=j].reverse();
+                    }
+                }
+            }
+            changed |= improved;
+        }
+
+        if changed {
+            self.customers = customers.into_iter().cloned().collect();
+        }
+        changed
+    }
+}
+
+impl VrpResult {
+    /// Apply [two_opt](Route::two_opt) to every route, returns whether any route changed
+    pub fn improve_2opt(&mut self) -> bool {
+        let mut changed = false;
+        for index in 0..self.routes.len() {
+            let mut route = self.routes[index].clone();
+            if route.two_opt(self.vehicle_capacity) {
+                self.replace_route(index, route);
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// [two_opt](Route::two_opt) as a [local search](crate::heuristics::local_search::LocalSearch)
+/// operator, a move is the descent of one route
+pub struct TwoOptOperator;
+
+impl Operator for TwoOptOperator {
+    fn name(&self) -> &'static str {
+        "2opt"
+    }
+
+    fn improving_moves(&self, result: &VrpResult, visit: &mut dyn FnMut(Move) -> bool) -> u64 {
+        let mut checks = 0;
+        for (index, route) in result.routes.iter().enumerate() {
+            let n = route.customers.len() as u64;
+            checks += n * n.saturating_sub(1) / 2;
+
+            let mut route = route.clone();
+            if !route.two_opt(result.vehicle_capacity) {
+                continue;
+            }
+            let mv = Move::new(result, vec![(index, route)]);
+            if mv.is_improving() && !visit(mv) {
+                break;
+            }
+        }
+        checks
+    }
+}
@@ -0,0 +1,34 @@
+/// Reusable scratch buffers for the inner loops of the heuristics.
+///
+/// Buffers are cleared, never shrunk, so after a few evaluations the inner loops stop allocating.
+/// Keep one workspace per thread and pass it to the `_with_workspace` variants of the heuristics,
+/// e.g. [aco_heuristic_with_workspace](crate::vrp::Vrp::aco_heuristic_with_workspace), to reuse
+/// it across runs.
+#[derive(Debug, Clone, Default)]
+pub struct SearchWorkspace {
+    /// Indices of the candidates that passed the feasibility checks
+    pub candidates: Vec<usize>,
+    /// X coordinates of the candidates
+    pub xs: Vec<f32>,
+    /// Y coordinates of the candidates
+    pub ys: Vec<f32>,
+    /// Distances from the current location to the candidates
+    pub distances: Vec<f32>,
+    /// Selection weights of the feasible candidates
+    pub probabilities: Vec<f32>,
+}
+
+impl SearchWorkspace {
+    pub fn new() -> SearchWorkspace {
+        SearchWorkspace::default()
+    }
+
+    /// Clear all buffers, keeping their allocations
+    pub fn clear(&mut self) {
+        self.candidates.clear();
+        self.xs.clear();
+        self.ys.clear();
+        self.distances.clear();
+        self.probabilities.clear();
+    }
+}
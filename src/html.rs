@@ -0,0 +1,167 @@
+use crate::book::{escape_html, STYLE};
+use crate::report::{ReportOptions, RouteOrder};
+use crate::vrp_result::VrpResult;
+use std::fs;
+use std::path::Path;
+
+/// Style of the zoomable charts, on top of the [style sheet](STYLE) of every page
+const ZOOM_STYLE: &str = "\
+    .zoomable svg { border: 1px solid #ccc; cursor: grab; max-width: 100%; height: auto; }\n\
+    .hint { color: #666; font-size: 0.9em; }\n";
+
+/// Zoom the charts of `.zoomable` blocks with the mouse wheel, pan them by dragging and reset
+/// them with a double click, by moving the view box of their SVG
+const ZOOM_SCRIPT: &str = r#"document.querySelectorAll('.zoomable svg').forEach(function (svg) {
+  var width = svg.width.baseVal.value, height = svg.height.baseVal.value;
+  var box = { x: 0, y: 0, w: width, h: height };
+  var drag = null;
+  function apply() {
+    svg.setAttribute('viewBox', box.x + ' ' + box.y + ' ' + box.w + ' ' + box.h);
+  }
+  svg.addEventListener('wheel', function (e) {
+    e.preventDefault();
+    var rect = svg.getBoundingClientRect();
+    var x = box.x + (e.clientX - rect.left) / rect.width * box.w;
+    var y = box.y + (e.clientY - rect.top) / rect.height * box.h;
+    var factor = Math.min(e.deltaY < 0 ? 0.8 : 1.25, width / box.w);
+    box = { x: x - (x - box.x) * factor, y: y - (y - box.y) * factor, w: box.w * factor, h: box.h * factor };
+    apply();
+  });
+  svg.addEventListener('mousedown', function (e) {
+    drag = { x: e.clientX, y: e.clientY };
+  });
+  window.addEventListener('mouseup', function () {
+    drag = null;
+  });
+  svg.addEventListener('mousemove', function (e) {
+    if (!drag) return;
+    var rect = svg.getBoundingClientRect();
+    box.x -= (e.clientX - drag.x) / rect.width * box.w;
+    box.y -= (e.clientY - drag.y) / rect.height * box.h;
+    drag = { x: e.clientX, y: e.clientY };
+    apply();
+  });
+  svg.addEventListener('dblclick', function () {
+    box = { x: 0, y: 0, w: width, h: height };
+    apply();
+  });
+});
+"#;
+
+impl VrpResult {
+    /// Print this solution to a self contained HTML page, with the default [ReportOptions]
+    pub fn as_html_string(&self) -> String {
+        self.as_html_string_with(&ReportOptions::default())
+    }
+
+    /// Print this solution to a self contained HTML page: its details, a zoomable map of the
+    /// routes and the cost history of the heuristic with the `plot` feature, then a table of
+    /// the schedule of every route, in the [order](ReportOptions::route_order) of the options.
+    ///
+    /// Charts are inlined SVG and the zoom a small script, the page needs no other file nor
+    /// network access
+    pub fn as_html_string_with(&self, options: &ReportOptions) -> String {
+        if options.route_order != RouteOrder::Construction {
+            return self
+                .with_route_order(options.route_order)
+                .as_html_string_with(&ReportOptions {
+                    route_order: RouteOrder::Construction,
+                    ..*options
+                });
+        }
+
+        let mut output = String::new();
+        output.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        output.push_str("<title>Vrp solution</title>\n");
+        output.push_str(&format! {"<style>\n{}{}</style>\n", STYLE, ZOOM_STYLE});
+        output.push_str("</head>\n<body>\n<h1>Vrp solution</h1>\n");
+
+        if !self.is_complete() {
+            output.push_str(&format! {
+                "<blockquote><strong>Partial solution</strong>: {} customers are not served: {}</blockquote>\n",
+                self.unassigned.len(),
+                self.unassigned.iter().map(u16::to_string).collect::<Vec<_>>().join(", ")
+            });
+        }
+
+        output.push_str("<h2>Details</h2>\n<ul>\n");
+        output.push_str(&format! {"<li>Total cost: {}</li>\n", self.total_cost()});
+        output.push_str(&format! {"<li>Total distance: {}</li>\n", self.total_distance()});
+        output.push_str(&format! {"<li>N° of routes: {}</li>\n", self.routes.len()});
+        output.push_str(&format! {"<li>N° of vehicles: {}</li>\n", self.n_vehicles});
+        output.push_str(&format! {"<li>Vehicle capacity: {}</li>\n", self.vehicle_capacity});
+        if let Some(stats) = &self.stats {
+            output.push_str(&format! {
+                "<li>Solving time: {:.3} s, {} solutions evaluated</li>\n",
+                stats.wall_time.as_secs_f32(),
+                stats.solutions_evaluated
+            });
+        }
+        output.push_str("</ul>\n");
+
+        #[cfg(feature = "plot")]
+        {
+            output.push_str("<h2>Map</h2>\n");
+            output.push_str(
+                "<p class=\"hint\">Scroll to zoom, drag to pan, double click to reset</p>\n",
+            );
+            output.push_str(&format! {
+                "<div class=\"zoomable\">\n{}</div>\n",
+                self.plot_with(options)
+            });
+
+            if let Some(chart) = self.plot_heuristic_cost_history() {
+                output.push_str("<h2>Heuristic Cost History</h2>\n");
+                output.push_str(&chart);
+            }
+        }
+
+        output.push_str("<h2>Routes</h2>\n");
+        for (i, route) in self.routes.iter().enumerate() {
+            output.push_str(&format! {"<h3>{}</h3>\n", escape_html(&self.route_name(i))});
+            output.push_str(&format! {
+                "<p>{} customers, load {} of {}, distance {:.2}, cost {:.2}, valid: {}</p>\n",
+                route.customers.len(),
+                route.total_demand(),
+                self.vehicle_capacity,
+                route.total_distance(),
+                self.route_cost(i),
+                route.is_valid(self.vehicle_capacity)
+            });
+
+            output.push_str("<table>\n<tr><th>#</th><th>Customer</th><th>Demand</th><th>Ready time</th><th>Due date</th><th>Arrival</th><th>Waiting</th><th>Start of service</th><th>Departure</th></tr>\n");
+            for (j, (customer, stop)) in route.customers.iter().zip(route.schedule()).enumerate() {
+                output.push_str(&format! {
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}</td></tr>\n",
+                    j + 1,
+                    customer.id,
+                    customer.demand,
+                    customer.ready_time,
+                    customer.due_date,
+                    stop.arrival,
+                    stop.waiting_time,
+                    stop.arrival + stop.waiting_time,
+                    stop.departure
+                });
+            }
+            output.push_str("</table>\n");
+
+            #[cfg(feature = "plot")]
+            if !route.customers.is_empty() {
+                output.push_str(&format! {
+                    "<details>\n<summary>Schedule</summary>\n<div class=\"zoomable\">\n{}</div>\n</details>\n",
+                    route.plot_schedule()
+                });
+            }
+        }
+
+        output.push_str(&format! {"<script>\n{}</script>\n", ZOOM_SCRIPT});
+        output.push_str("</body>\n</html>\n");
+        output
+    }
+
+    /// Write this solution to a [self contained HTML page](VrpResult::as_html_string) at `path`
+    pub fn export_html(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        fs::write(path, self.as_html_string())
+    }
+}
@@ -0,0 +1,337 @@
+use crate::distance::TravelMatrix;
+use crate::location::Location;
+use crate::route::{DriverBreak, Route};
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::str::FromStr;
+
+/// How travel times between locations are made integers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rounding {
+    Floor,
+    #[default]
+    Round,
+    Ceil,
+}
+
+impl FromStr for Rounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "floor" => Ok(Rounding::Floor),
+            "round" => Ok(Rounding::Round),
+            "ceil" => Ok(Rounding::Ceil),
+            other => Err(format!("Unknown rounding: {}", other)),
+        }
+    }
+}
+
+/// Largest time of an [integer instance](Vrp::with_integer_time), integers up to 2^24 are exact
+/// in f32
+pub const MAX_EXACT_TIME: u64 = 1 << 24;
+
+/// An integer time convention: travel times are euclidean distances rounded to integers, every
+/// computation is exact.
+///
+/// Times are multiplied by `scale` first, `Rounding::Floor` with a scale of 10 is the common
+/// convention of truncating distances to one decimal. Costs and schedules are in scaled units.
+/// Heuristics solve in this convention on the [integer instance](Vrp::with_integer_time)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegerTime {
+    pub rounding: Rounding,
+    pub scale: u32,
+}
+
+impl Default for IntegerTime {
+    fn default() -> Self {
+        IntegerTime {
+            rounding: Rounding::default(),
+            scale: 1,
+        }
+    }
+}
+
+/// Timing of the visit to one customer, in [IntegerTime] units
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntegerStop {
+    pub arrival: u64,
+    pub waiting_time: u64,
+    pub departure: u64,
+}
+
+impl IntegerTime {
+    /// Get the travel time between two locations, computed with an integer square root, or
+    /// rounded from their travel matrix if they have one
+    pub fn travel_time(&self, a: &Location, b: &Location) -> u64 {
+        if let Some((_, duration)) = a.matrix_travel_to(b) {
+            return self.round(f64::from(duration) * f64::from(self.scale));
+        }
+
+        let (dx, dy) = ((a.x - b.x).abs(), (a.y - b.y).abs());
+        // Only integer coordinates have an exact integer square root
+        if dx.fract() != 0.0 || dy.fract() != 0.0 || dx.max(dy) > f64::from(u16::MAX) {
+            return self.round(dx.hypot(dy) * f64::from(self.scale));
+        }
+
+        // At most 2^33 times 2^64, u128 never overflows
+        let (dx, dy) = (dx as u128, dy as u128);
+        let scale = u128::from(self.scale);
+        let squared = (dx * dx + dy * dy) * scale * scale;
+
+        let root = squared.isqrt();
+        let rounded = match self.rounding {
+            Rounding::Floor => root,
+            Rounding::Ceil => root + u128::from(root * root < squared),
+            // The root rounds up once squared > (root + 0.5)^2 = root^2 + root + 0.25
+            Rounding::Round => root + u128::from(squared > root * root + root),
+        };
+        // The root is below 2^49
+        rounded as u64
+    }
+
+    /// Convert a ready time, due date or service time, rounded like travel times unless it is
+    /// an integer
+    pub fn time(&self, time: f64) -> u64 {
+        self.round(time * f64::from(self.scale))
+    }
+
+    /// Negative values are 0 and values too large for a u64 are `u64::MAX`
+    fn round(&self, value: f64) -> u64 {
+        (match self.rounding {
+            Rounding::Floor => value.floor(),
+            Rounding::Ceil => value.ceil(),
+            Rounding::Round => value.round(),
+        }) as u64
+    }
+
+    /// Convert a time back to the units of the instance
+    pub fn to_instance_units(&self, time: u64) -> f64 {
+        time as f64 / f64::from(self.scale)
+    }
+}
+
+/// The [driver breaks](Route::breaks) of a route in integer time, taken like in
+/// [schedule](Route::schedule)
+struct IntegerBreaks {
+    /// The earliest start, latest start and duration of each break
+    breaks: Vec<(u64, u64, u64)>,
+    taken: usize,
+    late: bool,
+}
+
+impl IntegerBreaks {
+    fn new(route: &Route, time: &IntegerTime) -> IntegerBreaks {
+        IntegerBreaks {
+            breaks: route
+                .breaks
+                .iter()
+                .map(|b| {
+                    (
+                        time.time(b.earliest),
+                        time.time(b.latest),
+                        time.time(b.duration),
+                    )
+                })
+                .collect(),
+            taken: 0,
+            late: false,
+        }
+    }
+
+    /// Take the breaks due before a leg ending at `end(departure)`, returns the new departure
+    fn depart(&mut self, mut departure: u64, end: impl Fn(u64) -> u64) -> u64 {
+        while let Some(&(earliest, latest, duration)) = self.breaks.get(self.taken) {
+            if end(departure) <= latest {
+                break;
+            }
+            let start = departure.max(earliest);
+            self.late |= start > latest;
+            self.taken += 1;
+            departure = start.saturating_add(duration);
+        }
+        departure
+    }
+}
+
+impl Route {
+    /// Get the arrival, waiting and departure times at each customer in integer time, leaving
+    /// the warehouse at 0, see [schedule](Route::schedule)
+    pub fn integer_schedule(&self, time: &IntegerTime) -> Vec<IntegerStop> {
+        self.integer_walk(time).0
+    }
+
+    /// Get the schedule in integer time, whether a break was taken too late and the end of the
+    /// route: the return to the warehouse, or leaving the last customer of open routes
+    fn integer_walk(&self, time: &IntegerTime) -> (Vec<IntegerStop>, bool, u64) {
+        let mut stops = Vec::with_capacity(self.customers.len());
+        let mut breaks = IntegerBreaks::new(self, time);
+        let mut cost = 0;
+
+        for i in 1..self.len() - 1 {
+            let (previous, customer) = (&self[i - 1], &self[i]);
+            let travel = time.travel_time(previous, customer);
+            let ready = time.time(customer.ready_time);
+            let service = time.time(customer.service_time);
+
+            // Sums saturate, a saturated time is late for any due date
+            cost = breaks.depart(cost, |d| {
+                d.saturating_add(travel).max(ready).saturating_add(service)
+            });
+            let arrival = cost.saturating_add(travel);
+            let waiting_time = ready.saturating_sub(arrival);
+            cost = arrival.max(ready).saturating_add(service);
+
+            stops.push(IntegerStop {
+                arrival,
+                waiting_time,
+                departure: cost,
+            });
+        }
+
+        if let (Some(last), false) = (self.customers.last(), self.open) {
+            let travel = time.travel_time(last, &self.warehouse);
+            cost = breaks.depart(cost, |d| d.saturating_add(travel));
+            cost = cost.saturating_add(travel);
+        }
+
+        (stops, breaks.late, cost)
+    }
+
+    /// Get the cost of this route in integer time, see [total_cost](Route::total_cost)
+    pub fn integer_cost(&self, time: &IntegerTime) -> u64 {
+        self.integer_walk(time).2
+    }
+
+    /// Check the route in integer time, see [is_valid](Route::is_valid). Arriving exactly at a
+    /// due date is on time
+    pub fn is_valid_integer(&self, capacity: f64, time: &IntegerTime) -> bool {
+        if !self.is_load_valid(capacity) {
+            return false;
+        }
+
+        let (schedule, late_break, end) = self.integer_walk(time);
+
+        if self
+            .customers
+            .iter()
+            .zip(&schedule)
+            .any(|(customer, stop)| stop.arrival > time.time(customer.due_date))
+        {
+            return false;
+        }
+
+        // Open routes end at their last customer, the warehouse closing does not bound them
+        !late_break
+            && (self.open || end <= time.time(self.warehouse.due_date))
+            && self.max_duration.is_none_or(|max| end <= time.time(max))
+    }
+}
+
+impl VrpResult {
+    /// Get the total cost of all routes in integer time
+    pub fn integer_cost(&self, time: &IntegerTime) -> u64 {
+        self.routes
+            .iter()
+            .map(|route| route.integer_cost(time))
+            .fold(0, u64::saturating_add)
+    }
+
+    /// Whether every route is valid in integer time
+    pub fn is_valid_integer(&self, time: &IntegerTime) -> bool {
+        self.routes
+            .iter()
+            .all(|route| route.is_valid_integer(self.vehicle_capacity, time))
+    }
+}
+
+impl Vrp {
+    /// The same instance in the integer convention of `time`, in its scaled units: every travel
+    /// time is read from a [travel matrix](Vrp::with_travel_matrix) of
+    /// [integer travel times](IntegerTime::travel_time), which are the distances too, and every
+    /// time window, service time, max duration and break is [converted](IntegerTime::time).
+    ///
+    /// Sums of integers below 2^24 are exact in f32, so the heuristics check feasibility and
+    /// cost exactly in this convention. Map their solutions back to this instance with
+    /// [with_instance](VrpResult::with_instance).
+    ///
+    /// Returns an error if a scaled time or travel time is over [MAX_EXACT_TIME], the
+    /// heuristics would compare costs rounded to more than their improvement threshold
+    pub fn with_integer_time(&self, time: &IntegerTime) -> Result<Vrp, String> {
+        let locations: Vec<&Location> = self.locations().chain(&self.other_depots).collect();
+        let travel_times: Vec<Vec<u64>> = locations
+            .iter()
+            .map(|&a| locations.iter().map(|&b| time.travel_time(a, b)).collect())
+            .collect();
+
+        // Infinite times stay infinite
+        let convert = |value: f64| match value.is_finite() {
+            true => time.time(value) as f64,
+            false => value,
+        };
+        let convert_location = |location: &Location| Location {
+            ready_time: convert(location.ready_time),
+            due_date: convert(location.due_date),
+            service_time: convert(location.service_time),
+            ..location.clone()
+        };
+        let vrp = Vrp {
+            warehouse: convert_location(&self.warehouse),
+            customers: self.customers.iter().map(convert_location).collect(),
+            other_depots: self.other_depots.iter().map(convert_location).collect(),
+            max_route_duration: self.max_route_duration.map(convert),
+            driver_breaks: self
+                .driver_breaks
+                .iter()
+                .map(|b| DriverBreak {
+                    earliest: convert(b.earliest),
+                    latest: convert(b.latest),
+                    duration: convert(b.duration),
+                })
+                .collect(),
+            ..self.clone()
+        };
+
+        let times = vrp
+            .locations()
+            .chain(&vrp.other_depots)
+            .flat_map(|location| {
+                [
+                    location.ready_time,
+                    location.due_date,
+                    location.service_time,
+                ]
+            });
+        let breaks = vrp
+            .driver_breaks
+            .iter()
+            .flat_map(|b| [b.earliest, b.latest, b.duration]);
+        let largest = times
+            .chain(breaks)
+            .chain(vrp.max_route_duration)
+            .filter(|value| value.is_finite())
+            .map(|value| value as u64)
+            .chain(travel_times.iter().flatten().copied())
+            .max()
+            .unwrap_or(0);
+        if largest > MAX_EXACT_TIME {
+            return Err(format!(
+                "Integer times up to {} with scale {} are over {}, use a smaller scale",
+                largest, time.scale, MAX_EXACT_TIME
+            ));
+        }
+
+        let matrix = TravelMatrix::explicit(
+            travel_times
+                .into_iter()
+                .map(|row| row.into_iter().map(|t| t as f32).collect())
+                .collect(),
+            None,
+        )
+        .expect("The matrix is square by construction");
+
+        Ok(vrp
+            .with_travel_matrix(matrix)
+            .expect("The matrix has a row for every location"))
+    }
+}
@@ -0,0 +1,134 @@
+/// Write the distance from `(x, y)` to every `(xs[i], ys[i])` into `out[i]`
+pub fn distances_from(x: f32, y: f32, xs: &[f32], ys: &[f32], out: &mut [f32]) {
+    assert!(xs.len() == ys.len() && xs.len() == out.len());
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    let start = sse::distances_from(x, y, xs, ys, out);
+
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    let start = 0;
+
+    for i in start..xs.len() {
+        let dx = xs[i] - x;
+        let dy = ys[i] - y;
+        out[i] = (dx * dx + dy * dy).sqrt();
+    }
+}
+
+/// Push the index of every candidate `i` reachable from `(x, y)` at `current_cost` before
+/// `due_dates[i]` and whose `demands[i]` fits in `remaining_capacity` into `out`, in increasing
+/// order
+#[allow(clippy::too_many_arguments)]
+pub fn filter_deliverable(
+    x: f32,
+    y: f32,
+    current_cost: f32,
+    remaining_capacity: f64,
+    xs: &[f32],
+    ys: &[f32],
+    due_dates: &[f32],
+    demands: &[f64],
+    out: &mut Vec<usize>,
+) {
+    assert!(xs.len() == ys.len() && xs.len() == due_dates.len() && xs.len() == demands.len());
+
+    #[cfg(all(feature = "simd", target_arch = "x86_64"))]
+    let start = sse::filter_deliverable(
+        x,
+        y,
+        current_cost,
+        remaining_capacity,
+        xs,
+        ys,
+        due_dates,
+        demands,
+        out,
+    );
+
+    #[cfg(not(all(feature = "simd", target_arch = "x86_64")))]
+    let start = 0;
+
+    for i in start..xs.len() {
+        let dx = xs[i] - x;
+        let dy = ys[i] - y;
+
+        if due_dates[i] >= current_cost + (dx * dx + dy * dy).sqrt()
+            && demands[i] <= remaining_capacity
+        {
+            out.push(i);
+        }
+    }
+}
+
+// SSE2 paths handling four candidates at a time, SSE2 is available on every x86_64 CPU
+#[cfg(all(feature = "simd", target_arch = "x86_64"))]
+mod sse {
+    use std::arch::x86_64::*;
+
+    /// Process all full chunks of four, returns the index of the first unprocessed candidate
+    pub fn distances_from(x: f32, y: f32, xs: &[f32], ys: &[f32], out: &mut [f32]) -> usize {
+        let n_chunks = xs.len() / 4;
+
+        // SAFETY: SSE2 is part of the x86_64 baseline, loads and stores are unaligned and stay
+        // within the first `n_chunks * 4` elements of each slice
+        unsafe {
+            let x = _mm_set1_ps(x);
+            let y = _mm_set1_ps(y);
+
+            for chunk in 0..n_chunks {
+                let i = chunk * 4;
+                let dx = _mm_sub_ps(_mm_loadu_ps(xs.as_ptr().add(i)), x);
+                let dy = _mm_sub_ps(_mm_loadu_ps(ys.as_ptr().add(i)), y);
+                let squared = _mm_add_ps(_mm_mul_ps(dx, dx), _mm_mul_ps(dy, dy));
+
+                _mm_storeu_ps(out.as_mut_ptr().add(i), _mm_sqrt_ps(squared));
+            }
+        }
+
+        n_chunks * 4
+    }
+
+    /// Process all full chunks of four, returns the index of the first unprocessed candidate
+    #[allow(clippy::too_many_arguments)]
+    pub fn filter_deliverable(
+        x: f32,
+        y: f32,
+        current_cost: f32,
+        remaining_capacity: f64,
+        xs: &[f32],
+        ys: &[f32],
+        due_dates: &[f32],
+        demands: &[f64],
+        out: &mut Vec<usize>,
+    ) -> usize {
+        let n_chunks = xs.len() / 4;
+
+        // SAFETY: see distances_from
+        unsafe {
+            let x = _mm_set1_ps(x);
+            let y = _mm_set1_ps(y);
+            let current_cost = _mm_set1_ps(current_cost);
+
+            for chunk in 0..n_chunks {
+                let i = chunk * 4;
+                let dx = _mm_sub_ps(_mm_loadu_ps(xs.as_ptr().add(i)), x);
+                let dy = _mm_sub_ps(_mm_loadu_ps(ys.as_ptr().add(i)), y);
+                let distance = _mm_sqrt_ps(_mm_add_ps(_mm_mul_ps(dx, dx), _mm_mul_ps(dy, dy)));
+                let arrival = _mm_add_ps(current_cost, distance);
+
+                let reachable = _mm_movemask_ps(_mm_cmpge_ps(
+                    _mm_loadu_ps(due_dates.as_ptr().add(i)),
+                    arrival,
+                ));
+
+                for lane in 0..4 {
+                    if reachable & (1 << lane) != 0 && demands[i + lane] <= remaining_capacity {
+                        out.push(i + lane);
+                    }
+                }
+            }
+        }
+
+        n_chunks * 4
+    }
+}
@@ -1,20 +1,133 @@
 //! This crate defines classes to model the VRPTW and provides a parser for Solomon VRP instances
 //! It also implements various heuristics that can be used on the VRPTW
 
+/// Random feasible locations, routes and instances for property tests
+#[cfg(feature = "proptest-support")]
+pub mod arbitrary;
+
+/// Standardized micro-benchmarks to measure performance regressions
+#[cfg(feature = "bench")]
+pub mod bench;
+
+/// One report covering the instances of a batch run, as Markdown or HTML
+pub mod book;
+
+/// Stable one line text format of solutions, for snapshot tests and diffs
+pub mod canonical;
+
+/// Solutions stored as location indices, for large populations of candidates
+pub mod compact;
+
+/// Solver run settings, loadable from TOML files with the `config` feature
+pub mod config;
+
+/// Vehicle departures, returns and loading dock occupancy at the depot over time
+pub mod depot;
+
+/// Detect instance problems that rule out a feasible solution
+pub mod diagnostics;
+
+/// Travel distance and duration providers, great-circle distances for geographic coordinates
+/// and road networks with the `road` feature
+pub mod distance;
+
+/// Add, remove and update the customers of instances
+pub mod edit;
+
+/// Explain why customers are served by their route
+pub mod explain;
+
+/// Export the distance matrix and neighbor lists of instances for external tools
+pub mod export;
+
 /// Parse solomon VRPTW txt files
 pub mod file_parser;
 
+/// Giant tour encoding of solutions and the split algorithm cutting it into routes
+pub mod giant_tour;
+
+/// Hold solutions to the number of vehicles of their instance
+pub mod fleet;
+
+/// Generate random VRPTW instances
+pub mod generator;
+
 /// Various heuristic that can be used on the [Vrp](vrp::Vrp) object
 pub mod heuristics;
 
+/// Self contained HTML pages of solutions, with zoomable maps
+pub mod html;
+
+/// Exact integer time evaluation of solutions, for integer benchmark conventions
+pub mod integer_time;
+
+/// Batched distance and feasibility kernels, SIMD accelerated with the `simd` feature
+pub mod kernels;
+
 /// Represents individual locations in the VRP
 pub mod location;
 
+/// Stores [Location](location::Location) data as parallel arrays for tight evaluation loops
+pub mod location_table;
+
+/// Rank solutions by cost, distance or number of vehicles
+pub mod objective;
+
+/// Preprocessing passes that simplify instances before solving
+pub mod preprocess;
+
+/// Relatedness of customers in space, time and demand, and the Shaw removal built on it
+pub mod relatedness;
+
+/// Monte-Carlo evaluation of solutions under travel and service time noise
+pub mod robustness;
+
+/// Options making solution reports reproducible
+pub mod report;
+
 /// Represents vehicle routes in the VRP, contains [Location](location::Location) objects
 pub mod route;
 
+/// Change the coordinate and time units of instances
+pub mod scaling;
+
+/// Plan routes over sets of demand scenarios and evaluate their expected cost
+pub mod scenarios;
+
+/// REST API running solver jobs in the background, needs the `server` feature
+#[cfg(feature = "server")]
+pub mod server;
+
+/// Record the solutions of a program run and the improvements of the best known one
+pub mod session;
+
+/// Solution files of the SINTEF best known solutions, to submit and validate solutions
+pub mod sintef;
+
+/// One call solving with ready made settings, for users who do not tune heuristics
+pub mod solve;
+
+/// Spatial index over [Location](location::Location) coordinates, speeds up reachability queries
+pub mod spatial_index;
+
+/// Descriptive statistics of instances and solomon class detection
+pub mod statistics;
+
+/// Capacity use of solutions and the minimum fleet size of instances
+pub mod utilization;
+
+/// Named vehicles of instances and their assignment to the routes of solutions
+pub mod vehicle;
+
+/// Departure times of solutions leaving the depot as late as possible to cut waiting
+pub mod waiting;
+
 /// Represents a full VRPTW, contains [Location](location::Location) objects
 pub mod vrp;
 
+/// Import instances of the VRP-REP XML schema, needs the `quick-xml` feature
+#[cfg(feature = "quick-xml")]
+pub mod vrp_rep;
+
 /// Represents a solution to a VRPTW, contains [Route](route::Route) objects
 pub mod vrp_result;
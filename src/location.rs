@@ -1,39 +1,290 @@
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+use crate::distance::MatrixIndex;
+#[cfg(feature = "simd")]
+use crate::kernels;
+use crate::spatial_index::SpatialIndex;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "simd")]
+use std::cell::RefCell;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// The role of a location in an instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationKind {
+    /// Where vehicles start and end their routes
+    Depot,
+    #[default]
+    Customer,
+    /// A customer whose demand is picked up and brought back to the depot, served after every
+    /// customer delivered from the depot on its route
+    Backhaul,
+    /// Where electric vehicles can recharge, these have no demand
+    RechargingStation,
+}
+
+impl fmt::Display for LocationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LocationKind::Depot => "depot",
+            LocationKind::Customer => "customer",
+            LocationKind::Backhaul => "backhaul",
+            LocationKind::RechargingStation => "recharging_station",
+        })
+    }
+}
+
+impl FromStr for LocationKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "depot" => Ok(LocationKind::Depot),
+            "customer" => Ok(LocationKind::Customer),
+            "backhaul" => Ok(LocationKind::Backhaul),
+            "recharging_station" => Ok(LocationKind::RechargingStation),
+            other => Err(format!("Unknown location kind: {}", other)),
+        }
+    }
+}
+
+/// The other customer of a pickup and delivery pair (PDPTW), by id. The vehicle loads the
+/// demand at the pickup and unloads it at the delivery, so both are served by the same route,
+/// the pickup first
+///
+/// In JSON a pickup names its `delivery` and a delivery its `pickup`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Pairing {
+    Pickup { delivery: u16 },
+    Delivery { pickup: u16 },
+}
+
+/// Service priority of a customer, from the most to the least important tier
+///
+/// Heuristics serve higher tiers first. When the fleet is too small for all customers,
+/// [repair_fleet](crate::vrp_result::VrpResult::repair_fleet) may leave `High` and `Low`
+/// customers unassigned, at the cost of their [drop penalty](DropPenalties)
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Never dropped to save a vehicle
+    #[default]
+    MustServe,
+    High,
+    Low,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Priority::MustServe => "must_serve",
+            Priority::High => "high",
+            Priority::Low => "low",
+        })
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "must_serve" => Ok(Priority::MustServe),
+            "high" => Ok(Priority::High),
+            "low" => Ok(Priority::Low),
+            other => Err(format!("Unknown priority: {}", other)),
+        }
+    }
+}
+
+/// Cost added for every dropped customer of each droppable [Priority] tier
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DropPenalties {
+    pub high: f32,
+    pub low: f32,
+}
+
+impl Default for DropPenalties {
+    fn default() -> Self {
+        DropPenalties {
+            high: 1000.0,
+            low: 100.0,
+        }
+    }
+}
+
+impl DropPenalties {
+    /// Get the penalty of dropping a customer of `priority`, `None` if it cannot be dropped
+    pub fn of(&self, priority: Priority) -> Option<f32> {
+        match priority {
+            Priority::MustServe => None,
+            Priority::High => Some(self.high),
+            Priority::Low => Some(self.low),
+        }
+    }
+}
+
+/// Columns of the candidates of the simd [find_deliverable](Location::find_deliverable) and the
+/// indices it keeps, reused across calls so the kernel does not allocate
+#[cfg(feature = "simd")]
+#[derive(Default)]
+struct DeliverableWorkspace {
+    xs: Vec<f32>,
+    ys: Vec<f32>,
+    due_dates: Vec<f32>,
+    demands: Vec<f64>,
+    deliverable: Vec<usize>,
+}
+
+#[cfg(feature = "simd")]
+impl DeliverableWorkspace {
+    /// Replace the columns with the ones of `others`
+    fn fill(&mut self, others: &[&Location]) {
+        self.xs.clear();
+        self.ys.clear();
+        self.due_dates.clear();
+        self.demands.clear();
+        self.deliverable.clear();
+
+        for other in others {
+            self.xs.push(other.x as f32);
+            self.ys.push(other.y as f32);
+            self.due_dates.push(other.due_date as f32);
+            self.demands.push(other.demand);
+        }
+    }
+}
+
+#[cfg(feature = "simd")]
+thread_local! {
+    static DELIVERABLE_WORKSPACE: RefCell<DeliverableWorkspace> = RefCell::default();
+}
+
+/// A depot or a customer of an instance.
+///
+/// In JSON, a location is an object with the numeric fields `id`, `x`, `y`, `demand`,
+/// `ready_time`, `due_date` and `service_time`. The optional `kind` (like `"customer"`),
+/// `priority` (like `"low"`), `name` and `tags` default to a customer that must be served, and
+/// the [pairing](Pairing) of pickups and deliveries is their `delivery` or `pickup` id
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Location {
     pub id: u16,
-    pub x: u16,
-    pub y: u16,
-    pub demand: u16,
-    pub ready_time: u16,
-    pub due_date: u16,
-    pub service_time: u16,
+    pub x: f64,
+    pub y: f64,
+    pub demand: f64,
+    pub ready_time: f64,
+    pub due_date: f64,
+    pub service_time: f64,
+    #[serde(default)]
+    pub kind: LocationKind,
+    /// Human readable name shown in reports
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Free form labels, e.g. to group customers in reports and exports
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    /// Pickup and delivery pair of this customer, `None` for customers delivered from the depot
+    #[serde(default, flatten)]
+    pub pairing: Option<Pairing>,
+    /// Position in the explicit travel matrix of the instance, `None` for straight line
+    /// distances between the coordinates. Not part of the JSON of a location, the matrix is
+    /// stored with the instance
+    #[serde(skip)]
+    pub matrix_index: Option<MatrixIndex>,
+}
+
+// Every reader of the crate (instance files, JSON, TOML and VRP-REP) rejects NaN values, so
+// equality is total on the locations it builds. A NaN set by hand makes a location unequal to
+// itself
+impl Eq for Location {}
+
+// Hash `value` like its equality: 0.0 and -0.0 are equal but have different bits
+pub(crate) fn hash_f64<H: Hasher>(value: f64, state: &mut H) {
+    let value = if value == 0.0 { 0.0 } else { value };
+    value.to_bits().hash(state);
+}
+
+impl Hash for Location {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        for value in [
+            self.x,
+            self.y,
+            self.demand,
+            self.ready_time,
+            self.due_date,
+            self.service_time,
+        ] {
+            hash_f64(value, state);
+        }
+        self.kind.hash(state);
+        self.name.hash(state);
+        self.tags.hash(state);
+        self.priority.hash(state);
+        self.pairing.hash(state);
+        self.matrix_index.hash(state);
+    }
 }
 
 impl Location {
+    /// Whether this is a customer delivered from the depot, which every
+    /// [backhaul](LocationKind::Backhaul) of its route follows
+    pub fn is_linehaul(&self) -> bool {
+        self.kind == LocationKind::Customer && self.pairing.is_none()
+    }
+
+    /// Whether a vehicle may serve this location right after `previous`, no customer is
+    /// delivered from the depot after a backhaul
+    pub fn may_follow(&self, previous: &Location) -> bool {
+        previous.kind != LocationKind::Backhaul || !self.is_linehaul()
+    }
+
     // Calculate distance from current customer to other customer
     pub fn distance_to(&self, other: &Location) -> f32 {
-        f32::from(u16::pow(other.x.abs_diff(self.x), 2) + u16::pow(other.y.abs_diff(self.y), 2))
-            .sqrt()
+        if let Some((distance, _)) = self.matrix_travel_to(other) {
+            return distance;
+        }
+
+        let dx = other.x - self.x;
+        let dy = other.y - self.y;
+
+        (dx * dx + dy * dy).sqrt() as f32
+    }
+
+    // Calculate time to travel to other customer, the distance unless the travel matrix has
+    // durations
+    pub fn travel_time_to(&self, other: &Location) -> f32 {
+        match self.matrix_travel_to(other) {
+            Some((_, duration)) => duration,
+            None => self.distance_to(other),
+        }
     }
 
     // Calculate cost to arrive at this customer
     pub fn cost_to(&self, other: &Location, current_cost: f32) -> f32 {
-        // Add the distance to the other customer
-        current_cost + self.distance_to(other)
+        // Add the travel time to the other customer
+        current_cost + self.travel_time_to(other)
     }
 
     // Calculate cost to get to the delivery window of the other customer
     pub fn cost_to_delivery_window(&self, other: &Location, current_cost: f32) -> f32 {
-        // Add the distance to the other customer
-        let current_cost = current_cost + self.distance_to(other);
+        // Add the travel time to the other customer
+        let current_cost = current_cost + self.travel_time_to(other);
 
         current_cost + (other.ready_time as f32 - current_cost).max(0f32) // Add potentital waiting time
     }
 
     // Calculate cost to deliver to other customer from this customer
     pub fn cost_to_deliver(&self, other: &Location, current_cost: f32) -> f32 {
-        // Add the distance to the other customer
-        let current_cost = current_cost + self.distance_to(other);
+        // Add the travel time to the other customer
+        let current_cost = current_cost + self.travel_time_to(other);
 
         current_cost
             + (other.ready_time as f32 - current_cost).max(0f32) // Add potentital waiting time
@@ -53,11 +304,12 @@ impl Location {
     }
 
     // Find all neighbors whose delivery windows are reachable from the current location and whose demand can be fulfilled with the remaining truck capacity, return them.
+    #[cfg(not(feature = "simd"))]
     pub fn find_deliverable<'a>(
         &self,
         others: Vec<&'a Location>,
         current_cost: f32,
-        remaining_capacity: u16,
+        remaining_capacity: f64,
     ) -> Vec<&'a Location> {
         self.find_reachable(others, current_cost)
             .into_iter()
@@ -65,12 +317,49 @@ impl Location {
             .collect()
     }
 
+    // Find all neighbors whose delivery windows are reachable from the current location and whose demand can be fulfilled with the remaining truck capacity, return them.
+    #[cfg(feature = "simd")]
+    pub fn find_deliverable<'a>(
+        &self,
+        others: Vec<&'a Location>,
+        current_cost: f32,
+        remaining_capacity: f64,
+    ) -> Vec<&'a Location> {
+        // The kernel computes straight line distances
+        if self.matrix_index.is_some() {
+            return self
+                .find_reachable(others, current_cost)
+                .into_iter()
+                .filter(|&customer| customer.demand <= remaining_capacity)
+                .collect();
+        }
+
+        DELIVERABLE_WORKSPACE.with(|workspace| {
+            let workspace = &mut *workspace.borrow_mut();
+            workspace.fill(&others);
+
+            kernels::filter_deliverable(
+                self.x as f32,
+                self.y as f32,
+                current_cost,
+                remaining_capacity,
+                &workspace.xs,
+                &workspace.ys,
+                &workspace.due_dates,
+                &workspace.demands,
+                &mut workspace.deliverable,
+            );
+
+            workspace.deliverable.iter().map(|&i| others[i]).collect()
+        })
+    }
+
     // Find the neighbor that is the cheapest to deliver to from the current location, return it and the remaining list.
     pub fn find_cheapest_deliverable<'a>(
         &self,
         others: Vec<&'a Location>,
         current_cost: f32,
-        remaining_capacity: u16,
+        remaining_capacity: f64,
     ) -> Option<(&'a Location, f32, Vec<&'a Location>)> {
         let deliverable = self.find_deliverable(others.clone(), current_cost, remaining_capacity);
 
@@ -89,4 +378,63 @@ impl Location {
 
         Some((cheapest, cost, others))
     }
+
+    // Same as find_reachable, but only checks the locations of the index that are close enough to be reachable.
+    pub fn find_reachable_in<'a>(
+        &self,
+        index: &SpatialIndex<'a>,
+        current_cost: f32,
+    ) -> Vec<&'a Location> {
+        let radius = index.max_due_date() as f32 - current_cost;
+
+        self.find_reachable(index.within(self, radius), current_cost)
+    }
+
+    // Same as find_deliverable, but only checks the locations of the index that are close enough to be reachable.
+    pub fn find_deliverable_in<'a>(
+        &self,
+        index: &SpatialIndex<'a>,
+        current_cost: f32,
+        remaining_capacity: f64,
+    ) -> Vec<&'a Location> {
+        self.find_reachable_in(index, current_cost)
+            .into_iter()
+            .filter(|&customer| customer.demand <= remaining_capacity)
+            .collect()
+    }
+
+    // Find the location of the index that is the cheapest to deliver to from the current location, return it and its cost.
+    // Locations of a higher priority tier are always preferred to cheaper ones of a lower tier.
+    pub fn find_cheapest_deliverable_in<'a>(
+        &self,
+        index: &SpatialIndex<'a>,
+        current_cost: f32,
+        remaining_capacity: f64,
+    ) -> Option<(&'a Location, f32)> {
+        let radius = index.max_due_date() as f32 - current_cost;
+
+        self.find_cheapest_deliverable_among(
+            index.within(self, radius),
+            current_cost,
+            remaining_capacity,
+        )
+    }
+
+    // Same as find_cheapest_deliverable_in, only checks the given locations of an index.
+    pub(crate) fn find_cheapest_deliverable_among<'a>(
+        &self,
+        candidates: Vec<&'a Location>,
+        current_cost: f32,
+        remaining_capacity: f64,
+    ) -> Option<(&'a Location, f32)> {
+        self.find_reachable(candidates, current_cost)
+            .into_iter()
+            .filter(|&customer| customer.demand <= remaining_capacity)
+            .map(|customer| (customer, self.cost_to_deliver(customer, current_cost)))
+            .min_by(|a, b| {
+                a.0.priority
+                    .cmp(&b.0.priority)
+                    .then(a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            })
+    }
 }
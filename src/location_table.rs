@@ -0,0 +1,184 @@
+use crate::distance::MatrixIndex;
+use crate::location::{Location, LocationKind};
+
+/// A copy of the locations of an instance as parallel arrays, one entry per location.
+///
+/// [Location] stays the type instances and routes own, with its names, tags and pairs. This
+/// table is built once by [Vrp::location_table](crate::vrp::Vrp::location_table) for the loops
+/// that run over many locations but only read a few fields, such as the distances of the
+/// instance statistics or the cost of a [compact solution](crate::compact::CompactSolution):
+/// they only touch the arrays they need, which is much friendlier to the cache than walking a
+/// `Vec<Location>`. Indices follow [Vrp::locations](crate::vrp::Vrp::locations): the warehouse
+/// is at index 0.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LocationTable {
+    pub ids: Vec<u16>,
+    pub xs: Vec<f64>,
+    pub ys: Vec<f64>,
+    pub demands: Vec<f64>,
+    pub ready_times: Vec<f32>,
+    pub due_dates: Vec<f32>,
+    pub service_times: Vec<f32>,
+    /// Positions in the explicit travel matrix of the instance, empty for straight line
+    /// distances
+    pub matrix_indices: Vec<MatrixIndex>,
+}
+
+impl LocationTable {
+    pub fn from_locations<'a>(locations: impl IntoIterator<Item = &'a Location>) -> LocationTable {
+        let mut table = LocationTable::default();
+
+        for location in locations {
+            table.ids.push(location.id);
+            table.xs.push(location.x);
+            table.ys.push(location.y);
+            table.demands.push(location.demand);
+            table.ready_times.push(location.ready_time as f32);
+            table.due_dates.push(location.due_date as f32);
+            table.service_times.push(location.service_time as f32);
+            table.matrix_indices.extend(location.matrix_index.clone());
+        }
+
+        // Only read the matrix if every location is in it
+        if table.matrix_indices.len() != table.len() {
+            table.matrix_indices.clear();
+        }
+
+        table
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Get a view on the location at `index`
+    pub fn get(&self, index: usize) -> Option<LocationView<'_>> {
+        (index < self.len()).then_some(LocationView { table: self, index })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = LocationView<'_>> {
+        (0..self.len()).map(|index| LocationView { table: self, index })
+    }
+
+    /// Calculate distance between the locations at index `a` and `b`, same as
+    /// [Location::distance_to](crate::location::Location::distance_to)
+    pub fn distance(&self, a: usize, b: usize) -> f32 {
+        if let Some((distance, _)) = self.matrix_travel(a, b) {
+            return distance;
+        }
+
+        let dx = self.xs[a] - self.xs[b];
+        let dy = self.ys[a] - self.ys[b];
+
+        (dx * dx + dy * dy).sqrt() as f32
+    }
+
+    /// Calculate time to travel between the locations at index `a` and `b`, same as
+    /// [Location::travel_time_to](crate::location::Location::travel_time_to)
+    pub fn travel_time(&self, a: usize, b: usize) -> f32 {
+        match self.matrix_travel(a, b) {
+            Some((_, duration)) => duration,
+            None => self.distance(a, b),
+        }
+    }
+
+    fn matrix_travel(&self, a: usize, b: usize) -> Option<(f32, f32)> {
+        self.matrix_indices
+            .get(a)?
+            .travel_to(self.matrix_indices.get(b)?)
+    }
+
+    /// Calculate cost to deliver to `b` when leaving `a` at `current_cost`, same as
+    /// [Location::cost_to_deliver](crate::location::Location::cost_to_deliver)
+    pub fn cost_to_deliver(&self, a: usize, b: usize, current_cost: f32) -> f32 {
+        let arrival = current_cost + self.travel_time(a, b);
+
+        arrival + (self.ready_times[b] - arrival).max(0f32) + self.service_times[b]
+    }
+
+    /// Push the indices of `candidates` reachable from `from` before their due date and whose
+    /// demand fits in `remaining_capacity` into `out`, same as
+    /// [Location::find_deliverable](crate::location::Location::find_deliverable)
+    pub fn find_deliverable(
+        &self,
+        from: usize,
+        candidates: &[usize],
+        current_cost: f32,
+        remaining_capacity: f64,
+        out: &mut Vec<usize>,
+    ) {
+        out.extend(candidates.iter().copied().filter(|&to| {
+            self.due_dates[to] >= current_cost + self.travel_time(from, to)
+                && self.demands[to] <= remaining_capacity
+        }));
+    }
+}
+
+/// A lightweight view on one location of a [LocationTable]
+#[derive(Debug, Clone, Copy)]
+pub struct LocationView<'a> {
+    table: &'a LocationTable,
+    index: usize,
+}
+
+impl LocationView<'_> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn id(&self) -> u16 {
+        self.table.ids[self.index]
+    }
+
+    pub fn x(&self) -> f64 {
+        self.table.xs[self.index]
+    }
+
+    pub fn y(&self) -> f64 {
+        self.table.ys[self.index]
+    }
+
+    pub fn demand(&self) -> f64 {
+        self.table.demands[self.index]
+    }
+
+    pub fn ready_time(&self) -> f32 {
+        self.table.ready_times[self.index]
+    }
+
+    pub fn due_date(&self) -> f32 {
+        self.table.due_dates[self.index]
+    }
+
+    pub fn service_time(&self) -> f32 {
+        self.table.service_times[self.index]
+    }
+
+    pub fn distance_to(&self, other: &LocationView) -> f32 {
+        self.table.distance(self.index, other.index)
+    }
+
+    /// Copy this view back into an owned [Location](crate::location::Location), names and tags
+    /// are not stored in the table
+    pub fn to_location(&self) -> Location {
+        Location {
+            id: self.id(),
+            x: self.x(),
+            y: self.y(),
+            demand: self.demand(),
+            ready_time: f64::from(self.ready_time()),
+            due_date: f64::from(self.due_date()),
+            service_time: f64::from(self.service_time()),
+            kind: match self.index {
+                0 => LocationKind::Depot,
+                _ => LocationKind::Customer,
+            },
+            matrix_index: self.table.matrix_indices.get(self.index).cloned(),
+            ..Default::default()
+        }
+    }
+}
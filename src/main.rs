@@ -1,24 +1,212 @@
 //! This crate uses the solomon_vrp_solver library to solve a VRP
-use solomon_vrptw::heuristics::aco::AcoParams;
+use clap::{Args, Parser, Subcommand};
+use solomon_vrptw::book::ReportBook;
+use solomon_vrptw::config::{RunConfig, DEFAULT_CHECKPOINT_INTERVAL};
+use solomon_vrptw::distance::CoordinateMode;
+use solomon_vrptw::fleet::FleetPolicy;
+use solomon_vrptw::generator::GeneratorParams;
+use solomon_vrptw::heuristics::checkpoint::Checkpoint;
+use solomon_vrptw::heuristics::registry::HeuristicKind;
+use solomon_vrptw::integer_time::{IntegerTime, Rounding};
+use solomon_vrptw::objective::Objective;
+use solomon_vrptw::report::RouteOrder;
+use solomon_vrptw::robustness::TimeBuffer;
+use solomon_vrptw::session::SessionTracker;
+use solomon_vrptw::sintef::SolutionInfo;
+use solomon_vrptw::solve::SolvePreset;
+use solomon_vrptw::vehicle::AssignmentStrategy;
 use solomon_vrptw::vrp::Vrp;
+use solomon_vrptw::vrp_result::VrpResult;
 use std::fs;
-use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::Duration;
 
-fn delete_all_files_in_directory(directory: &str) -> io::Result<()> {
-    // Read the directory
-    for entry in fs::read_dir(directory)? {
-        let entry = entry?; // Handle Result from read_dir
-        let path = entry.path();
+/// Solve vehicle routing problems with time windows
+#[derive(Parser)]
+#[command(name = "solomon-vrptw")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-        // If the entry is a file, delete it
-        if path.is_file() {
-            fs::remove_file(path)?;
-        }
+#[derive(Subcommand)]
+enum Command {
+    /// Solve solomon, Li & Lim, TSPLIB (.vrp), VRP-REP (.xml) or JSON instances, several give
+    /// one book
+    Solve(Box<SolveArgs>),
+    /// Run the micro-benchmarks (needs the bench feature)
+    Bench {
+        /// Instance file, picked in a file dialog if missing with the gui feature
+        instance: Option<String>,
+        /// Number of timed runs
+        #[arg(long, default_value_t = 10)]
+        iterations: u32,
+        /// Also time these comma separated heuristics, like nn,i1,aco
+        #[arg(long, value_delimiter = ',')]
+        heuristics: Vec<HeuristicKind>,
+    },
+    /// Check an instance for problems ruling out a solution
+    Check {
+        /// Instance file, picked in a file dialog if missing with the gui feature
+        instance: Option<String>,
+    },
+    /// Export the distance matrix or the neighbor lists
+    Export {
+        /// Instance file, picked in a file dialog if missing with the gui feature
+        instance: Option<String>,
+        /// Data to export
+        #[arg(long, default_value = "distances", value_parser = ["distances", "neighbors"])]
+        what: String,
+        /// Output format, npy needs --out
+        #[arg(long, default_value = "csv", value_parser = ["csv", "npy"])]
+        format: String,
+        /// Number of neighbors per location
+        #[arg(long, default_value_t = 10)]
+        k: usize,
+        /// Write to a file instead of stdout
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Explain why a customer of a JSON solution is in its route
+    Explain {
+        /// Instance file
+        instance: String,
+        /// Solution saved as JSON
+        solution: String,
+        /// Id of the customer
+        customer: u16,
+    },
+    /// Serve the REST API (needs the server feature)
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
+    /// Generate a random instance in the solomon format
+    Generate {
+        /// Path of the instance file to write
+        out: String,
+        /// Number of customers
+        #[arg(long, default_value_t = 100)]
+        customers: u16,
+        /// Seed of the generator
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+
+/// Options of the solve command, they override the config file
+#[derive(Args)]
+struct SolveArgs {
+    /// Instance files, picked in a file dialog if missing with the gui feature
+    instances: Vec<String>,
+    /// Use the fast, balanced or quality settings, other options refine them
+    #[arg(long)]
+    preset: Option<SolvePreset>,
+    /// Heuristic to use: nn, i1, sweep, grasp, aco or pdp [default: aco]
+    #[arg(long)]
+    solver: Option<String>,
+    /// Stop the heuristic after this many seconds
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    time_limit: Option<Duration>,
+    /// Write the report to a file instead of stdout
+    #[arg(long)]
+    out: Option<String>,
+    /// Report format, html gives a report book [default: md]
+    #[arg(long, value_parser = ["md", "html", "json", "csv", "text", "sintef"])]
+    format: Option<String>,
+    /// Authors of the solution in the sintef format
+    #[arg(long, default_value = "")]
+    authors: String,
+    /// Sort routes by construction, cost, load, angle or vehicle
+    #[arg(long)]
+    route_order: Option<RouteOrder>,
+    /// Write the solutions of every step and the improvements as Markdown
+    #[arg(long, value_name = "PATH")]
+    timeline: Option<String>,
+    /// Seed of the route colors of the report [default: 0]
+    #[arg(long)]
+    report_seed: Option<u64>,
+    /// Load solver settings from a TOML file
+    #[arg(long, value_name = "PATH")]
+    config: Option<String>,
+    /// Apply a named profile of the config file
+    #[arg(long, requires = "config")]
+    profile: Option<String>,
+    /// Periodically write the best solution so far as JSON
+    #[arg(long, value_name = "PATH")]
+    checkpoint: Option<String>,
+    /// Seconds between two checkpoints [default: 60]
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    checkpoint_interval: Option<Duration>,
+    /// Keep slack before due dates, in time units or percent like 5%
+    #[arg(long)]
+    time_buffer: Option<TimeBuffer>,
+    /// repair, error, penalize or ignore extra vehicles [default: repair]
+    #[arg(long)]
+    fleet: Option<FleetPolicy>,
+    /// Give vehicles to routes by index or capacity_fit [default: index]
+    #[arg(long)]
+    vehicle_assignment: Option<AssignmentStrategy>,
+    /// Rank solutions by cost, distance, vehicles_then_distance or weighted
+    #[arg(long)]
+    objective: Option<Objective>,
+    /// Read coordinates as cartesian or geographic longitude and latitude
+    #[arg(long)]
+    coordinates: Option<CoordinateMode>,
+    /// Kilometers travelled per time unit with geographic coordinates
+    #[arg(long, value_name = "KM", value_parser = parse_positive::<f64>)]
+    speed: Option<f64>,
+    /// Solve and evaluate with floor, round or ceil integer travel times
+    #[arg(long, value_name = "ROUNDING")]
+    integer_time: Option<Rounding>,
+    /// Multiply times by n before rounding [default: 1]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    integer_scale: Option<u32>,
+    /// Also print the vehicles at the depot per bucket of this many time units
+    #[arg(long, value_name = "T", value_parser = parse_positive::<f32>)]
+    depot_buckets: Option<f32>,
+    /// Also print the latest departures cutting waiting
+    #[arg(long)]
+    departures: bool,
+}
+
+impl SolveArgs {
+    fn instance(&self) -> Result<String, String> {
+        instance_path(self.instances.first().cloned())
+    }
+}
+
+/// Read a number of seconds, like `30` or `0.5`
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    value
+        .parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("not a number of seconds: {}", value))
+}
+
+/// Read a number greater than zero
+fn parse_positive<T: FromStr + PartialOrd + Default>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|v| *v > T::default())
+        .ok_or_else(|| format!("not a positive number: {}", value))
+}
+
+/// The instance path given on the command line, or one picked in a file dialog
+fn instance_path(instance: Option<String>) -> Result<String, String> {
+    match instance {
+        Some(path) => Ok(path),
+        None => pick_file().ok_or_else(|| String::from("Missing instance path")),
     }
-    Ok(())
 }
 
 /// Let the user pick a solomon VRPTW .txt problem definition
+#[cfg(feature = "gui")]
 fn pick_file() -> Option<String> {
     let path = rfd::FileDialog::new()
         .add_filter("Text Files", &["txt"])
@@ -27,56 +215,441 @@ fn pick_file() -> Option<String> {
     path.to_str().map(String::from)
 }
 
-fn main() {
-    let path = match pick_file() {
-        Some(val) => val,
-        None => return,
+#[cfg(not(feature = "gui"))]
+fn pick_file() -> Option<String> {
+    None
+}
+
+/// The name of the instance read from `path`, its upper case file name without extension
+fn instance_name(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(path)
+        .to_uppercase()
+}
+
+/// Read a solomon instance or a Li & Lim one, a TSPLIB one if its extension is `.vrp`, a VRP-REP
+/// one if it is `.xml` or a JSON one if it is `.json`
+fn load_instance(path: &String) -> Result<Vrp, String> {
+    let vrp = match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("json") => Vrp::from_json_file(path),
+        Some("vrp") => Vrp::from_tsplib_file(path).map_err(|e| e.to_string()),
+        #[cfg(feature = "quick-xml")]
+        Some("xml") => {
+            Vrp::from_vrp_rep_file(path).ok_or_else(|| String::from("Not a VRP-REP instance"))
+        }
+        _ => Vrp::from_file(path)
+            .or_else(|e| Vrp::from_li_lim_file(path).map_err(|_| e))
+            .map_err(|e| e.to_string()),
     };
-    {
-        let split: Vec<String> = path.split("/").map(String::from).collect();
-        println!("Selected {}", split[split.len() - 2..split.len()].join("/"));
+    vrp.map_err(|e| format!("Failed to parse {}: {}", path, e))
+}
+
+fn solve(args: &SolveArgs) -> Result<(), String> {
+    let config = solve_config(args)?;
+
+    let format = config.format.as_deref().unwrap_or("md");
+    if args.instances.len() > 1 || format == "html" {
+        return solve_book(args, &config, format);
     }
 
-    let target_dir = "routes";
+    let vrp = load_instance(&args.instance()?)?;
+    let mut tracker = SessionTracker::new();
+    let result = solve_instance(args, &config, &vrp, &mut tracker)?;
+    if let Some(path) = &args.timeline {
+        fs::write(path, tracker.as_md_string())
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
 
-    _ = delete_all_files_in_directory(target_dir);
+    let report = match format {
+        "md" => format!(
+            "{}\n{}",
+            vrp.statistics().as_md_string(),
+            result.as_md_string_with(&config.report)
+        ),
+        "json" => result.as_json_string(),
+        "csv" => result.as_csv_string(),
+        "text" => result.to_canonical_string(&vrp),
+        "sintef" => result.to_sintef_string(&SolutionInfo {
+            instance_name: instance_name(&args.instance()?),
+            authors: args.authors.clone(),
+            ..Default::default()
+        }),
+        other => return Err(format!("Unknown format: {}", other)),
+    };
 
-    // -- Create an instance of VRP from the .txt problem definition --
-    let vrp = Vrp::from_file(&path).expect("Failed to parse file");
+    write_output(config.out.as_deref(), &report)?;
+    checkpoint_error(&config)
+}
 
-    dbg!(&vrp);
+/// Solve every instance and write a single report book, instead of one report per instance
+fn solve_book(args: &SolveArgs, config: &RunConfig, format: &str) -> Result<(), String> {
+    if !["md", "html"].contains(&format) {
+        return Err(format!(
+            "Several instances give a md or html report book, not {}",
+            format
+        ));
+    }
+    if args.timeline.is_some() {
+        return Err(String::from("--timeline needs a single instance"));
+    }
 
-    // -- Run nearest neighbour heuristic --
-    let nn_result = vrp.nearest_neighbour_heuristic();
-    println!(
-        "Total cost (nearest_neighbour_heuristic): {}",
-        nn_result.total_cost()
-    );
+    let paths = match args.instances.as_slice() {
+        [] => vec![args.instance()?],
+        paths => paths.to_vec(),
+    };
+
+    let mut book = ReportBook::new("Report book", config.report);
+    for path in &paths {
+        let vrp = load_instance(path)?;
+        let name = instance_name(path);
+
+        eprintln!("# {}", name);
+        let result = solve_instance(args, config, &vrp, &mut SessionTracker::new())?;
+        book.add(&name, &vrp, result);
+    }
+
+    let report = match format {
+        "html" => book.as_html_string(),
+        _ => book.as_md_string(),
+    };
+    write_output(config.out.as_deref(), &report)?;
+    checkpoint_error(config)
+}
+
+/// Fail if the checkpoint of the run could not be written, once the result is saved
+fn checkpoint_error(config: &RunConfig) -> Result<(), String> {
+    let Some(checkpoint) = &config.checkpoint else {
+        return Ok(());
+    };
+    match checkpoint.take_error() {
+        Some(e) => Err(format!(
+            "Failed to write checkpoint {}: {}",
+            checkpoint.path.display(),
+            e
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The run settings of the config file, overridden by the command line options
+fn solve_config(args: &SolveArgs) -> Result<RunConfig, String> {
+    let mut config = load_config(args.config.as_deref(), args.profile.as_deref())?;
+
+    // -- Command line options override the config file --
+    if let Some(preset) = args.preset {
+        preset.apply(&mut config);
+    }
+    if let Some(solver) = &args.solver {
+        config.pipeline = vec![solver.clone()];
+    }
+    if let Some(time_limit) = args.time_limit {
+        config.time_limit = Some(time_limit);
+    }
+    if let Some(format) = &args.format {
+        config.format = Some(format.clone());
+    }
+    if let Some(out) = &args.out {
+        config.out = Some(out.clone());
+    }
+    if let Some(seed) = args.report_seed {
+        config.report.seed = seed;
+    }
+    if let Some(route_order) = args.route_order {
+        config.report.route_order = route_order;
+    }
+    if let Some(path) = &args.checkpoint {
+        let interval = config
+            .checkpoint
+            .as_ref()
+            .map_or(DEFAULT_CHECKPOINT_INTERVAL, |c| c.interval);
+        config.checkpoint = Some(Checkpoint::new(path, interval));
+    }
+    if let Some(interval) = args.checkpoint_interval {
+        match &mut config.checkpoint {
+            Some(checkpoint) => checkpoint.interval = interval,
+            None => {
+                return Err(String::from(
+                    "--checkpoint-interval needs a --checkpoint path",
+                ))
+            }
+        }
+    }
+
+    if let Some(buffer) = args.time_buffer {
+        config.time_buffer = Some(buffer);
+    }
+    if let Some(fleet) = args.fleet {
+        config.fleet = fleet;
+    }
+    if let Some(assignment) = args.vehicle_assignment {
+        config.vehicle_assignment = assignment;
+    }
+    if let Some(objective) = args.objective {
+        config.objective = Some(objective);
+    }
+    if let Some(mode) = args.coordinates {
+        config.coordinates = Some(mode);
+    }
+    if let Some(speed) = args.speed {
+        match config.coordinates {
+            Some(CoordinateMode::Geographic { .. }) | None => {
+                config.coordinates = Some(CoordinateMode::Geographic { speed })
+            }
+            Some(CoordinateMode::Cartesian) => {
+                return Err(String::from("--speed needs geographic coordinates"))
+            }
+        }
+    }
+    if let Some(rounding) = args.integer_time {
+        config.integer_time = Some(IntegerTime {
+            rounding,
+            ..config.integer_time.unwrap_or_default()
+        });
+    }
+    if let Some(scale) = args.integer_scale {
+        config.integer_time = Some(IntegerTime {
+            scale,
+            ..config.integer_time.unwrap_or_default()
+        });
+    }
+
+    Ok(config)
+}
+
+/// Solve `vrp` and print the cost, the effort and the extra evaluations asked for to stderr
+fn solve_instance(
+    args: &SolveArgs,
+    config: &RunConfig,
+    vrp: &Vrp,
+    tracker: &mut SessionTracker,
+) -> Result<VrpResult, String> {
+    let result = config
+        .run_tracked(vrp, tracker)?
+        .with_route_order(config.report.route_order);
+
+    let solver = config.pipeline.join("+");
+    eprintln!("Total cost ({}): {}", solver, result.total_cost());
+    eprintln!("N° of routes ({}): {}", solver, result.routes().len());
+    if !result.is_complete() {
+        eprintln!("Unassigned customers ({}): {:?}", solver, result.unassigned);
+    }
+    if let Some(stats) = &result.stats {
+        eprintln!(
+            "Effort ({}): {:.3} s, {} solutions evaluated, {} feasibility checks",
+            solver,
+            stats.wall_time.as_secs_f64(),
+            stats.solutions_evaluated,
+            stats.feasibility_checks
+        );
+    }
+
+    if let Some(time) = &config.integer_time {
+        eprintln!(
+            "Integer cost ({:?}, scale {}): {}, valid: {}",
+            time.rounding,
+            time.scale,
+            result.integer_cost(time),
+            result.is_valid_integer(time)
+        );
+    }
+
+    if let Some(bucket_size) = args.depot_buckets {
+        eprint!("{}", result.depot_occupancy(bucket_size)?.as_md_string());
+    }
+    if args.departures {
+        eprint!("{}", result.minimize_waiting().as_md_string());
+    }
+
+    Ok(result)
+}
+
+#[cfg(feature = "config")]
+fn load_config(path: Option<&str>, profile: Option<&str>) -> Result<RunConfig, String> {
+    match path {
+        Some(path) => RunConfig::from_file(path, profile).map_err(|e| e.to_string()),
+        None => Ok(RunConfig::default()),
+    }
+}
+
+#[cfg(not(feature = "config"))]
+fn load_config(path: Option<&str>, _profile: Option<&str>) -> Result<RunConfig, String> {
+    match path {
+        Some(_) => Err(String::from(
+            "solomon-vrptw was built without the config feature",
+        )),
+        None => Ok(RunConfig::default()),
+    }
+}
+
+#[cfg(feature = "bench")]
+fn bench(
+    instance: Option<String>,
+    iterations: u32,
+    heuristics: &[HeuristicKind],
+) -> Result<(), String> {
+    let vrp = load_instance(&instance_path(instance)?)?;
+
+    for result in solomon_vrptw::bench::run_all(&vrp, iterations) {
+        println!("{}", result.as_string());
+    }
+    for kind in heuristics {
+        let heuristic = kind.with_defaults();
+        let result = solomon_vrptw::bench::bench_heuristic(&vrp, heuristic.as_ref(), iterations);
+        println!("{}", result.as_string());
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "bench"))]
+fn bench(
+    _instance: Option<String>,
+    _iterations: u32,
+    _heuristics: &[HeuristicKind],
+) -> Result<(), String> {
+    Err(String::from(
+        "solomon-vrptw was built without the bench feature",
+    ))
+}
+
+#[cfg(feature = "server")]
+fn serve(addr: &str) -> Result<(), String> {
+    let server = solomon_vrptw::server::Server::bind(addr)
+        .map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+    eprintln!("Listening on {}", addr);
+
+    server.run().map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(_addr: &str) -> Result<(), String> {
+    Err(String::from(
+        "solomon-vrptw was built without the server feature",
+    ))
+}
+
+fn check(instance: Option<String>) -> Result<(), String> {
+    let vrp = load_instance(&instance_path(instance)?)?;
+
+    println!("N° of customers: {}", vrp.customers.len());
+    println!("N° of vehicles: {}", vrp.n_vehicles);
+    println!("Vehicle capacity: {}", vrp.vehicle_capacity);
+
+    let diagnosis = vrp.diagnose();
+    print!("\n{}\n", diagnosis.as_md_string());
+
+    if !diagnosis.is_ok() {
+        return Err(String::from("The instance has no feasible solution"));
+    }
+
+    let (_, report) = vrp.tighten_time_windows();
     println!(
-        "N° of routes (nearest_neighbour_heuristic): {}",
-        nn_result.routes.len()
+        "Time windows that can be tightened: {}",
+        report.changes.len()
     );
+    Ok(())
+}
+
+fn export(
+    instance: Option<String>,
+    what: &str,
+    format: &str,
+    k: usize,
+    out: Option<&str>,
+) -> Result<(), String> {
+    let vrp = load_instance(&instance_path(instance)?)?;
+
+    match format {
+        "csv" if what == "distances" => write_output(out, &vrp.distance_matrix_csv()),
+        "csv" => write_output(out, &vrp.neighbors_csv(k)),
+        "npy" => {
+            let path = out.ok_or_else(|| String::from("--format npy needs an --out path"))?;
+            let content = match what {
+                "distances" => vrp.distance_matrix_npy(),
+                _ => vrp.neighbors_npy(k),
+            };
+            fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path, e))
+        }
+        other => Err(format!("Unknown format: {}", other)),
+    }
+}
 
-    fs::write(
-        format! {"{target_dir}/nearest_neighbour_heuristic.md"},
-        nn_result.as_md_string(),
-    )
-    .expect("Failed to write nearest_neighbour_heuristic results");
+fn explain(instance: &String, solution: &String, customer: u16) -> Result<(), String> {
+    let vrp = load_instance(instance)?;
+    let result = VrpResult::load(solution, &vrp)
+        .map_err(|e| format!("Failed to load {}: {}", solution, e))?;
+
+    let explanation = result
+        .explain(customer)
+        .ok_or_else(|| format!("Customer {} is not served by the solution", customer))?;
+    print!("{}", explanation.as_md_string());
+    Ok(())
+}
 
-    // -- Run Ant Colony Optimization heuristic
-    let aco_params = AcoParams {
-        pheromone_amt: 1.0 / nn_result.total_cost(),
-        ..AcoParams::default()
+fn generate(out: &str, n_customers: u16, seed: u64) -> Result<(), String> {
+    let params = GeneratorParams {
+        n_customers,
+        seed,
+        ..GeneratorParams::default()
     };
 
-    let aco_result = vrp.aco_heuristic(&aco_params);
+    let name = Path::new(out)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("GENERATED")
+        .to_uppercase();
 
-    println!("Total cost (aco_heuristic): {}", aco_result.total_cost());
-    println!("N° of routes (aco_heuristic): {}", aco_result.routes.len());
+    write_output(Some(out), &Vrp::generate(&params).as_solomon_string(&name))
+}
 
-    fs::write(
-        format! {"{target_dir}/aco_heuristic.md"},
-        aco_result.as_md_string(),
-    )
-    .expect("Failed to write aco_heuristic results");
+/// Write to `path`, or to stdout if no path is given
+fn write_output(path: Option<&str>, content: &str) -> Result<(), String> {
+    match path {
+        Some(path) => {
+            fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path, e))
+        }
+        None => {
+            print!("{}", content);
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Solve(args) => solve(&args),
+        Command::Bench {
+            instance,
+            iterations,
+            heuristics,
+        } => bench(instance, iterations, &heuristics),
+        Command::Check { instance } => check(instance),
+        Command::Export {
+            instance,
+            what,
+            format,
+            k,
+            out,
+        } => export(instance, &what, &format, k, out.as_deref()),
+        Command::Explain {
+            instance,
+            solution,
+            customer,
+        } => explain(&instance, &solution, customer),
+        Command::Serve { addr } => serve(&addr),
+        Command::Generate {
+            out,
+            customers,
+            seed,
+        } => generate(&out, customers, seed),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
 }
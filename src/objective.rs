@@ -0,0 +1,107 @@
+use crate::vrp_result::VrpResult;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Weight of a route for [Objective::Weighted] when none is configured, about the length of a
+/// solomon scheduling horizon
+pub const DEFAULT_VEHICLE_WEIGHT: f32 = 1000.0;
+
+/// What solutions are ranked by, see [objective](crate::vrp::Vrp::objective).
+///
+/// Heuristics keep the best solution under the objective of their instance, the moves of their
+/// local searches still lower the total cost
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Objective {
+    /// The total cost, travel plus waiting and service times
+    #[default]
+    Cost,
+    /// The total distance travelled
+    Distance,
+    /// The number of routes, then the total distance, as solutions are ranked in the solomon
+    /// literature
+    VehiclesThenDistance,
+    /// A weighted sum of the number of routes, the total distance and the total cost
+    Weighted {
+        vehicle: f32,
+        distance: f32,
+        cost: f32,
+    },
+}
+
+impl FromStr for Objective {
+    type Err = String;
+
+    /// Parse `cost`, `distance`, `vehicles_then_distance` or `weighted`, `weighted` uses the
+    /// [default vehicle weight](DEFAULT_VEHICLE_WEIGHT) and the distance
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cost" => Ok(Objective::Cost),
+            "distance" => Ok(Objective::Distance),
+            "vehicles_then_distance" => Ok(Objective::VehiclesThenDistance),
+            "weighted" => Ok(Objective::Weighted {
+                vehicle: DEFAULT_VEHICLE_WEIGHT,
+                distance: 1.0,
+                cost: 0.0,
+            }),
+            other => Err(format!("Unknown objective: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Objective::Cost => write!(f, "cost"),
+            Objective::Distance => write!(f, "distance"),
+            Objective::VehiclesThenDistance => write!(f, "vehicles_then_distance"),
+            Objective::Weighted {
+                vehicle,
+                distance,
+                cost,
+            } => write!(
+                f,
+                "weighted ({} per vehicle, {} per distance, {} per cost)",
+                vehicle, distance, cost
+            ),
+        }
+    }
+}
+
+impl Objective {
+    /// Get the values `result` is ranked by, compared in order, lower is better
+    pub fn key(&self, result: &VrpResult) -> (f32, f32) {
+        match *self {
+            Objective::Cost => (0.0, result.total_cost()),
+            Objective::Distance => (0.0, result.total_distance()),
+            Objective::VehiclesThenDistance => {
+                (result.routes.len() as f32, result.total_distance())
+            }
+            Objective::Weighted {
+                vehicle,
+                distance,
+                cost,
+            } => (
+                0.0,
+                vehicle * result.routes.len() as f32
+                    + distance * result.total_distance()
+                    + cost * result.total_cost(),
+            ),
+        }
+    }
+
+    /// Rank `a` against `b`, `Less` if `a` is better
+    pub fn compare(&self, a: &VrpResult, b: &VrpResult) -> Ordering {
+        Objective::compare_keys(self.key(a), self.key(b))
+    }
+
+    /// Rank two [keys](Objective::key) of the same objective, `Less` if `a` is better
+    pub fn compare_keys(a: (f32, f32), b: (f32, f32)) -> Ordering {
+        a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1))
+    }
+
+    /// Whether `a` is a better solution than `b`
+    pub fn is_better(&self, a: &VrpResult, b: &VrpResult) -> bool {
+        self.compare(a, b) == Ordering::Less
+    }
+}
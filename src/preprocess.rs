@@ -0,0 +1,151 @@
+use crate::location::{Location, Pairing};
+use crate::vrp::Vrp;
+use std::collections::HashSet;
+
+/// A time window changed by [tighten_time_windows](Vrp::tighten_time_windows)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeWindowChange {
+    pub id: u16,
+    /// The `(ready_time, due_date)` before tightening
+    pub old: (f64, f64),
+    /// The `(ready_time, due_date)` after tightening
+    pub new: (f64, f64),
+}
+
+/// Changes made by [tighten_time_windows](Vrp::tighten_time_windows)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TighteningReport {
+    pub changes: Vec<TimeWindowChange>,
+    /// Customers that no vehicle can serve, they are not part of the tightened instance
+    pub removed: Vec<Location>,
+}
+
+impl Vrp {
+    /// Copy this VRP with every customer time window reduced to the arrival times a vehicle can
+    /// actually achieve:
+    ///
+    /// - a vehicle cannot arrive before driving straight from the warehouse
+    /// - a vehicle must arrive early enough to serve the customer and drive back before the
+    ///   warehouse closes, unless routes are [open](Vrp::open)
+    ///
+    /// On [multi-depot](Vrp::is_multi_depot) instances, the vehicle may come from and go back to
+    /// any depot
+    ///
+    /// Customers left with an empty window, or with a demand above the vehicle capacity, are
+    /// removed, along with their pickup and delivery partner which cannot be served without
+    /// them. Bounds are rounded outwards, so no feasible route and no route cost changes.
+    pub fn tighten_time_windows(&self) -> (Vrp, TighteningReport) {
+        let mut report = TighteningReport::default();
+        let mut customers = Vec::with_capacity(self.customers.len());
+
+        for customer in &self.customers {
+            // Travel costs may depend on the direction, the way out and back can differ
+            let earliest = self
+                .depots()
+                .map(|depot| depot.ready_time as f32 + depot.travel_time_to(customer))
+                .fold(f32::INFINITY, f32::min)
+                .floor();
+            // Open routes end at their last customer, nothing bounds them on the way back
+            let latest = if self.open {
+                f32::INFINITY
+            } else {
+                self.depots()
+                    .map(|depot| {
+                        depot.due_date as f32
+                            - customer.service_time as f32
+                            - customer.travel_time_to(depot)
+                    })
+                    .fold(f32::NEG_INFINITY, f32::max)
+                    .ceil()
+            };
+
+            // Serving starts at the ready time at the earliest, so it must fit before `latest`
+            if latest < earliest.max(customer.ready_time as f32)
+                || customer.demand > self.vehicle_capacity
+            {
+                report.removed.push(customer.clone());
+                continue;
+            }
+
+            let ready_time = customer.ready_time.max(f64::from(earliest));
+            let due_date = customer.due_date.min(f64::from(latest));
+
+            // The due date is before the earliest arrival
+            if due_date < ready_time {
+                report.removed.push(customer.clone());
+                continue;
+            }
+
+            if (ready_time, due_date) != (customer.ready_time, customer.due_date) {
+                report.changes.push(TimeWindowChange {
+                    id: customer.id,
+                    old: (customer.ready_time, customer.due_date),
+                    new: (ready_time, due_date),
+                });
+            }
+
+            customers.push(Location {
+                ready_time,
+                due_date,
+                ..customer.clone()
+            });
+        }
+
+        // A pair is served by one vehicle or not at all
+        let removed: HashSet<u16> = report.removed.iter().map(|c| c.id).collect();
+        let orphaned = |customer: &Location| match customer.pairing {
+            Some(Pairing::Pickup { delivery: partner } | Pairing::Delivery { pickup: partner }) => {
+                removed.contains(&partner) && !removed.contains(&customer.id)
+            }
+            None => false,
+        };
+        report
+            .removed
+            .extend(self.customers.iter().filter(|c| orphaned(c)).cloned());
+        customers.retain(|c| !orphaned(c));
+        report
+            .changes
+            .retain(|change| customers.iter().any(|c| c.id == change.id));
+
+        (
+            Vrp {
+                customers,
+                ..self.clone()
+            },
+            report,
+        )
+    }
+}
+
+impl TighteningReport {
+    /// Print this report to a Markdown string
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("# Time window tightening\n\n");
+        output.push_str(&format! {"- N° of tightened windows: {}\n", self.changes.len()});
+        output.push_str(&format! {"- N° of removed customers: {}\n", self.removed.len()});
+
+        if !self.removed.is_empty() {
+            output.push_str("\n## Removed customers\n\n");
+            for customer in &self.removed {
+                output.push_str(&format! {
+                    "- {} (TW: {} - {}, demand: {})\n",
+                    customer.id, customer.ready_time, customer.due_date, customer.demand
+                });
+            }
+        }
+
+        if !self.changes.is_empty() {
+            output.push_str("\n## Tightened windows\n\n");
+            output.push_str("| Customer | Before | After |\n");
+            output.push_str("|---|---|---|\n");
+            for change in &self.changes {
+                output.push_str(&format! {
+                    "| {} | {} - {} | {} - {} |\n",
+                    change.id, change.old.0, change.old.1, change.new.0, change.new.1
+                });
+            }
+        }
+        output
+    }
+}
@@ -0,0 +1,195 @@
+use crate::location::Location;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+
+/// Importance of each criterion of [Relatedness], only their ratios matter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RelatednessWeights {
+    pub distance: f32,
+    pub time_window: f32,
+    pub demand: f32,
+}
+
+impl Default for RelatednessWeights {
+    /// The weights of Ropke and Pisinger for Shaw removal
+    fn default() -> Self {
+        RelatednessWeights {
+            distance: 9.0,
+            time_window: 3.0,
+            demand: 2.0,
+        }
+    }
+}
+
+/// How related two customers of an instance are, from 0 for opposite customers to 1 for
+/// customers at the same place, with the same time window and the same demand.
+///
+/// This is the measure of Shaw removal: customers close to each other in space, time and demand
+/// can swap places in routes, so removing and reinserting them together is likely to improve a
+/// solution. It also groups customers for clustering and diagnostics. Build one for an instance
+/// with [Vrp::relatedness]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Relatedness {
+    pub weights: RelatednessWeights,
+    /// Distances are divided by the largest distance between two locations: the diagonal of
+    /// their bounding box, or the largest reachable distance of their travel matrix
+    max_distance: f32,
+    /// Demand differences are divided by the largest demand
+    max_demand: f32,
+}
+
+impl Relatedness {
+    /// Get the relatedness of `a` and `b`, between 0 and 1, symmetric.
+    ///
+    /// Each criterion is a similarity between 0 and 1: the distance relative to the extent of
+    /// the instance, the share of the narrower time window overlapping the other one, and the
+    /// demand difference relative to the largest demand. Returns 0 if every weight is 0
+    pub fn between(&self, a: &Location, b: &Location) -> f32 {
+        let total = self.weights.distance + self.weights.time_window + self.weights.demand;
+        if total <= 0.0 {
+            return 0.0;
+        }
+
+        let distance = 1.0 - (a.distance_to(b) / self.max_distance).min(1.0);
+
+        let overlap = a.due_date.min(b.due_date) as f32 - a.ready_time.max(b.ready_time) as f32;
+        let narrower = (a.due_date - a.ready_time)
+            .min(b.due_date - b.ready_time)
+            .max(0.0) as f32;
+        // An instant overlaps another window entirely or not at all
+        let time_window = if narrower > 0.0 {
+            (overlap / narrower).clamp(0.0, 1.0)
+        } else if overlap >= 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+
+        let demand = 1.0 - (a.demand - b.demand).abs() as f32 / self.max_demand;
+
+        (self.weights.distance * distance
+            + self.weights.time_window * time_window
+            + self.weights.demand * demand)
+            / total
+    }
+}
+
+impl Vrp {
+    /// Build the [Relatedness] measure of the customers of this instance
+    pub fn relatedness(&self, weights: RelatednessWeights) -> Relatedness {
+        let (min_x, max_x, min_y, max_y) = self.locations().fold(
+            (
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(min_x, max_x, min_y, max_y), l| {
+                (
+                    min_x.min(l.x),
+                    max_x.max(l.x),
+                    min_y.min(l.y),
+                    max_y.max(l.y),
+                )
+            },
+        );
+        let diagonal = (max_x - min_x).max(0.0).hypot((max_y - min_y).max(0.0)) as f32;
+        // Matrix distances follow the roads and can be longer than the diagonal
+        let max_distance = match &self.warehouse.matrix_index {
+            Some(index) => index
+                .matrix
+                .distances
+                .iter()
+                .copied()
+                .filter(|d| d.is_finite())
+                .fold(0.0, f32::max),
+            None => diagonal,
+        };
+
+        let max_demand = self.customers.iter().map(|c| c.demand).fold(0.0, f64::max);
+
+        Relatedness {
+            weights,
+            // Avoid dividing by zero when every location is the same
+            max_distance: max_distance.max(1.0),
+            max_demand: if max_demand > 0.0 {
+                max_demand as f32
+            } else {
+                1.0
+            },
+        }
+    }
+
+    /// Get the other customers by decreasing relatedness to the customer `id`, with their
+    /// relatedness. Empty if no customer has this id
+    pub fn most_related(&self, id: u16, weights: RelatednessWeights) -> Vec<(&Location, f32)> {
+        let Some(customer) = self.customers.iter().find(|c| c.id == id) else {
+            return Vec::new();
+        };
+        let relatedness = self.relatedness(weights);
+
+        let mut related: Vec<(&Location, f32)> = self
+            .customers
+            .iter()
+            .filter(|c| c.id != id)
+            .map(|c| (c, relatedness.between(customer, c)))
+            .collect();
+        related.sort_by(|a, b| b.1.total_cmp(&a.1));
+        related
+    }
+}
+
+impl VrpResult {
+    /// Shaw removal: take the customer `seed` and the `count - 1` routed customers most related
+    /// to it out of their routes, to reinsert them with
+    /// [best_insertion](VrpResult::best_insertion). Routes left empty are removed.
+    ///
+    /// Returns the removed customers, the seed first, none if the seed is not routed. Drawing
+    /// the seed at random is left to the caller
+    pub fn shaw_removal(
+        &mut self,
+        vrp: &Vrp,
+        seed: u16,
+        count: usize,
+        weights: RelatednessWeights,
+    ) -> Vec<Location> {
+        let routed = |result: &VrpResult, id: u16| {
+            result
+                .routes
+                .iter()
+                .any(|route| route.customers.iter().any(|c| c.id == id))
+        };
+        if count == 0 || !routed(self, seed) {
+            return Vec::new();
+        }
+
+        let mut ids = vec![seed];
+        ids.extend(
+            vrp.most_related(seed, weights)
+                .into_iter()
+                .map(|(c, _)| c.id)
+                .filter(|&id| routed(self, id))
+                .take(count - 1),
+        );
+
+        let mut removed = Vec::with_capacity(ids.len());
+        for id in ids {
+            let Some((index, position)) =
+                self.routes.iter().enumerate().find_map(|(i, route)| {
+                    Some((i, route.customers.iter().position(|c| c.id == id)?))
+                })
+            else {
+                continue;
+            };
+
+            let mut route = self.routes[index].clone();
+            removed.push(route.customers.remove(position));
+            if route.customers.is_empty() {
+                self.remove_route(index);
+            } else {
+                self.replace_route(index, route);
+            }
+        }
+        removed
+    }
+}
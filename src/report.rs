@@ -0,0 +1,251 @@
+use crate::route::Route;
+use crate::vrp_result::VrpResult;
+#[cfg(feature = "plot")]
+use plotters::coord::types::RangedCoordi32;
+#[cfg(feature = "plot")]
+use plotters::coord::Shift;
+#[cfg(feature = "plot")]
+use plotters::prelude::*;
+#[cfg(feature = "plot")]
+use rand::rngs::StdRng;
+#[cfg(feature = "plot")]
+use rand::SeedableRng;
+use std::fmt;
+#[cfg(feature = "plot")]
+use std::path::Path;
+use std::str::FromStr;
+
+/// Options of the Markdown reports of solutions and of their charts
+///
+/// Everything random in a report, like the colors of the routes, is drawn from `seed`: the same
+/// solution reported with the same options always gives the same bytes, so reports can be kept
+/// in version control and diffed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReportOptions {
+    pub seed: u64,
+    /// Order of the routes in the report, see [with_route_order](VrpResult::with_route_order)
+    pub route_order: RouteOrder,
+}
+
+/// Order of the routes of a solution in reports and exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RouteOrder {
+    /// The order the heuristic built the routes in
+    #[default]
+    Construction,
+    /// Cheapest routes first
+    Cost,
+    /// Least loaded routes first
+    Load,
+    /// By the angle of the first customer around the depot, counterclockwise from the left
+    Angle,
+    /// By [vehicle](VrpResult::vehicles) id, routes without a vehicle last
+    Vehicle,
+}
+
+impl FromStr for RouteOrder {
+    type Err = String;
+
+    /// Parse `construction`, `cost`, `load`, `angle` or `vehicle`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "construction" => Ok(RouteOrder::Construction),
+            "cost" => Ok(RouteOrder::Cost),
+            "load" => Ok(RouteOrder::Load),
+            "angle" => Ok(RouteOrder::Angle),
+            "vehicle" => Ok(RouteOrder::Vehicle),
+            other => Err(format!("Unknown route order: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for RouteOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RouteOrder::Construction => "construction",
+            RouteOrder::Cost => "cost",
+            RouteOrder::Load => "load",
+            RouteOrder::Angle => "angle",
+            RouteOrder::Vehicle => "vehicle",
+        })
+    }
+}
+
+/// Image format of the charts saved to files, like with [plot_to_file](VrpResult::plot_to_file)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlotFormat {
+    /// The format of the charts embedded in the Markdown reports
+    #[default]
+    Svg,
+    /// A bitmap, for documents and viewers that do not take SVG
+    Png,
+}
+
+impl FromStr for PlotFormat {
+    type Err = String;
+
+    /// Parse `svg` or `png`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "svg" => Ok(PlotFormat::Svg),
+            "png" => Ok(PlotFormat::Png),
+            other => Err(format!("Unknown plot format: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for PlotFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PlotFormat::Svg => "svg",
+            PlotFormat::Png => "png",
+        })
+    }
+}
+
+impl ReportOptions {
+    /// A random generator seeded with [seed](ReportOptions::seed), create a new one for every
+    /// chart so charts do not depend on each other
+    #[cfg(feature = "plot")]
+    pub(crate) fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+}
+
+impl VrpResult {
+    /// The same solution with its routes, and their vehicles, sorted by `order`. Ties keep the
+    /// construction order.
+    ///
+    /// Every report and export numbers routes by their position, reordering once before
+    /// printing keeps route numbers the same in the Markdown report, the CSV and the plots
+    pub fn with_route_order(&self, order: RouteOrder) -> VrpResult {
+        if order == RouteOrder::Construction {
+            return self.clone();
+        }
+
+        let angle = |route: &Route| match route.customers.first() {
+            Some(first) => ((first.y as f32) - (route.warehouse.y as f32))
+                .atan2((first.x as f32) - (route.warehouse.x as f32)),
+            None => f32::INFINITY,
+        };
+        let key = |i: usize| -> f32 {
+            match order {
+                RouteOrder::Construction => 0.0,
+                RouteOrder::Cost => self.route_cost(i),
+                RouteOrder::Load => self.routes[i].total_demand() as f32,
+                RouteOrder::Angle => angle(&self.routes[i]),
+                RouteOrder::Vehicle => self
+                    .vehicle_of(i)
+                    .map_or(f32::INFINITY, |vehicle| f32::from(vehicle.id)),
+            }
+        };
+
+        let mut indices: Vec<usize> = (0..self.routes.len()).collect();
+        indices.sort_by(|&a, &b| key(a).total_cmp(&key(b)));
+
+        let mut result = VrpResult {
+            routes: indices.iter().map(|&i| self.routes[i].clone()).collect(),
+            vehicles: indices
+                .iter()
+                .map_while(|&i| self.vehicle_of(i).cloned())
+                .collect(),
+            ..self.clone()
+        };
+        result.refresh_cost();
+        result
+    }
+}
+
+/// Chart of the `plot` feature, with location coordinates as axes
+#[cfg(feature = "plot")]
+pub(crate) type RouteChart<'a, DB> =
+    ChartContext<'a, DB, Cartesian2d<RangedCoordi32, RangedCoordi32>>;
+
+/// A chart drawn the same way on the backend of every [PlotFormat]
+#[cfg(feature = "plot")]
+pub(crate) trait Plot {
+    /// Draw the whole chart on `root`, without presenting it
+    fn draw<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>);
+}
+
+/// Draw `plot` on a chart of `size` pixels and save it to `path` as `format`
+#[cfg(feature = "plot")]
+pub(crate) fn save_plot(
+    plot: &impl Plot,
+    path: &Path,
+    format: PlotFormat,
+    size: (u32, u32),
+) -> std::io::Result<()> {
+    match format {
+        PlotFormat::Svg => {
+            let root = SVGBackend::new(path, size).into_drawing_area();
+            plot.draw(&root);
+            root.present().map_err(std::io::Error::other)
+        }
+        PlotFormat::Png => {
+            let root = BitMapBackend::new(path, size).into_drawing_area();
+            plot.draw(&root);
+            root.present().map_err(std::io::Error::other)
+        }
+    }
+}
+
+/// Draw every arc of `route` in `color`, with an arrow head before each location showing the
+/// direction of travel. Travel costs may depend on the direction, so a route and its mirror
+/// image are different routes
+#[cfg(feature = "plot")]
+pub(crate) fn draw_route_arcs<DB: DrawingBackend>(
+    chart: &mut RouteChart<DB>,
+    route: &Route,
+    color: RGBColor,
+) {
+    const ARROW_SIZE: f64 = 8.0;
+    // Radius of the location markers, the tip stops at the edge of the marker
+    const MARKER_RADIUS: f64 = 4.0;
+
+    // Open routes end at their last customer
+    let points: Vec<(i32, i32)> = std::iter::once(&route.warehouse)
+        .chain(&route.customers)
+        .chain(std::iter::once(&route.warehouse).filter(|_| !route.open))
+        .map(|l| (l.x as i32, l.y as i32))
+        .collect();
+
+    chart
+        .draw_series(LineSeries::new(points.iter().copied(), &color))
+        .unwrap();
+
+    let heads: Vec<_> = points
+        .windows(2)
+        .filter_map(|arc| {
+            // Directions in pixels, the y axis points down and scales differ from the x axis
+            let (from, to) = (chart.backend_coord(&arc[0]), chart.backend_coord(&arc[1]));
+            let (dx, dy) = (f64::from(to.0 - from.0), f64::from(to.1 - from.1));
+            let length = dx.hypot(dy);
+            if length <= ARROW_SIZE + MARKER_RADIUS {
+                return None;
+            }
+            let (ux, uy) = (dx / length, dy / length);
+
+            let at = |along: f64, across: f64| {
+                (
+                    (-ux * along - uy * across).round() as i32,
+                    (-uy * along + ux * across).round() as i32,
+                )
+            };
+            let base = MARKER_RADIUS + ARROW_SIZE;
+            Some(
+                EmptyElement::at(arc[1])
+                    + Polygon::new(
+                        vec![
+                            at(MARKER_RADIUS, 0.0),
+                            at(base, ARROW_SIZE / 2.0),
+                            at(base, -ARROW_SIZE / 2.0),
+                        ],
+                        color.filled(),
+                    ),
+            )
+        })
+        .collect();
+
+    chart.draw_series(heads).unwrap();
+}
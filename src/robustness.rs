@@ -0,0 +1,285 @@
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Random relative deviation applied to a duration, a sampled deviation of `0.1` makes the
+/// duration 10% longer. Durations never become negative
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Noise {
+    None,
+    /// Deviation drawn uniformly in `-spread..=spread`
+    Uniform {
+        spread: f32,
+    },
+    /// Deviation drawn from a normal distribution centered on 0
+    Normal {
+        std_dev: f32,
+    },
+}
+
+impl Noise {
+    fn sample(&self, rng: &mut StdRng) -> f32 {
+        let deviation = match *self {
+            Noise::None => 0.0,
+            Noise::Uniform { spread } if spread > 0.0 => rng.gen_range(-spread..=spread),
+            Noise::Uniform { .. } => 0.0,
+            Noise::Normal { std_dev } => {
+                // Box-Muller transform
+                let u1: f32 = 1.0 - rng.gen::<f32>();
+                let u2: f32 = rng.gen();
+                std_dev * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos()
+            }
+        };
+        (1.0 + deviation).max(0.0)
+    }
+}
+
+/// Slack kept before every due date when solving, so routes survive moderate delays
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeBuffer {
+    /// Arrive at least this many time units before each due date
+    Absolute(f64),
+    /// Arrive early enough to absorb a delay of this share of the elapsed route time, `0.05`
+    /// still arrives in time when running 5% late
+    Relative(f32),
+}
+
+impl TimeBuffer {
+    /// The latest buffered arrival time for `due_date`
+    pub fn apply(&self, due_date: f64) -> f64 {
+        match *self {
+            TimeBuffer::Absolute(buffer) => (due_date - buffer).max(0.0),
+            TimeBuffer::Relative(share) => due_date / (1.0 + f64::from(share.max(0.0))),
+        }
+    }
+}
+
+impl FromStr for TimeBuffer {
+    type Err = String;
+
+    /// Parse `10` as an absolute buffer and `5%` as a relative one
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid time buffer: {}", s);
+
+        match s.trim().strip_suffix('%') {
+            Some(percent) => percent
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .filter(|p| p.is_finite() && *p >= 0.0)
+                .map(|p| TimeBuffer::Relative(p / 100.0))
+                .ok_or_else(invalid),
+            None => s
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|b| b.is_finite() && *b >= 0.0)
+                .map(TimeBuffer::Absolute)
+                .ok_or_else(invalid),
+        }
+    }
+}
+
+impl Vrp {
+    /// Copy this VRP with due dates tightened by `buffer`, solve the copy then map the solution
+    /// back with [with_instance](VrpResult::with_instance).
+    ///
+    /// A due date is never moved before the ready time, so every customer stays servable.
+    pub fn with_time_buffer(&self, buffer: TimeBuffer) -> Vrp {
+        let buffered = |location: &Location| Location {
+            due_date: buffer.apply(location.due_date).max(location.ready_time),
+            ..location.clone()
+        };
+
+        Vrp {
+            warehouse: buffered(&self.warehouse),
+            customers: self.customers.iter().map(buffered).collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl VrpResult {
+    /// Replace the locations of every route by the locations of `vrp` with the same id, its
+    /// depots included, and recompute the costs. Routes take the max duration and breaks of
+    /// `vrp`. Used to report a solution of a modified copy of `vrp`
+    pub fn with_instance(&self, vrp: &Vrp) -> VrpResult {
+        let customers: HashMap<u16, &Location> = vrp.customers.iter().map(|c| (c.id, c)).collect();
+
+        let routes = self
+            .routes
+            .iter()
+            .map(|route| {
+                let depot = vrp
+                    .depots()
+                    .find(|d| d.id == route.warehouse.id)
+                    .unwrap_or(&vrp.warehouse);
+                Route {
+                    customers: route
+                        .customers
+                        .iter()
+                        .map(|c| {
+                            customers
+                                .get(&c.id)
+                                .map_or_else(|| c.clone(), |&c| c.clone())
+                        })
+                        .collect(),
+                    open: route.open,
+                    ..vrp.empty_route_from(depot)
+                }
+            })
+            .collect();
+
+        VrpResult {
+            stats: self.stats,
+            unassigned: self.unassigned.clone(),
+            vehicles: self.vehicles.clone(),
+            heuristic_restarts: self.heuristic_restarts.clone(),
+            ..VrpResult::from_vrp(vrp, routes, self.heuristic_cost_history.clone())
+        }
+    }
+}
+
+/// Parameters of the Monte-Carlo evaluation of a solution
+#[derive(Debug, Clone)]
+pub struct NoiseParams {
+    /// Noise on the travel time between two locations
+    pub travel_time: Noise,
+    /// Noise on the service time of customers
+    pub service_time: Noise,
+    /// The number of simulated executions
+    pub n_samples: u32,
+    /// Seed of the random number generator, the same seed always gives the same report
+    pub seed: u64,
+}
+
+impl Default for NoiseParams {
+    fn default() -> Self {
+        NoiseParams {
+            travel_time: Noise::Normal { std_dev: 0.1 },
+            service_time: Noise::Normal { std_dev: 0.1 },
+            n_samples: 1000,
+            seed: 0,
+        }
+    }
+}
+
+/// Simulated behavior of one route
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteRobustness {
+    /// Share of the samples in which at least one due date (customer or warehouse) is missed
+    pub violation_probability: f32,
+    /// Mean total time past due dates over all samples
+    pub expected_lateness: f32,
+    /// Mean cost (return time to the warehouse) over all samples
+    pub expected_cost: f32,
+}
+
+/// Simulated behavior of a full solution
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RobustnessReport {
+    pub routes: Vec<RouteRobustness>,
+    /// Share of the samples in which at least one route misses a due date
+    pub violation_probability: f32,
+    /// Mean total lateness of all routes
+    pub expected_lateness: f32,
+    pub expected_cost: f32,
+}
+
+impl VrpResult {
+    /// Simulate the execution of this solution `n_samples` times with perturbed travel and
+    /// service times.
+    ///
+    /// Late vehicles still serve their customers, the time past the due date counts as lateness.
+    pub fn evaluate_robustness(&self, params: &NoiseParams) -> RobustnessReport {
+        let mut rng = StdRng::seed_from_u64(params.seed);
+        let n_samples = params.n_samples.max(1);
+
+        let mut routes = vec![RouteRobustness::default(); self.routes.len()];
+        let mut violations = 0;
+
+        for _ in 0..n_samples {
+            let mut violated = false;
+
+            for (route, robustness) in self.routes.iter().zip(routes.iter_mut()) {
+                let (cost, lateness) = simulate(route, params, &mut rng);
+
+                if lateness > 0.0 {
+                    robustness.violation_probability += 1.0;
+                    violated = true;
+                }
+                robustness.expected_lateness += lateness;
+                robustness.expected_cost += cost;
+            }
+
+            if violated {
+                violations += 1;
+            }
+        }
+
+        for robustness in routes.iter_mut() {
+            robustness.violation_probability /= n_samples as f32;
+            robustness.expected_lateness /= n_samples as f32;
+            robustness.expected_cost /= n_samples as f32;
+        }
+
+        RobustnessReport {
+            violation_probability: violations as f32 / n_samples as f32,
+            expected_lateness: routes.iter().map(|r| r.expected_lateness).sum(),
+            expected_cost: routes.iter().map(|r| r.expected_cost).sum(),
+            routes,
+        }
+    }
+}
+
+impl RobustnessReport {
+    /// Print this report to a Markdown string
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("# Robustness\n\n");
+        output.push_str(
+            &format! {"- Violation probability: {:.1}%\n", self.violation_probability * 100.0},
+        );
+        output.push_str(&format! {"- Expected lateness: {}\n", self.expected_lateness});
+        output.push_str(&format! {"- Expected cost: {}\n\n", self.expected_cost});
+
+        output.push_str("| Route | Violation probability | Expected lateness | Expected cost |\n");
+        output.push_str("|---|---|---|---|\n");
+        for (i, route) in self.routes.iter().enumerate() {
+            output.push_str(&format! {
+                "| {} | {:.1}% | {} | {} |\n",
+                i + 1,
+                route.violation_probability * 100.0,
+                route.expected_lateness,
+                route.expected_cost
+            });
+        }
+        output
+    }
+}
+
+/// Run one perturbed execution of `route`, returns its cost and total lateness
+fn simulate(route: &Route, params: &NoiseParams, rng: &mut StdRng) -> (f32, f32) {
+    let mut time = 0.0;
+    let mut lateness = 0.0;
+
+    for i in 1..route.len() {
+        let location = &route[i];
+
+        time += route[i - 1].travel_time_to(location) * params.travel_time.sample(rng);
+        lateness += (time - location.due_date as f32).max(0.0);
+
+        // The warehouse is the last location, there is nothing to serve there
+        if i < route.len() - 1 {
+            time = time.max(location.ready_time as f32)
+                + location.service_time as f32 * params.service_time.sample(rng);
+        }
+    }
+
+    (time, lateness)
+}
@@ -1,10 +1,129 @@
-use crate::location::Location;
+use crate::location::{Location, LocationKind, Pairing};
+#[cfg(feature = "plot")]
+use crate::report::{draw_route_arcs, save_plot, Plot, PlotFormat};
+#[cfg(feature = "plot")]
+use plotters::coord::Shift;
+#[cfg(feature = "plot")]
 use plotters::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+#[cfg(feature = "plot")]
+use std::path::Path;
+
+/// Timing of the visit to one customer of a [Route]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Stop {
+    pub arrival: f32,
+    pub waiting_time: f32,
+    pub departure: f32,
+}
+
+/// A break the driver takes at a stop, starting between `earliest` and `latest`, see
+/// [breaks](Route::breaks)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DriverBreak {
+    pub earliest: f64,
+    pub latest: f64,
+    pub duration: f64,
+}
+
+/// Why a route is not feasible
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteError {
+    /// Merged routes start from different warehouses, or only one of them is open
+    DifferentWarehouses,
+    /// The vehicle carries more than its capacity, `demand` is the most it carries at once
+    OverCapacity { demand: f64, capacity: f64 },
+    /// A customer of a pickup and delivery pair is served without its partner, or a delivery
+    /// before its pickup
+    BrokenPair { customer: u16 },
+    /// A customer is delivered from the warehouse after a backhaul
+    BackhaulFirst { customer: u16 },
+    /// The vehicle reaches a customer after its due date
+    LateArrival { customer: u16, arrival: f32 },
+    /// The vehicle is back at the warehouse after it closes
+    LateReturn { arrival: f32 },
+    /// The route ends after its max duration
+    TooLong { duration: f32, max_duration: f64 },
+    /// The driver cannot start a break before its latest start
+    LateBreak { start: f32, latest: f64 },
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::DifferentWarehouses => write!(f, "The routes use different warehouses"),
+            RouteError::OverCapacity { demand, capacity } => {
+                write!(
+                    f,
+                    "Demand of {} exceeds the capacity of {}",
+                    demand, capacity
+                )
+            }
+            RouteError::BrokenPair { customer } => {
+                write!(
+                    f,
+                    "Customer {} is not served with its pickup and delivery partner, pickup first",
+                    customer
+                )
+            }
+            RouteError::BackhaulFirst { customer } => {
+                write!(f, "Customer {} is delivered after a backhaul", customer)
+            }
+            RouteError::LateArrival { customer, arrival } => {
+                write!(
+                    f,
+                    "Customer {} is reached too late, at {}",
+                    customer, arrival
+                )
+            }
+            RouteError::LateReturn { arrival } => {
+                write!(
+                    f,
+                    "The vehicle is back at the warehouse too late, at {}",
+                    arrival
+                )
+            }
+            RouteError::TooLong {
+                duration,
+                max_duration,
+            } => {
+                write!(
+                    f,
+                    "The route takes {}, more than the limit of {}",
+                    duration, max_duration
+                )
+            }
+            RouteError::LateBreak { start, latest } => {
+                write!(
+                    f,
+                    "The driver starts a break at {}, after its latest start of {}",
+                    start, latest
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RouteError {}
 
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Route {
     pub warehouse: Location,
     pub customers: Vec<Location>,
+    /// The vehicle stops at the last customer instead of coming back to the warehouse, the
+    /// final leg is left out of costs, checks, printing and plotting. Indexing and iterating
+    /// still end with the warehouse
+    pub open: bool,
+    /// Longest the route may take from leaving the warehouse at 0 to its end, see
+    /// [max_route_duration](crate::vrp::Vrp::max_route_duration)
+    pub max_duration: Option<f64>,
+    /// Breaks the driver takes in order, see [driver_breaks](crate::vrp::Vrp::driver_breaks).
+    /// A break is taken at the last stop before the one the vehicle would leave after its
+    /// latest start, or before the last leg of a route coming back after it. Routes ending
+    /// sooner skip it
+    pub breaks: Vec<DriverBreak>,
 }
 
 impl Route {
@@ -14,8 +133,19 @@ impl Route {
     pub fn is_empty(&self) -> bool {
         self.customers.is_empty()
     }
+
+    // Get the number of legs the vehicle drives, from the warehouse up to the last customer
+    // for open routes
+    pub fn n_legs(&self) -> usize {
+        if self.open {
+            self.customers.len()
+        } else {
+            self.len() - 1
+        }
+    }
+
     pub fn total_distance(&self) -> f32 {
-        (0..self.len() - 1)
+        (0..self.n_legs())
             .map(|i| self[i].distance_to(&self[i + 1]))
             .sum::<f32>()
     }
@@ -24,8 +154,10 @@ impl Route {
     // Get the cost for this route (distance + waiting time + service time)
     pub fn total_cost(&self) -> f32 {
         let mut cost = 0.0;
+        let mut breaks = BreakPlan::new(&self.breaks);
 
-        for i in 0..self.len() - 1 {
+        for i in 0..self.n_legs() {
+            cost = breaks.depart(cost, |d| self[i].cost_to_deliver(&self[i + 1], d));
             cost = self[i].cost_to_deliver(&self[i + 1], cost)
         }
 
@@ -34,21 +166,31 @@ impl Route {
 
     // Get the cost for this route using a separate array of customers (distance + waiting time + service time)
     pub fn total_cost_with(&self, customers: &[&Location]) -> f32 {
-        let mut cost = self.warehouse.cost_to_deliver(customers[0], 0f32);
+        let mut breaks = BreakPlan::new(&self.breaks);
+        let mut cost = breaks.depart(0f32, |d| self.warehouse.cost_to_deliver(customers[0], d));
+        cost = self.warehouse.cost_to_deliver(customers[0], cost);
 
         for i in 0..customers.len() - 1 {
+            cost = breaks.depart(cost, |d| customers[i].cost_to_deliver(customers[i + 1], d));
             cost = customers[i].cost_to_deliver(customers[i + 1], cost)
         }
 
-        self.customers[customers.len() - 1].cost_to_deliver(&self.warehouse, cost)
+        if self.open {
+            return cost;
+        }
+        let last = customers[customers.len() - 1];
+        cost = breaks.depart(cost, |d| last.cost_to_deliver(&self.warehouse, d));
+        last.cost_to_deliver(&self.warehouse, cost)
     }
 
     // -- Calculate total route cost without service time --
     // Get the cost for this route (distance + waiting time)
     pub fn total_cost_no_service_time(&self) -> f32 {
         let mut cost = 0.0;
+        let mut breaks = BreakPlan::new(&self.breaks);
 
-        for i in 0..self.len() - 1 {
+        for i in 0..self.n_legs() {
+            cost = breaks.depart(cost, |d| self[i].cost_to_delivery_window(&self[i + 1], d));
             cost = self[i].cost_to_delivery_window(&self[i + 1], cost)
         }
 
@@ -57,27 +199,68 @@ impl Route {
 
     // Get the cost for this route using a separate array of customers (distance + waiting time)
     pub fn total_cost_no_service_time_with(&self, customers: &[&Location]) -> f32 {
-        let mut cost = self.warehouse.cost_to_delivery_window(customers[0], 0f32);
+        let mut breaks = BreakPlan::new(&self.breaks);
+        let mut cost = breaks.depart(0f32, |d| {
+            self.warehouse.cost_to_delivery_window(customers[0], d)
+        });
+        cost = self.warehouse.cost_to_delivery_window(customers[0], cost);
 
         for i in 0..customers.len() - 1 {
+            cost = breaks.depart(cost, |d| {
+                customers[i].cost_to_delivery_window(customers[i + 1], d)
+            });
             cost = customers[i].cost_to_delivery_window(customers[i + 1], cost)
         }
 
-        self.customers[customers.len() - 1].cost_to_delivery_window(&self.warehouse, cost)
+        if self.open {
+            return cost;
+        }
+        let last = customers[customers.len() - 1];
+        cost = breaks.depart(cost, |d| last.cost_to_delivery_window(&self.warehouse, d));
+        last.cost_to_delivery_window(&self.warehouse, cost)
     }
 
     // -- Calculate the total demand of all customers in the route
-    pub fn total_demand(&self) -> u16 {
+    pub fn total_demand(&self) -> f64 {
         self.customers.iter().map(|c| c.demand).sum()
     }
 
-    pub fn total_demand_with(customers: &[&Location]) -> u16 {
+    pub fn total_demand_with(customers: &[&Location]) -> f64 {
         customers.iter().map(|c| c.demand).sum()
     }
 
+    // -- Get the most the vehicle carries at once --
+    // The total demand, unless the route serves pickup and delivery pairs or backhauls: their
+    // demand is carried from the pickup to the delivery, or from the backhaul to the warehouse
+    pub fn max_load(&self) -> f64 {
+        peak_load(self.customers.iter())
+    }
+
+    pub fn max_load_with(customers: &[&Location]) -> f64 {
+        peak_load(customers.iter().copied())
+    }
+
+    // -- Get the least peak load of this route with `inserted` served anywhere --
+    // The linehaul customers are all on board leaving the warehouse and the backhauls coming
+    // back, wherever they are served. Unlike the total demand, exceeding the capacity rules
+    // out every position
+    pub fn min_load_with(&self, inserted: &[&Location]) -> f64 {
+        let customers = self.customers.iter().chain(inserted.iter().copied());
+        let (linehaul, backhaul) = customers.fold((0.0, 0.0), |(linehaul, backhaul), c| {
+            if c.is_linehaul() {
+                (linehaul + c.demand, backhaul)
+            } else if c.kind == LocationKind::Backhaul {
+                (linehaul, backhaul + c.demand)
+            } else {
+                (linehaul, backhaul)
+            }
+        });
+        f64::max(linehaul, backhaul)
+    }
+
     // -- Check if route is valid --
-    pub fn is_valid(&self, capacity: u16) -> bool {
-        if self.total_demand() > capacity {
+    pub fn is_valid(&self, capacity: f64) -> bool {
+        if !self.is_load_valid(capacity) {
             return false;
         }
 
@@ -85,37 +268,23 @@ impl Route {
             return true;
         }
 
-        let mut cost = self.warehouse.cost_to(&self.customers[0], 0f32);
-
-        for (i, customer) in self.customers.iter().enumerate() {
-            if cost > customer.due_date as f32 {
-                return false;
-            }
-
-            cost += (customer.ready_time as f32 - cost).max(0f32); // Add potentital waiting time
-            cost += customer.service_time as f32; // Add service time
-
-            // If this is not the last customer, add the cost to the next customer
-            if i < self.customers.len() - 1 {
-                cost = customer.cost_to(&self.customers[i + 1], cost)
-            }
-        }
-
-        cost = self
-            .customers
-            .last()
-            .unwrap()
-            .cost_to(&self.warehouse, cost);
-
-        if cost > self.warehouse.due_date as f32 {
-            return false;
-        }
+        let customers: Vec<&Location> = self.customers.iter().collect();
+        self.is_schedule_valid(&customers)
+    }
 
-        true
+    // Check the peak load, the pickup and delivery pairs and the backhauls of the route,
+    // everything but its schedule
+    pub(crate) fn is_load_valid(&self, capacity: f64) -> bool {
+        peak_load(self.customers.iter()) <= capacity
+            && broken_pair(self.customers.iter()).is_none()
+            && linehaul_after_backhaul(self.customers.iter()).is_none()
     }
 
-    pub fn is_valid_with(&self, customers: &[&Location], capacity: u16) -> bool {
-        if Route::total_demand_with(customers) > capacity {
+    pub fn is_valid_with(&self, customers: &[&Location], capacity: f64) -> bool {
+        if peak_load(customers.iter().copied()) > capacity
+            || broken_pair(customers.iter().copied()).is_some()
+            || linehaul_after_backhaul(customers.iter().copied()).is_some()
+        {
             return false;
         }
 
@@ -123,7 +292,15 @@ impl Route {
             return true;
         }
 
-        let mut cost = self.warehouse.cost_to(customers[0], 0f32);
+        self.is_schedule_valid(customers)
+    }
+
+    // Check the time windows, breaks and max duration of this route serving the non-empty
+    // `customers`
+    fn is_schedule_valid(&self, customers: &[&Location]) -> bool {
+        let mut breaks = BreakPlan::new(&self.breaks);
+        let mut cost = breaks.depart(0f32, |d| self.warehouse.cost_to_deliver(customers[0], d));
+        cost = self.warehouse.cost_to(customers[0], cost);
 
         for (i, customer) in customers.iter().enumerate() {
             if cost > customer.due_date as f32 {
@@ -135,21 +312,32 @@ impl Route {
 
             // If this is not the last customer, add the cost to the next customer
             if i < customers.len() - 1 {
-                cost = customer.cost_to(customers[i + 1], cost)
+                let next = customers[i + 1];
+                cost = breaks.depart(cost, |d| customer.cost_to_deliver(next, d));
+                cost = customer.cost_to(next, cost)
             }
         }
 
-        cost = customers.last().unwrap().cost_to(&self.warehouse, cost);
+        if !self.open {
+            let last = customers[customers.len() - 1];
+            cost = breaks.depart(cost, |d| last.cost_to(&self.warehouse, d));
+            cost = last.cost_to(&self.warehouse, cost);
 
-        if cost > self.warehouse.due_date as f32 {
-            return false;
+            if cost > self.warehouse.due_date as f32 {
+                return false;
+            }
         }
 
-        true
+        // The route ends at the warehouse, or at the last customer of open routes
+        breaks.late().is_none() && self.max_duration.is_none_or(|max| cost <= max as f32)
     }
 
     // -- Try and insert a customer into the route, find the best index --
-    pub fn try_insert(&self, customer: &Location, capacity: u16) -> Option<(f32, u16)> {
+    pub fn try_insert(&self, customer: &Location, capacity: f64) -> Option<(f32, u16)> {
+        if !self.can_possibly_insert(customer, capacity) {
+            return None;
+        }
+
         let mut min_cost = f32::INFINITY;
         let mut min_index = 0;
 
@@ -182,6 +370,223 @@ impl Route {
         Some((min_cost, min_index as u16))
     }
 
+    // -- Compute the schedule of the route --
+    // Get the arrival, waiting and departure times at each customer, leaving the warehouse at 0
+    pub fn schedule(&self) -> Vec<Stop> {
+        self.schedule_from(0.0)
+    }
+
+    // -- Compute the schedule of the route for a later departure --
+    // Same as schedule, leaving the warehouse at `departure`
+    pub fn schedule_from(&self, departure: f32) -> Vec<Stop> {
+        self.walk(departure).0
+    }
+
+    // -- Get the start of the breaks the driver takes --
+    // In the order of breaks, leaving the warehouse at 0. Breaks the route ends too soon for
+    // are left out
+    pub fn break_starts(&self) -> Vec<f32> {
+        self.walk(0.0).1.starts
+    }
+
+    // Get the schedule leaving the warehouse at `departure`, the breaks taken and the end of
+    // the route: the return to the warehouse, or leaving the last customer of open routes
+    fn walk(&self, departure: f32) -> (Vec<Stop>, BreakPlan<'_>, f32) {
+        let mut stops = Vec::with_capacity(self.customers.len());
+        let mut breaks = BreakPlan::new(&self.breaks);
+        let mut cost = departure;
+
+        for i in 1..self.len() - 1 {
+            let customer = &self[i];
+            cost = breaks.depart(cost, |d| self[i - 1].cost_to_deliver(customer, d));
+            let arrival = cost + self[i - 1].travel_time_to(customer);
+            let waiting_time = (customer.ready_time as f32 - arrival).max(0.0);
+
+            cost = arrival + waiting_time + customer.service_time as f32;
+
+            stops.push(Stop {
+                arrival,
+                waiting_time,
+                departure: cost,
+            });
+        }
+
+        if let (Some(last), false) = (self.customers.last(), self.open) {
+            cost = breaks.depart(cost, |d| last.cost_to(&self.warehouse, d));
+            cost = last.cost_to(&self.warehouse, cost);
+        }
+
+        (stops, breaks, cost)
+    }
+
+    // -- Check if route is valid, returning the first broken constraint --
+    // Same checks as is_valid
+    pub fn validate(&self, capacity: f64) -> Result<(), RouteError> {
+        let demand = self.max_load();
+        if demand > capacity {
+            return Err(RouteError::OverCapacity { demand, capacity });
+        }
+        if let Some(customer) = broken_pair(self.customers.iter()) {
+            return Err(RouteError::BrokenPair { customer });
+        }
+        if let Some(customer) = linehaul_after_backhaul(self.customers.iter()) {
+            return Err(RouteError::BackhaulFirst { customer });
+        }
+
+        let (schedule, breaks, end) = self.walk(0.0);
+
+        for (customer, stop) in self.customers.iter().zip(&schedule) {
+            if stop.arrival > customer.due_date as f32 {
+                return Err(RouteError::LateArrival {
+                    customer: customer.id,
+                    arrival: stop.arrival,
+                });
+            }
+        }
+
+        if let Some((start, latest)) = breaks.late() {
+            return Err(RouteError::LateBreak { start, latest });
+        }
+
+        if !self.customers.is_empty() && !self.open && end > self.warehouse.due_date as f32 {
+            return Err(RouteError::LateReturn { arrival: end });
+        }
+
+        // The route ends at the warehouse, or at the last customer of open routes
+        if let Some(max_duration) = self.max_duration {
+            if end > max_duration as f32 {
+                return Err(RouteError::TooLong {
+                    duration: end,
+                    max_duration,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    // -- Get the time the route takes --
+    // From leaving the warehouse at 0 to coming back, or to leaving the last customer of open
+    // routes. 0 for empty routes
+    pub fn duration(&self) -> f32 {
+        if self.customers.is_empty() {
+            return 0.0;
+        }
+        self.walk(0.0).2
+    }
+
+    // -- Split the route in two --
+    // The first route serves the customers before `index`, the second the others. Panics if
+    // `index` is greater than the number of customers, like Vec::split_at
+    pub fn split_at(&self, index: usize) -> (Route, Route) {
+        let (first, second) = self.customers.split_at(index);
+
+        (
+            Route {
+                warehouse: self.warehouse.clone(),
+                customers: first.to_vec(),
+                open: self.open,
+                max_duration: self.max_duration,
+                breaks: self.breaks.clone(),
+            },
+            Route {
+                warehouse: self.warehouse.clone(),
+                customers: second.to_vec(),
+                open: self.open,
+                max_duration: self.max_duration,
+                breaks: self.breaks.clone(),
+            },
+        )
+    }
+
+    // -- Merge two routes --
+    // Serve the customers of `other` after the ones of this route, fails if the routes start
+    // from different warehouses or if the merged route is not valid for `capacity`
+    pub fn merge(self, other: Route, capacity: f64) -> Result<Route, RouteError> {
+        if self.warehouse != other.warehouse || self.open != other.open {
+            return Err(RouteError::DifferentWarehouses);
+        }
+
+        let mut merged = self;
+        merged.customers.extend(other.customers);
+        merged.validate(capacity)?;
+
+        Ok(merged)
+    }
+
+    // -- Reverse the route --
+    // Serve the customers in the opposite order, fails if the reversed route is not valid for
+    // `capacity`. Time windows rarely allow both directions, the cost is computed again since
+    // travel costs may depend on the direction
+    pub fn reversed(&self, capacity: f64) -> Result<Route, RouteError> {
+        let reversed = Route {
+            warehouse: self.warehouse.clone(),
+            customers: self.customers.iter().rev().cloned().collect(),
+            open: self.open,
+            max_duration: self.max_duration,
+            breaks: self.breaks.clone(),
+        };
+        reversed.validate(capacity)?;
+
+        Ok(reversed)
+    }
+
+    // -- Pick the orientation of the route --
+    // Keep the valid direction, then the cheapest one. When both directions are as good, like
+    // without waiting times, the one visiting the lowest customer ids first is kept, so a route
+    // and its mirror image are normalized to the same route
+    pub fn normalized(&self, capacity: f64) -> Route {
+        let reversed = Route {
+            warehouse: self.warehouse.clone(),
+            customers: self.customers.iter().rev().cloned().collect(),
+            open: self.open,
+            max_duration: self.max_duration,
+            breaks: self.breaks.clone(),
+        };
+
+        let (valid, reversed_valid) = (self.is_valid(capacity), reversed.is_valid(capacity));
+        if valid != reversed_valid {
+            return if valid { self.clone() } else { reversed };
+        }
+
+        let (cost, reversed_cost) = (self.total_cost(), reversed.total_cost());
+        // The directions sum the same terms in another order, allow for rounding errors
+        if (cost - reversed_cost).abs() > 1e-5 * cost.max(reversed_cost) {
+            return if cost < reversed_cost {
+                self.clone()
+            } else {
+                reversed
+            };
+        }
+
+        if self
+            .customers
+            .iter()
+            .map(|c| c.id)
+            .le(reversed.customers.iter().map(|c| c.id))
+        {
+            self.clone()
+        } else {
+            reversed
+        }
+    }
+
+    // -- Count the customers the vehicle is committed to at `current_time` --
+    // A customer is committed once the vehicle has left the previous stop (the warehouse is left
+    // at 0), the schedule of these customers can no longer change
+    pub fn fixed_prefix_len(&self, current_time: f32) -> usize {
+        let mut departure = 0.0;
+
+        for (i, stop) in self.schedule().iter().enumerate() {
+            if departure > current_time {
+                return i;
+            }
+            departure = stop.departure;
+        }
+
+        self.customers.len()
+    }
+
     // -- Print the route --
     pub fn print(&self, name: Option<&str>) -> &Route {
         print!("{}", self.print_to_string(name));
@@ -200,64 +605,79 @@ impl Route {
         output.push_str(&format!("Total customers: {}\n", self.customers.len()));
         output.push('\n');
 
+        output.push_str(&self.stops_to_string());
+
+        output
+    }
+
+    // Get the timeline of the route, from the warehouse to the warehouse or the last customer
+    // of open routes, with the breaks the driver takes
+    fn stops_to_string(&self) -> String {
+        let mut output = String::new();
+        let mut breaks = BreakPlan::new(&self.breaks);
         let mut cost = 0.0;
 
         output.push_str(&format!(
             "{:<30} ID: {}  TW: {} - {}\n",
             "■ Warehouse", self.warehouse.id, self.warehouse.ready_time, self.warehouse.due_date
         ));
-        output.push_str(&format!("|   Departure: {}\n", cost));
-        output.push_str("|\n");
 
-        cost += if !self.customers.is_empty() {
-            self.warehouse.distance_to(&self.customers[0])
-        } else {
-            0.0
-        };
+        for i in 0..self.n_legs() {
+            let (from, to) = (&self[i], &self[i + 1]);
+
+            let taken = breaks.starts.len();
+            cost = if i < self.customers.len() {
+                breaks.depart(cost, |d| from.cost_to_deliver(to, d))
+            } else {
+                breaks.depart(cost, |d| from.cost_to(to, d))
+            };
+            for (j, start) in breaks.starts.iter().enumerate().skip(taken) {
+                output.push_str(&format!(
+                    "☕ Break {}/{}  Start: {}  Duration: {}\n",
+                    j + 1,
+                    self.breaks.len(),
+                    start,
+                    self.breaks[j].duration
+                ));
+            }
+            output.push_str(&format!("|   Departure: {}\n", cost));
+            output.push_str("|\n");
 
-        for (i, customer) in self.customers.iter().enumerate() {
+            cost += from.travel_time_to(to);
             output.push_str("|\n");
             output.push_str(&format!("▼   Arrival: {}\n", cost));
-            output.push_str(&format!(
-                "{:<30} ID: {}  TW: {} - {}\n",
-                format!("⌂ Customer {}/{}", i + 1, self.customers.len()),
-                customer.id,
-                customer.ready_time,
-                customer.due_date
-            ));
-
-            let waiting_time = (customer.ready_time as f32 - cost).max(0.0);
+
+            if i == self.customers.len() {
+                output.push_str(&format!(
+                    "{:<30} ID: {}  TW: {} - {}\n",
+                    "■ Warehouse",
+                    self.warehouse.id,
+                    self.warehouse.ready_time,
+                    self.warehouse.due_date
+                ));
+                break;
+            }
+
+            output.push_str(&stop_line(to, i, self.customers.len()));
+
+            let waiting_time = (to.ready_time as f32 - cost).max(0.0);
             output.push_str(&format!("… Waiting Time: {}\n", waiting_time));
             cost += waiting_time;
-            output.push_str(&format!("… Service Time: {}\n", customer.service_time));
-            cost += customer.service_time as f32;
+            output.push_str(&format!("… Service Time: {}\n", to.service_time));
+            cost += to.service_time as f32;
+        }
+
+        if self.open {
             output.push_str(&format!("|   Departure: {}\n", cost));
             output.push_str("|\n");
-
-            if i < self.customers.len() - 1 {
-                cost += customer.distance_to(&self.customers[i + 1]);
-            }
         }
 
-        cost += if !self.customers.is_empty() {
-            self.customers.last().unwrap().distance_to(&self.warehouse)
-        } else {
-            0.0
-        };
-
-        output.push_str("|\n");
-        output.push_str(&format!("▼   Arrival: {}\n", cost));
-        output.push_str(&format!(
-            "{:<30} ID: {}  TW: {} - {}\n",
-            "■ Warehouse", self.warehouse.id, self.warehouse.ready_time, self.warehouse.due_date
-        ));
-
         output
     }
 
     pub fn print_to_md_string(
         &self,
-        vehicle_capacity: u16,
+        vehicle_capacity: f64,
         coord_bounds: (i32, i32, i32, i32),
     ) -> String {
         let mut output = String::new();
@@ -276,147 +696,105 @@ impl Route {
             self.is_valid(vehicle_capacity)
         ));
 
-        output.push_str("\n#### Display\n\n");
-
-        output.push_str(&self.plot(coord_bounds));
-
-        output.push_str("\n#### Locations\n\n");
-
-        let mut cost = 0.0;
-
-        output.push_str("```\n");
-
-        output.push_str(&format!(
-            "{:<30} ID: {}  TW: {} - {}\n",
-            "■ Warehouse", self.warehouse.id, self.warehouse.ready_time, self.warehouse.due_date
-        ));
-        output.push_str(&format!("|   Departure: {}\n", cost));
-        output.push_str("|\n");
+        #[cfg(feature = "plot")]
+        {
+            output.push_str("\n#### Display\n\n");
 
-        cost += if !self.customers.is_empty() {
-            self.warehouse.distance_to(&self.customers[0])
-        } else {
-            0.0
-        };
+            output.push_str(&self.plot(coord_bounds));
 
-        for (i, customer) in self.customers.iter().enumerate() {
-            output.push_str("|\n");
-            output.push_str(&format!("▼   Arrival: {}\n", cost));
-            output.push_str(&format!(
-                "{:<30} ID: {}  TW: {} - {}\n",
-                format!("⌂ Customer {}/{}", i + 1, self.customers.len()),
-                customer.id,
-                customer.ready_time,
-                customer.due_date
-            ));
-
-            let waiting_time = (customer.ready_time as f32 - cost).max(0.0);
-            output.push_str(&format!("… Waiting Time: {}\n", waiting_time));
-            cost += waiting_time;
-            output.push_str(&format!("… Service Time: {}\n", customer.service_time));
-            cost += customer.service_time as f32;
-            output.push_str(&format!("|   Departure: {}\n", cost));
-            output.push_str("|\n");
+            if !self.customers.is_empty() {
+                output.push_str("\n#### Schedule\n\n");
 
-            if i < self.customers.len() - 1 {
-                cost += customer.distance_to(&self.customers[i + 1]);
+                output.push_str(&self.plot_schedule());
             }
         }
+        #[cfg(not(feature = "plot"))]
+        let _ = coord_bounds;
 
-        cost += if !self.customers.is_empty() {
-            self.customers.last().unwrap().distance_to(&self.warehouse)
-        } else {
-            0.0
-        };
+        output.push_str("\n#### Locations\n\n");
 
-        output.push_str("|\n");
-        output.push_str(&format!("▼   Arrival: {}\n", cost));
-        output.push_str(&format!(
-            "{:<30} ID: {}  TW: {} - {}\n",
-            "■ Warehouse", self.warehouse.id, self.warehouse.ready_time, self.warehouse.due_date
-        ));
+        output.push_str("```\n");
+
+        output.push_str(&self.stops_to_string());
 
         output.push_str("```\n");
 
         output
     }
+    #[cfg(feature = "plot")]
     pub fn plot(&self, coord_bounds: (i32, i32, i32, i32)) -> String {
         let mut svg_data: String = String::new();
         {
             let root = SVGBackend::with_string(&mut svg_data, (800, 480)).into_drawing_area();
-            root.fill(&WHITE).unwrap();
-
-            let mut chart = ChartBuilder::on(&root)
-                .x_label_area_size(35)
-                .y_label_area_size(40)
-                .build_cartesian_2d(
-                    coord_bounds.0..coord_bounds.1,
-                    coord_bounds.2..coord_bounds.3,
-                )
-                .unwrap();
-
-            chart
-                .configure_mesh()
-                .x_desc("X")
-                .y_desc("Y")
-                .draw()
-                .unwrap();
-
-            // -- Plot the route --
-            let route_iter = std::iter::once((self.warehouse.x as i32, self.warehouse.y as i32))
-                .chain(
-                    self.customers
-                        .iter()
-                        .map(|c| (c.x as i32, c.y as i32))
-                        .chain(std::iter::once((
-                            self.warehouse.x as i32,
-                            self.warehouse.y as i32,
-                        ))),
-                );
-
-            // Plot the route
-            chart
-                .draw_series(LineSeries::new(route_iter, &GREEN))
-                .unwrap();
-
-            // -- Plot the locations --
-            // Plot the warehouse
-            chart
-                .draw_series(std::iter::once(Circle::new(
-                    (self.warehouse.x as i32, self.warehouse.y as i32),
-                    5,
-                    RED.filled(),
-                )))
-                .unwrap();
-
-            // Plot the customers
-            for customer in &self.customers {
-                chart
-                    .draw_series(std::iter::once(Circle::new(
-                        (customer.x as i32, customer.y as i32),
-                        5,
-                        BLUE.filled(),
-                    )))
-                    .unwrap();
-
-                chart
-                    .draw_series(std::iter::once(Text::new(
-                        format!("{}", customer.id),
-                        (customer.x as i32 + 1, customer.y as i32 + 1),
-                        ("sans-serif", 15).into_font(),
-                    )))
-                    .unwrap();
+            RoutePlot {
+                route: self,
+                coord_bounds,
             }
+            .draw(&root);
+            root.present().unwrap();
+        }
+        svg_data
+    }
 
+    /// Plot the route like [plot](Route::plot) to an image of `size` pixels at `path`
+    #[cfg(feature = "plot")]
+    pub fn plot_to_file(
+        &self,
+        coord_bounds: (i32, i32, i32, i32),
+        path: impl AsRef<Path>,
+        format: PlotFormat,
+        size: (u32, u32),
+    ) -> std::io::Result<()> {
+        let plot = RoutePlot {
+            route: self,
+            coord_bounds,
+        };
+        save_plot(&plot, path.as_ref(), format, size)
+    }
+
+    /// Plot the schedule of the route as a Gantt chart with one row per stop in visit order:
+    /// the time window of the customer, then the travel to it, the waiting and the service.
+    /// Breaks are drawn on the row of the leg they delay, closed routes end with a row for the
+    /// return to the warehouse
+    #[cfg(feature = "plot")]
+    pub fn plot_schedule(&self) -> String {
+        let plot = SchedulePlot { route: self };
+        let mut svg_data: String = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg_data, plot.size()).into_drawing_area();
+            plot.draw(&root);
             root.present().unwrap();
         }
         svg_data
     }
 
-    pub fn iter(&self) -> RouteIterator {
+    /// Plot the schedule like [plot_schedule](Route::plot_schedule) to an image of `size`
+    /// pixels at `path`
+    #[cfg(feature = "plot")]
+    pub fn plot_schedule_to_file(
+        &self,
+        path: impl AsRef<Path>,
+        format: PlotFormat,
+        size: (u32, u32),
+    ) -> std::io::Result<()> {
+        save_plot(&SchedulePlot { route: self }, path.as_ref(), format, size)
+    }
+
+    // Get the location at `index` like indexing, the warehouse at both ends, or None if out of bounds
+    pub fn get(&self, index: usize) -> Option<&Location> {
+        match index {
+            0 => Some(&self.warehouse),
+            x if x <= self.customers.len() => Some(&self.customers[x - 1]),
+            x if x == self.customers.len() + 1 => Some(&self.warehouse),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> RouteIterator<'_> {
         RouteIterator {
             route: self,
             index: 0,
+            end: self.len(),
         }
     }
 }
@@ -425,39 +803,425 @@ impl std::ops::Index<usize> for Route {
     type Output = Location;
 
     fn index(&self, index: usize) -> &Self::Output {
-        match index {
-            0 => &self.warehouse,
-            x if x <= self.customers.len() => &self.customers[index - 1],
-            x if x == self.customers.len() + 1 => &self.warehouse,
-            _ => panic!("Index out of bounds"),
+        self.get(index).expect("Index out of bounds")
+    }
+}
+
+impl<'a> IntoIterator for &'a Route {
+    type Item = &'a Location;
+    type IntoIter = RouteIterator<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Get the most a vehicle serving `customers` in order carries at once. Linehaul customers are
+// loaded at the warehouse, pickups and backhauls load their demand, deliveries and linehaul
+// customers unload it
+fn peak_load<'a>(customers: impl Iterator<Item = &'a Location> + Clone) -> f64 {
+    let mut load: f64 = customers
+        .clone()
+        .filter(|c| c.is_linehaul())
+        .map(|c| c.demand)
+        .sum();
+    let mut peak = load;
+
+    for customer in customers {
+        let demand = customer.demand;
+        match (customer.kind, customer.pairing) {
+            (_, Some(Pairing::Pickup { .. })) | (LocationKind::Backhaul, _) => load += demand,
+            (_, Some(Pairing::Delivery { .. })) => load = (load - demand).max(0.0),
+            _ if customer.is_linehaul() => load = (load - demand).max(0.0),
+            _ => {}
+        }
+        peak = peak.max(load);
+    }
+
+    peak
+}
+
+// The breaks of a route taken while walking along it, see Route::breaks
+struct BreakPlan<'a> {
+    breaks: &'a [DriverBreak],
+    // Start of the breaks taken so far
+    starts: Vec<f32>,
+}
+
+impl<'a> BreakPlan<'a> {
+    fn new(breaks: &'a [DriverBreak]) -> BreakPlan<'a> {
+        BreakPlan {
+            breaks,
+            starts: Vec::new(),
         }
     }
+
+    // Take the breaks due at a stop left at `departure`, before a leg ending at `end(departure)`:
+    // leaving the next customer, or the end of the route. Returns the new departure, a break
+    // starts once the driver is done at the stop and its window is open
+    fn depart(&mut self, mut departure: f32, end: impl Fn(f32) -> f32) -> f32 {
+        while let Some(driver_break) = self.breaks.get(self.starts.len()) {
+            if end(departure) <= driver_break.latest as f32 {
+                break;
+            }
+            let start = departure.max(driver_break.earliest as f32);
+            self.starts.push(start);
+            departure = start + driver_break.duration as f32;
+        }
+        departure
+    }
+
+    // Get the start and latest start of the first break taken too late
+    fn late(&self) -> Option<(f32, f64)> {
+        self.starts
+            .iter()
+            .zip(self.breaks)
+            .find(|(&start, b)| start > b.latest as f32)
+            .map(|(&start, b)| (start, b.latest))
+    }
+}
+
+// Get the first linehaul customer of `customers` served after a backhaul
+fn linehaul_after_backhaul<'a>(mut customers: impl Iterator<Item = &'a Location>) -> Option<u16> {
+    customers
+        .position(|c| c.kind == LocationKind::Backhaul)
+        .and_then(|_| customers.find(|c| c.is_linehaul()))
+        .map(|c| c.id)
+}
+
+// Get the first customer of `customers` whose pickup and delivery partner is not in the
+// sequence, or on the wrong side of it
+fn broken_pair<'a>(customers: impl Iterator<Item = &'a Location> + Clone) -> Option<u16> {
+    if customers.clone().all(|c| c.pairing.is_none()) {
+        return None;
+    }
+
+    let positions: HashMap<u16, usize> = customers
+        .clone()
+        .enumerate()
+        .map(|(i, c)| (c.id, i))
+        .collect();
+
+    customers
+        .enumerate()
+        .find(|&(i, c)| match c.pairing {
+            None => false,
+            Some(Pairing::Pickup { delivery }) => positions.get(&delivery).is_none_or(|&j| j < i),
+            Some(Pairing::Delivery { pickup }) => positions.get(&pickup).is_none_or(|&j| j > i),
+        })
+        .map(|(_, c)| c.id)
+}
+
+// Get the report line of the `i`th of `n` customers, with its name if it has one
+fn stop_line(location: &Location, i: usize, n: usize) -> String {
+    let symbol = match (location.kind, location.pairing) {
+        (LocationKind::RechargingStation, _) => "⚡ Station",
+        (_, Some(Pairing::Pickup { .. })) => "↑ Pickup",
+        (_, Some(Pairing::Delivery { .. })) => "↓ Delivery",
+        (LocationKind::Backhaul, _) => "↩ Backhaul",
+        _ => "⌂ Customer",
+    };
+    let name = location
+        .name
+        .as_ref()
+        .map_or(String::new(), |name| format!(" ({})", name));
+
+    format!(
+        "{:<30} ID: {}{}  TW: {} - {}\n",
+        format!("{} {}/{}", symbol, i + 1, n),
+        location.id,
+        name,
+        location.ready_time,
+        location.due_date
+    )
 }
 
 // -- Implement route iterator --
+// Iterates over the warehouse, the customers and the warehouse again, from both ends
 pub struct RouteIterator<'a> {
     route: &'a Route,
     index: usize,
+    end: usize,
 }
 
 impl<'a> Iterator for RouteIterator<'a> {
     type Item = &'a Location;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.index {
-            0 => {
-                self.index += 1;
-                Some(&self.route.warehouse)
-            }
-            x if x <= self.route.customers.len() => {
-                self.index += 1;
-                Some(&self.route.customers[x - 1])
-            }
-            x if x == self.route.customers.len() + 1 => {
-                self.index += 1;
-                Some(&self.route.warehouse)
-            }
-            _ => None,
+        if self.index >= self.end {
+            return None;
         }
+        self.index += 1;
+        self.route.get(self.index - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for RouteIterator<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index >= self.end {
+            return None;
+        }
+        self.end -= 1;
+        self.route.get(self.end)
+    }
+}
+
+impl ExactSizeIterator for RouteIterator<'_> {}
+
+/// A route plotted with the axes of its whole solution, so routes plotted side by side line up
+#[cfg(feature = "plot")]
+struct RoutePlot<'a> {
+    route: &'a Route,
+    coord_bounds: (i32, i32, i32, i32),
+}
+
+#[cfg(feature = "plot")]
+impl Plot for RoutePlot<'_> {
+    fn draw<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>) {
+        root.fill(&WHITE).unwrap();
+
+        let mut chart = ChartBuilder::on(root)
+            .x_label_area_size(35)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                self.coord_bounds.0..self.coord_bounds.1,
+                self.coord_bounds.2..self.coord_bounds.3,
+            )
+            .unwrap();
+
+        chart
+            .configure_mesh()
+            .x_desc("X")
+            .y_desc("Y")
+            .draw()
+            .unwrap();
+
+        // -- Plot the route --
+        draw_route_arcs(&mut chart, self.route, GREEN);
+
+        // -- Plot the locations --
+        // Plot the warehouse
+        chart
+            .draw_series(std::iter::once(Circle::new(
+                (self.route.warehouse.x as i32, self.route.warehouse.y as i32),
+                5,
+                RED.filled(),
+            )))
+            .unwrap();
+
+        // Plot the customers
+        for customer in &self.route.customers {
+            chart
+                .draw_series(std::iter::once(Circle::new(
+                    (customer.x as i32, customer.y as i32),
+                    5,
+                    BLUE.filled(),
+                )))
+                .unwrap();
+
+            chart
+                .draw_series(std::iter::once(Text::new(
+                    format!("{}", customer.id),
+                    (customer.x as i32 + 1, customer.y as i32 + 1),
+                    ("sans-serif", 15).into_font(),
+                )))
+                .unwrap();
+        }
+    }
+}
+
+/// The schedule of a route as a Gantt chart, see [plot_schedule](Route::plot_schedule)
+#[cfg(feature = "plot")]
+struct SchedulePlot<'a> {
+    route: &'a Route,
+}
+
+#[cfg(feature = "plot")]
+impl SchedulePlot<'_> {
+    const TIME_WINDOW: RGBColor = RGBColor(215, 215, 215);
+    const TRAVEL: RGBColor = RGBColor(70, 110, 200);
+    const WAITING: RGBColor = RGBColor(240, 160, 40);
+    const SERVICE: RGBColor = RGBColor(60, 160, 80);
+    const BREAK: RGBColor = RGBColor(170, 80, 170);
+
+    /// Every leg of the route as the location it ends at, its start and its arrival, with the
+    /// stop at its end, `None` for the return to the warehouse
+    fn legs(&self) -> Vec<(&Location, f32, f32, Option<Stop>)> {
+        let route = self.route;
+        let (stops, _, end) = route.walk(0.0);
+
+        let mut legs: Vec<(&Location, f32, f32, Option<Stop>)> = route
+            .customers
+            .iter()
+            .zip(stops)
+            .enumerate()
+            .map(|(i, (customer, stop))| {
+                let start = stop.arrival - route[i].travel_time_to(customer);
+                (customer, start, stop.arrival, Some(stop))
+            })
+            .collect();
+        if let (Some(last), false) = (route.customers.last(), route.open) {
+            let start = end - last.travel_time_to(&route.warehouse);
+            legs.push((&route.warehouse, start, end, None));
+        }
+        legs
+    }
+
+    /// Size of the chart, taller for long routes so rows stay readable
+    fn size(&self) -> (u32, u32) {
+        let rows = self.legs().len() as u32;
+        (800, 480.max(80 + 24 * rows))
+    }
+}
+
+#[cfg(feature = "plot")]
+impl Plot for SchedulePlot<'_> {
+    fn draw<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>) {
+        root.fill(&WHITE).unwrap();
+
+        let legs = self.legs();
+        let break_starts = self.route.break_starts();
+        // The first stop on the top row
+        let rows = legs.len().max(1);
+        let row = |i: usize| (rows - 1 - i) as f32;
+
+        let end = legs
+            .iter()
+            .map(|&(location, _, arrival, stop)| {
+                let departure = stop.map_or(arrival, |stop| stop.departure);
+                departure.max(location.due_date as f32)
+            })
+            .fold(1f32, f32::max);
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(5)
+            .x_label_area_size(35)
+            .y_label_area_size(60)
+            .build_cartesian_2d(0f32..end, -0.5f32..rows as f32 - 0.5)
+            .unwrap();
+
+        let labels: Vec<String> = legs
+            .iter()
+            .map(|(location, _, _, stop)| match stop {
+                Some(_) => format!("{}", location.id),
+                None => String::from("Warehouse"),
+            })
+            .collect();
+        chart
+            .configure_mesh()
+            .disable_y_mesh()
+            .y_labels(rows)
+            .y_label_formatter(&|y| {
+                let i = rows as f32 - 1.0 - y;
+                match (i.fract() == 0.0).then(|| labels.get(i as usize)).flatten() {
+                    Some(label) => label.clone(),
+                    None => String::new(),
+                }
+            })
+            .x_desc("Time")
+            .y_desc("Stop")
+            .draw()
+            .unwrap();
+
+        let bar = |i: usize, from: f32, to: f32, height: f32, color: RGBColor| {
+            Rectangle::new(
+                [(from, row(i) - height / 2.0), (to, row(i) + height / 2.0)],
+                color.filled(),
+            )
+        };
+
+        chart
+            .draw_series(legs.iter().enumerate().map(|(i, (location, ..))| {
+                let due_date = (location.due_date as f32).min(end);
+                bar(
+                    i,
+                    location.ready_time as f32,
+                    due_date,
+                    0.7,
+                    Self::TIME_WINDOW,
+                )
+            }))
+            .unwrap()
+            .label("Time window")
+            .legend(|(x, y)| {
+                Rectangle::new([(x, y - 5), (x + 20, y + 5)], Self::TIME_WINDOW.filled())
+            });
+
+        chart
+            .draw_series(
+                legs.iter()
+                    .enumerate()
+                    .map(|(i, &(_, start, arrival, _))| bar(i, start, arrival, 0.35, Self::TRAVEL)),
+            )
+            .unwrap()
+            .label("Travel")
+            .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], Self::TRAVEL.filled()));
+
+        chart
+            .draw_series(
+                legs.iter()
+                    .enumerate()
+                    .filter_map(|(i, &(_, _, arrival, stop))| {
+                        let stop = stop?;
+                        Some(bar(
+                            i,
+                            arrival,
+                            arrival + stop.waiting_time,
+                            0.35,
+                            Self::WAITING,
+                        ))
+                    }),
+            )
+            .unwrap()
+            .label("Waiting")
+            .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], Self::WAITING.filled()));
+
+        chart
+            .draw_series(
+                legs.iter()
+                    .enumerate()
+                    .filter_map(|(i, &(_, _, arrival, stop))| {
+                        let stop = stop?;
+                        let start = arrival + stop.waiting_time;
+                        Some(bar(i, start, stop.departure, 0.35, Self::SERVICE))
+                    }),
+            )
+            .unwrap()
+            .label("Service")
+            .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], Self::SERVICE.filled()));
+
+        if !break_starts.is_empty() {
+            // A break delays the first leg starting once it is over
+            let breaks =
+                break_starts
+                    .iter()
+                    .zip(&self.route.breaks)
+                    .filter_map(|(&start, driver_break)| {
+                        let over = start + driver_break.duration as f32;
+                        let i = legs
+                            .iter()
+                            .position(|&(_, leg_start, ..)| leg_start >= over - 1e-3)?;
+                        Some(bar(i, start, over, 0.35, Self::BREAK))
+                    });
+            chart
+                .draw_series(breaks)
+                .unwrap()
+                .label("Break")
+                .legend(|(x, y)| {
+                    Rectangle::new([(x, y - 5), (x + 20, y + 5)], Self::BREAK.filled())
+                });
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()
+            .unwrap();
     }
 }
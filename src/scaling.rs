@@ -0,0 +1,173 @@
+use crate::distance::TravelMatrix;
+use crate::location::Location;
+use crate::route::DriverBreak;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::sync::Arc;
+
+/// An affine change of units applied to an instance with [scaled](Vrp::scaled):
+/// `x' = (x - origin.0) * coordinate_scale` and `t' = (t - time_origin) * time_scale`.
+///
+/// Travel times equal distances, so scaling coordinates and times differently changes the speed
+/// of vehicles. The distances and durations of an explicit
+/// [travel matrix](crate::distance::TravelMatrix) are scaled like coordinates and times.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scaling {
+    pub coordinate_scale: f64,
+    pub coordinate_origin: (f64, f64),
+    pub time_scale: f64,
+    /// Ready times, due dates and service times are counted from this time
+    pub time_origin: f64,
+}
+
+impl Default for Scaling {
+    fn default() -> Self {
+        Scaling {
+            coordinate_scale: 1.0,
+            coordinate_origin: (0.0, 0.0),
+            time_scale: 1.0,
+            time_origin: 0.0,
+        }
+    }
+}
+
+impl Scaling {
+    /// Multiply times by `factor`, `60.0` converts minutes to seconds
+    pub fn time(factor: f64) -> Scaling {
+        Scaling {
+            time_scale: factor,
+            ..Default::default()
+        }
+    }
+
+    /// Count times from `origin`, `origin` becomes 0
+    pub fn shift_time(origin: f64) -> Scaling {
+        Scaling {
+            time_origin: origin,
+            ..Default::default()
+        }
+    }
+
+    /// Move and scale the locations of `vrp` to fit in a `size` x `size` square starting at 0.
+    /// Times are scaled by the same factor, so travel times keep matching distances
+    pub fn fit(vrp: &Vrp, size: u16) -> Scaling {
+        let (min_x, max_x) = bounds(vrp.locations().map(|l| l.x));
+        let (min_y, max_y) = bounds(vrp.locations().map(|l| l.y));
+
+        let extent = (max_x - min_x).max(max_y - min_y).max(1.0);
+        let scale = f64::from(size) / extent;
+
+        Scaling {
+            coordinate_scale: scale,
+            coordinate_origin: (min_x, min_y),
+            time_scale: scale,
+            time_origin: 0.0,
+        }
+    }
+
+    /// Scale a time point
+    pub fn apply_time(&self, time: f64) -> f64 {
+        (time - self.time_origin) * self.time_scale
+    }
+
+    /// Convert a scaled time point, like a [Stop](crate::route::Stop) arrival, back to the
+    /// original units
+    pub fn invert_time(&self, time: f32) -> f32 {
+        (f64::from(time) / self.time_scale + self.time_origin) as f32
+    }
+
+    /// Convert a scaled duration, like a route cost, back to the original units
+    pub fn invert_duration(&self, duration: f32) -> f32 {
+        (f64::from(duration) / self.time_scale) as f32
+    }
+
+    /// Convert scaled coordinates back to the original units
+    pub fn invert_point(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            x / self.coordinate_scale + self.coordinate_origin.0,
+            y / self.coordinate_scale + self.coordinate_origin.1,
+        )
+    }
+
+    fn apply(&self, location: &Location) -> Location {
+        let coordinate = |value: f64, origin: f64| (value - origin) * self.coordinate_scale;
+
+        Location {
+            x: coordinate(location.x, self.coordinate_origin.0),
+            y: coordinate(location.y, self.coordinate_origin.1),
+            ready_time: self.apply_time(location.ready_time),
+            due_date: self.apply_time(location.due_date),
+            service_time: location.service_time * self.time_scale,
+            ..location.clone()
+        }
+    }
+
+    fn apply_break(&self, driver_break: &DriverBreak) -> DriverBreak {
+        DriverBreak {
+            earliest: self.apply_time(driver_break.earliest),
+            latest: self.apply_time(driver_break.latest),
+            duration: driver_break.duration * self.time_scale,
+        }
+    }
+
+    fn apply_matrix(&self, matrix: &TravelMatrix) -> TravelMatrix {
+        let scale = |values: &[f32], factor: f64| {
+            values
+                .iter()
+                .map(|&v| (f64::from(v) * factor) as f32)
+                .collect()
+        };
+
+        TravelMatrix {
+            size: matrix.size,
+            distances: scale(&matrix.distances, self.coordinate_scale),
+            durations: scale(&matrix.durations, self.time_scale),
+        }
+    }
+}
+
+impl Vrp {
+    /// Copy this VRP with coordinates and times converted by `scaling`: its locations and other
+    /// depots, the max route duration, the driver breaks and the travel matrix. Solutions of the
+    /// copy convert back with [with_instance](VrpResult::with_instance), since ids do not change
+    pub fn scaled(&self, scaling: &Scaling) -> Vrp {
+        let mut vrp = Vrp {
+            warehouse: scaling.apply(&self.warehouse),
+            customers: self.customers.iter().map(|c| scaling.apply(c)).collect(),
+            other_depots: self.other_depots.iter().map(|d| scaling.apply(d)).collect(),
+            max_route_duration: self
+                .max_route_duration
+                .map(|duration| duration * scaling.time_scale),
+            driver_breaks: self
+                .driver_breaks
+                .iter()
+                .map(|b| scaling.apply_break(b))
+                .collect(),
+            ..self.clone()
+        };
+
+        if let Some(index) = &self.warehouse.matrix_index {
+            let matrix = Arc::new(scaling.apply_matrix(&index.matrix));
+            let locations = std::iter::once(&mut vrp.warehouse)
+                .chain(&mut vrp.customers)
+                .chain(&mut vrp.other_depots);
+            for index in locations.filter_map(|l| l.matrix_index.as_mut()) {
+                index.matrix = Arc::clone(&matrix);
+            }
+        }
+        vrp
+    }
+}
+
+impl VrpResult {
+    /// Convert a solution of a [scaled](Vrp::scaled) copy of `original` back to `original`
+    pub fn unscaled(&self, original: &Vrp) -> VrpResult {
+        self.with_instance(original)
+    }
+}
+
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    })
+}
@@ -0,0 +1,171 @@
+use crate::config::RunConfig;
+use crate::heuristics::stats::Stopwatch;
+use crate::location::Location;
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+
+/// One possible realization of the orders of an instance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    /// Relative probability of this scenario, weights do not need to sum to 1
+    pub weight: f32,
+    /// Ids of the customers placing an order in this scenario
+    pub customers: HashSet<u16>,
+}
+
+impl Scenario {
+    /// Sample `n_scenarios` equally likely scenarios of `vrp`, each customer orders with its
+    /// probability in `presence`. Customers missing from `presence` always order
+    pub fn sample(
+        vrp: &Vrp,
+        presence: &HashMap<u16, f32>,
+        n_scenarios: usize,
+        seed: u64,
+    ) -> Vec<Scenario> {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..n_scenarios)
+            .map(|_| Scenario {
+                weight: 1.0,
+                customers: vrp
+                    .customers
+                    .iter()
+                    .filter(|c| presence.get(&c.id).is_none_or(|&p| rng.gen::<f32>() < p))
+                    .map(|c| c.id)
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl Vrp {
+    /// The instance restricted to the customers of `scenario`
+    pub fn scenario(&self, scenario: &Scenario) -> Vrp {
+        Vrp {
+            customers: self
+                .customers
+                .iter()
+                .filter(|c| scenario.customers.contains(&c.id))
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+impl VrpResult {
+    /// The cost of following these routes in `scenario`, vehicles skip the customers without an
+    /// order. Skipping customers only makes vehicles earlier, so feasible routes stay feasible
+    pub fn scenario_cost(&self, scenario: &Scenario) -> f32 {
+        self.routes
+            .iter()
+            .map(|route| Route {
+                warehouse: route.warehouse.clone(),
+                customers: route
+                    .customers
+                    .iter()
+                    .filter(|c| scenario.customers.contains(&c.id))
+                    .cloned()
+                    .collect(),
+                open: route.open,
+                max_duration: route.max_duration,
+                breaks: route.breaks.clone(),
+            })
+            .filter(|route| !route.is_empty())
+            .map(|route| route.total_cost())
+            .sum()
+    }
+
+    /// The weighted mean of the [scenario costs](VrpResult::scenario_cost) of these routes
+    pub fn expected_cost(&self, scenarios: &[Scenario]) -> f32 {
+        let total_weight: f32 = scenarios.iter().map(|s| s.weight).sum();
+
+        if total_weight <= 0.0 {
+            return self.total_cost();
+        }
+
+        scenarios
+            .iter()
+            .map(|s| s.weight * self.scenario_cost(s))
+            .sum::<f32>()
+            / total_weight
+    }
+}
+
+impl RunConfig {
+    /// Plan routes over all customers of `vrp` before knowing which ones will order, minimizing
+    /// the [expected cost](VrpResult::expected_cost) over `scenarios`.
+    ///
+    /// The pipeline is run on the full instance and on every scenario, the customers missing from
+    /// a scenario solution are added by cheapest insertion. The candidate with the lowest expected
+    /// cost plus [drop penalty](VrpResult::drop_penalty) respecting the
+    /// [fleet policy](RunConfig::fleet) is returned, with the [stats](VrpResult::stats) of all
+    /// runs.
+    pub fn run_scenarios(&self, vrp: &Vrp, scenarios: &[Scenario]) -> Result<VrpResult, String> {
+        let stopwatch = Stopwatch::start();
+
+        let cost = |result: &VrpResult| {
+            result.expected_cost(scenarios) + result.drop_penalty(vrp, &self.drop_penalties)
+        };
+
+        let mut best = self.run(vrp)?;
+        let mut best_cost = cost(&best);
+        let mut stats = best.stats.unwrap_or_default();
+
+        for scenario in scenarios {
+            let candidate = self
+                .fleet
+                .apply(complete(&self.run(&vrp.scenario(scenario))?, vrp));
+            stats += candidate.stats.unwrap_or_default();
+            let candidate_cost = cost(&candidate);
+
+            if candidate_cost < best_cost && self.fleet.check(&candidate).is_ok() {
+                best = candidate;
+                best_cost = candidate_cost;
+            }
+        }
+
+        stats.wall_time = stopwatch.elapsed();
+        best.stats = Some(stats);
+        Ok(best)
+    }
+}
+
+/// Insert the customers of `vrp` missing from `result`, tightest due dates first. Customers that
+/// fit in no route get a dedicated one, the ones no vehicle can serve stay unassigned
+fn complete(result: &VrpResult, vrp: &Vrp) -> VrpResult {
+    let mut result = result.with_instance(vrp);
+    result.unassigned.clear();
+
+    let served: HashSet<u16> = result
+        .routes
+        .iter()
+        .flat_map(|route| route.customers.iter().map(|c| c.id))
+        .collect();
+
+    let mut missing: Vec<&Location> = vrp
+        .customers
+        .iter()
+        .filter(|c| !served.contains(&c.id))
+        .collect();
+    missing.sort_by(|a, b| a.due_date.total_cmp(&b.due_date));
+
+    for customer in missing {
+        match result.best_insertion(customer, vrp) {
+            Some((index, position, _)) => {
+                result.insert_customer(customer.clone(), index, position, vrp)
+            }
+            None if vrp.can_serve_alone(customer) => result.push_route(Route {
+                customers: vec![customer.clone()],
+                ..vrp.empty_route()
+            }),
+            None => result.unassigned.push(customer.id),
+        }
+    }
+
+    result
+}
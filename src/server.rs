@@ -0,0 +1,306 @@
+use crate::config::RunConfig;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Requests with a larger body are rejected
+const MAX_BODY_SIZE: usize = 64 * 1024 * 1024;
+
+/// Longest wait for the next bytes of a request, or for the client to accept the response,
+/// before the connection is dropped
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A small HTTP/1.1 REST API running solver jobs in the background:
+///
+/// - `POST /solve` with `{"instance": {..}, "params": {..}}` starts a job and returns its id
+/// - `GET /status/:id` returns the state of a job
+/// - `GET /result/:id` returns the solution of a finished job
+///
+/// The instance is in the [JSON format](Vrp::from_json) of instances, the optional params are
+/// a [JSON configuration](RunConfig::from_json) with the keys of a [RunConfig] TOML file.
+pub struct Server {
+    listener: TcpListener,
+    jobs: Arc<Jobs>,
+}
+
+#[derive(Default)]
+struct Jobs {
+    next_id: Mutex<u64>,
+    jobs: Mutex<HashMap<u64, Job>>,
+}
+
+struct Job {
+    started: Instant,
+    state: JobState,
+}
+
+enum JobState {
+    Running,
+    Done {
+        result: Box<VrpResult>,
+        elapsed: Duration,
+    },
+    Failed {
+        error: String,
+        elapsed: Duration,
+    },
+}
+
+/// Body of `GET /status/:id`, `elapsed` is in seconds
+#[derive(Serialize)]
+struct JobStatus {
+    id: u64,
+    status: &'static str,
+    elapsed: f64,
+    error: Option<String>,
+}
+
+struct Response {
+    status: u16,
+    /// JSON body
+    body: String,
+}
+
+impl Response {
+    fn ok(body: String) -> Response {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response {
+            status,
+            body: json!({ "error": message }).to_string(),
+        }
+    }
+}
+
+impl Server {
+    /// Listen on `addr`, use port 0 to let the OS pick a free port
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            jobs: Arc::default(),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serve requests forever, each connection and each job runs on its own thread
+    pub fn run(self) -> io::Result<()> {
+        for stream in self.listener.incoming() {
+            let stream = stream?;
+            let jobs = Arc::clone(&self.jobs);
+
+            thread::spawn(move || {
+                if let Err(e) = handle_connection(stream, &jobs) {
+                    eprintln!("Failed to handle request: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Jobs {
+    fn start(self: &Arc<Self>, vrp: Vrp, config: RunConfig) -> u64 {
+        let id = {
+            let mut next_id = self.next_id.lock().unwrap();
+            *next_id += 1;
+            *next_id
+        };
+
+        let started = Instant::now();
+        self.jobs.lock().unwrap().insert(
+            id,
+            Job {
+                started,
+                state: JobState::Running,
+            },
+        );
+
+        let jobs = Arc::clone(self);
+        thread::spawn(move || {
+            // A panicking solver must not leave the job running forever
+            let result = panic::catch_unwind(AssertUnwindSafe(|| config.run(&vrp)))
+                .unwrap_or_else(|_| Err(String::from("The solver panicked")));
+
+            let elapsed = started.elapsed();
+            let state = match result {
+                Ok(result) => JobState::Done {
+                    result: Box::new(result),
+                    elapsed,
+                },
+                Err(error) => JobState::Failed { error, elapsed },
+            };
+
+            if let Some(job) = jobs.jobs.lock().unwrap().get_mut(&id) {
+                job.state = state;
+            }
+        });
+
+        id
+    }
+
+    fn status(&self, id: u64) -> Option<String> {
+        let jobs = self.jobs.lock().unwrap();
+        let job = jobs.get(&id)?;
+
+        let (status, elapsed, error) = match &job.state {
+            JobState::Running => ("running", job.started.elapsed(), None),
+            JobState::Done { elapsed, .. } => ("done", *elapsed, None),
+            JobState::Failed { error, elapsed } => ("failed", *elapsed, Some(error.clone())),
+        };
+
+        let status = JobStatus {
+            id,
+            status,
+            elapsed: elapsed.as_secs_f64(),
+            error,
+        };
+        Some(serde_json::to_string(&status).expect("Job statuses are valid JSON"))
+    }
+
+    fn result(&self, id: u64) -> Response {
+        match self.jobs.lock().unwrap().get(&id).map(|job| &job.state) {
+            None => Response::error(404, "Unknown job"),
+            Some(JobState::Running) => Response::error(409, "The job is still running"),
+            Some(JobState::Done { result, .. }) => Response::ok(result.as_json_string()),
+            Some(JobState::Failed { error, .. }) => Response::error(500, error),
+        }
+    }
+}
+
+fn handle_connection(stream: TcpStream, jobs: &Arc<Jobs>) -> io::Result<()> {
+    // A client that never finishes its request must not hold this thread forever
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+
+    let response = match read_request(&stream, jobs) {
+        Ok(response) => response,
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            Response::error(408, "Timed out reading the request")
+        }
+        Err(e) => return Err(e),
+    };
+
+    write_response(stream, &response)
+}
+
+fn read_request(stream: &TcpStream, jobs: &Arc<Jobs>) -> io::Result<Response> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    // -- Headers, only the body length matters --
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Ok(Response::error(413, "Request body too large"));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    Ok(route(&method, &path, &String::from_utf8_lossy(&body), jobs))
+}
+
+fn route(method: &str, path: &str, body: &str, jobs: &Arc<Jobs>) -> Response {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        ("POST", ["solve"]) => match parse_solve_request(body) {
+            Ok((vrp, config)) => Response {
+                status: 202,
+                body: json!({ "id": jobs.start(vrp, config) }).to_string(),
+            },
+            Err(message) => Response::error(400, &message),
+        },
+        ("GET", ["status", id]) => match id.parse().ok().and_then(|id| jobs.status(id)) {
+            Some(status) => Response::ok(status),
+            None => Response::error(404, "Unknown job"),
+        },
+        ("GET", ["result", id]) => match id.parse() {
+            Ok(id) => jobs.result(id),
+            Err(_) => Response::error(404, "Unknown job"),
+        },
+        (_, ["solve"] | ["status", _] | ["result", _]) => {
+            Response::error(405, "Method not allowed")
+        }
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+fn write_response(mut stream: TcpStream, response: &Response) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+    let body = &response.body;
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn parse_solve_request(body: &str) -> Result<(Vrp, RunConfig), String> {
+    let request: Value = serde_json::from_str(body).map_err(|e| format!("Invalid JSON: {}", e))?;
+
+    let vrp = Vrp::from_json(
+        request
+            .get("instance")
+            .ok_or_else(|| String::from("Missing instance"))?,
+    )?;
+
+    let config = match request.get("params") {
+        Some(params) if !params.is_null() => {
+            RunConfig::from_json(params).map_err(|e| e.to_string())?
+        }
+        _ => RunConfig::default(),
+    };
+
+    Ok((vrp, config))
+}
@@ -0,0 +1,218 @@
+use crate::heuristics::stats::Stopwatch;
+use crate::objective::Objective;
+use crate::vrp_result::VrpResult;
+#[cfg(feature = "plot")]
+use plotters::prelude::*;
+use std::time::Duration;
+
+/// One solution produced during a session, see [SessionTracker::record]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionEntry {
+    /// Name of the heuristic that built the solution, like a pipeline step
+    pub heuristic: String,
+    /// Parameters of the heuristic, free text
+    pub params: String,
+    pub cost: f32,
+    pub n_routes: usize,
+    /// [Key](Objective::key) of the solution under its [objective](VrpResult::objective),
+    /// entries are ranked by it
+    pub key: (f32, f32),
+    /// Whether every customer is served, see [is_complete](VrpResult::is_complete)
+    pub complete: bool,
+    /// Time between the start of the session and the end of the heuristic, always zero on wasm
+    pub found_after: Duration,
+}
+
+/// Records every solution produced during a program run, to log experiments without extra code
+/// and show how the best known solution improved over time.
+///
+/// Partial solutions are recorded but never count as the best known one
+#[derive(Debug, Clone)]
+pub struct SessionTracker {
+    stopwatch: Stopwatch,
+    entries: Vec<SessionEntry>,
+}
+
+impl Default for SessionTracker {
+    fn default() -> Self {
+        SessionTracker::new()
+    }
+}
+
+impl SessionTracker {
+    /// Start a session, the times of the entries are measured from now
+    pub fn new() -> SessionTracker {
+        SessionTracker {
+            stopwatch: Stopwatch::start(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Record a solution built by `heuristic` with `params`, returns whether it improves on the
+    /// best known solution
+    pub fn record(
+        &mut self,
+        heuristic: &str,
+        params: impl Into<String>,
+        result: &VrpResult,
+    ) -> bool {
+        let entry = SessionEntry {
+            heuristic: String::from(heuristic),
+            params: params.into(),
+            cost: result.total_cost(),
+            n_routes: result.routes.len(),
+            key: result.objective.key(result),
+            complete: result.is_complete(),
+            found_after: self.stopwatch.elapsed(),
+        };
+
+        let improves = entry.complete
+            && self
+                .best()
+                .is_none_or(|best| Objective::compare_keys(entry.key, best.key).is_lt());
+        self.entries.push(entry);
+        improves
+    }
+
+    /// Every recorded solution, in the order they were recorded
+    pub fn entries(&self) -> &[SessionEntry] {
+        &self.entries
+    }
+
+    /// Get the best complete solution recorded so far under the objective of the solutions, the
+    /// first one of equally good ones
+    pub fn best(&self) -> Option<&SessionEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.complete)
+            .min_by(|a, b| Objective::compare_keys(a.key, b.key))
+    }
+
+    /// The entries that improved on the best known solution when they were recorded, the first
+    /// complete solution included
+    pub fn improvements(&self) -> Vec<&SessionEntry> {
+        let mut best = (f32::INFINITY, f32::INFINITY);
+        self.entries
+            .iter()
+            .filter(|entry| {
+                let improves = entry.complete && Objective::compare_keys(entry.key, best).is_lt();
+                if improves {
+                    best = entry.key;
+                }
+                improves
+            })
+            .collect()
+    }
+
+    /// Print the improvement timeline to a Markdown string, with a chart of the best known cost
+    /// over time with the `plot` feature
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("# Session\n\n");
+        output.push_str(&format! {"- Solutions recorded: {}\n", self.entries.len()});
+        if let Some(best) = self.best() {
+            output.push_str(&format! {
+                "- Best known cost: {} ({}, {} routes)\n", best.cost, best.heuristic, best.n_routes
+            });
+        }
+
+        output.push_str("\n## Improvements\n\n");
+        output.push_str("| Found after (s) | Heuristic | Cost | Routes | Params |\n");
+        output.push_str("|---|---|---|---|---|\n");
+        for entry in self.improvements() {
+            output.push_str(&format! {
+                "| {:.3} | {} | {} | {} | {} |\n",
+                entry.found_after.as_secs_f64(),
+                entry.heuristic,
+                entry.cost,
+                entry.n_routes,
+                entry.params.replace('|', "\\|")
+            });
+        }
+
+        #[cfg(feature = "plot")]
+        if let Some(chart) = self.plot_timeline() {
+            output.push_str("\n## Timeline\n\n");
+            output.push_str(&chart);
+        }
+
+        output
+    }
+
+    /// Plot the best known cost over time as steps, and every recorded complete solution as a
+    /// point. Returns `None` before the first complete solution
+    #[cfg(feature = "plot")]
+    pub fn plot_timeline(&self) -> Option<String> {
+        let improvements = self.improvements();
+        let first = improvements.first()?;
+
+        let complete: Vec<(f64, f32)> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.complete)
+            .map(|entry| (entry.found_after.as_secs_f64(), entry.cost))
+            .collect();
+
+        let end = complete.iter().map(|e| e.0).fold(0.0, f64::max).max(1e-3);
+        let max_cost = complete.iter().map(|e| e.1).fold(first.cost, f32::max);
+        let min_cost = improvements.last()?.cost;
+        // Keep a visible range when every solution has the same cost
+        let margin = ((max_cost - min_cost) * 0.05).max(1.0);
+
+        // The best known cost holds until the next improvement
+        let mut steps = Vec::new();
+        for (i, entry) in improvements.iter().enumerate() {
+            let time = entry.found_after.as_secs_f64();
+            if i > 0 {
+                steps.push((time, improvements[i - 1].cost));
+            }
+            steps.push((time, entry.cost));
+        }
+        steps.push((end, min_cost));
+
+        let mut svg_data = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg_data, (800, 480)).into_drawing_area();
+            root.fill(&WHITE).unwrap();
+
+            let mut chart = ChartBuilder::on(&root)
+                .margin(5)
+                .x_label_area_size(35)
+                .y_label_area_size(40)
+                .build_cartesian_2d(0.0..end, (min_cost - margin)..(max_cost + margin))
+                .unwrap();
+
+            chart
+                .configure_mesh()
+                .x_desc("Time (s)")
+                .y_desc("Cost")
+                .draw()
+                .unwrap();
+
+            chart
+                .draw_series(
+                    complete
+                        .iter()
+                        .map(|&point| Circle::new(point, 3, BLUE.filled())),
+                )
+                .unwrap()
+                .label("Solutions")
+                .legend(|(x, y)| Circle::new((x + 10, y), 3, BLUE.filled()));
+
+            chart
+                .draw_series(LineSeries::new(steps, &RED))
+                .unwrap()
+                .label("Best known cost")
+                .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .draw()
+                .unwrap();
+
+            root.present().unwrap();
+        }
+        Some(svg_data)
+    }
+}
@@ -0,0 +1,62 @@
+use crate::vrp_result::VrpResult;
+
+/// Number of decimals of the distance line, the precision of the best known solution tables
+const DISTANCE_PRECISION: usize = 2;
+
+/// Who found a solution, the header of the [SINTEF format](VrpResult::to_sintef_string)
+#[derive(Debug, Clone, Default)]
+pub struct SolutionInfo {
+    /// Name of the instance, like `C1_2_1`
+    pub instance_name: String,
+    pub authors: String,
+    /// When the solution was found, like `2024-05-17`
+    pub date: String,
+    /// Publication or software describing how the solution was found
+    pub reference: String,
+}
+
+impl VrpResult {
+    /// Print this solution in the solution file format of the SINTEF best known solutions of
+    /// the solomon and Gehring & Homberger instances:
+    ///
+    /// ```text
+    /// Instance name : C101
+    /// Authors       : Jane Doe
+    /// Date          : 2024-05-17
+    /// Reference     : solomon-vrptw
+    /// Distance      : 828.94
+    /// Solution
+    /// Route 1 : 5 3 7
+    /// Route 2 : 8 9
+    /// ```
+    ///
+    /// Empty routes are left out and the others are numbered in order. Partial solutions are
+    /// printed without their unassigned customers, validators reject them
+    pub fn to_sintef_string(&self, info: &SolutionInfo) -> String {
+        let mut output = String::new();
+        output.push_str(&format!("Instance name : {}\n", info.instance_name));
+        output.push_str(&format!("Authors       : {}\n", info.authors));
+        output.push_str(&format!("Date          : {}\n", info.date));
+        output.push_str(&format!("Reference     : {}\n", info.reference));
+        output.push_str(&format!(
+            "Distance      : {:.*}\n",
+            DISTANCE_PRECISION,
+            self.total_distance()
+        ));
+        output.push_str("Solution\n");
+
+        let routes = self
+            .routes
+            .iter()
+            .filter(|route| !route.customers.is_empty());
+        for (i, route) in routes.enumerate() {
+            output.push_str(&format!("Route {} :", i + 1));
+            for customer in &route.customers {
+                output.push_str(&format!(" {}", customer.id));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+}
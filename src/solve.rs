@@ -0,0 +1,102 @@
+use crate::config::RunConfig;
+use crate::fleet::{FleetPolicy, DEFAULT_VEHICLE_PENALTY};
+use crate::heuristics::aco::AcoParams;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Ready made solver settings trading solving time for quality, see [Vrp::solve]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SolvePreset {
+    /// Nearest neighbor routes re-cut by the split algorithm, in milliseconds
+    Fast,
+    /// Fast, then a small ant colony for up to 10 seconds
+    #[default]
+    Balanced,
+    /// Fast, then a large ant colony for up to 60 seconds
+    Quality,
+}
+
+impl FromStr for SolvePreset {
+    type Err = String;
+
+    /// Parse `fast`, `balanced` or `quality`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fast" => Ok(SolvePreset::Fast),
+            "balanced" => Ok(SolvePreset::Balanced),
+            "quality" => Ok(SolvePreset::Quality),
+            other => Err(format!("Unknown preset: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for SolvePreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SolvePreset::Fast => "fast",
+            SolvePreset::Balanced => "balanced",
+            SolvePreset::Quality => "quality",
+        })
+    }
+}
+
+impl SolvePreset {
+    /// The run settings of this preset, other settings keep their default
+    pub fn config(&self) -> RunConfig {
+        let mut config = RunConfig::default();
+        self.apply(&mut config);
+        config
+    }
+
+    /// Replace the pipeline, time limit, aco parameters and fleet policy of `config` with the
+    /// ones of this preset.
+    ///
+    /// Every preset builds a nearest neighbor solution and re-cuts it with the split step, the
+    /// others then run the ant colony, re-cut the best routes again and remove routes with
+    /// ejection chains until the fleet is respected, so they are never worse than
+    /// [Fast](SolvePreset::Fast). Extra vehicles left are [penalized](FleetPolicy::Penalize), so
+    /// a solution is always returned
+    pub fn apply(&self, config: &mut RunConfig) {
+        let aco = |n_ants, max_iter, candidate_list_size, seconds| AcoParams {
+            n_ants,
+            max_iter,
+            candidate_list_size: Some(candidate_list_size),
+            time_limit: Some(Duration::from_secs(seconds)),
+            ..AcoParams::default()
+        };
+
+        let (pipeline, aco): (&[&str], AcoParams) = match self {
+            SolvePreset::Fast => (&["nn", "split"], AcoParams::default()),
+            SolvePreset::Balanced => (
+                &["nn", "split", "aco", "split", "minimize"],
+                aco(20, 100, 20, 10),
+            ),
+            SolvePreset::Quality => (
+                &["nn", "split", "aco", "split", "minimize"],
+                aco(50, 1000, 30, 60),
+            ),
+        };
+
+        config.pipeline = pipeline.iter().map(|&step| String::from(step)).collect();
+        config.time_limit = None;
+        config.aco = aco;
+        config.pheromone_amt = None;
+        config.fleet = FleetPolicy::Penalize {
+            cost_per_vehicle: DEFAULT_VEHICLE_PENALTY,
+        };
+    }
+}
+
+impl Vrp {
+    /// Solve this instance with the settings of `preset`, without choosing heuristics and their
+    /// parameters. Use a [RunConfig] for more control
+    pub fn solve(&self, preset: SolvePreset) -> VrpResult {
+        preset
+            .config()
+            .run(self)
+            .expect("Preset pipelines are valid and penalize extra vehicles instead of failing")
+    }
+}
@@ -0,0 +1,176 @@
+use crate::location::Location;
+
+/// A uniform grid over [Location](crate::location::Location) coordinates, used to prune
+/// reachability queries by distance before checking time windows
+#[derive(Debug, Clone)]
+pub struct SpatialIndex<'a> {
+    locations: Vec<&'a Location>,
+    /// Indices into `locations` of the locations still present in each cell, in row-major order
+    cells: Vec<Vec<usize>>,
+    min_x: f64,
+    min_y: f64,
+    cell_size: f64,
+    n_cols: usize,
+    n_rows: usize,
+    len: usize,
+    max_due_date: f64,
+}
+
+impl<'a> SpatialIndex<'a> {
+    /// Build an index over `locations` using square cells of side `cell_size`
+    pub fn new(locations: Vec<&'a Location>, cell_size: f64) -> SpatialIndex<'a> {
+        let cell_size = cell_size.max(1.0);
+
+        let (min_x, max_x) = bounds(locations.iter().map(|l| l.x));
+        let (min_y, max_y) = bounds(locations.iter().map(|l| l.y));
+
+        let n_cols = ((max_x - min_x) / cell_size) as usize + 1;
+        let n_rows = ((max_y - min_y) / cell_size) as usize + 1;
+
+        let mut index = SpatialIndex {
+            cells: vec![Vec::new(); n_cols * n_rows],
+            min_x,
+            min_y,
+            cell_size,
+            n_cols,
+            n_rows,
+            len: locations.len(),
+            max_due_date: bounds(locations.iter().map(|l| l.due_date)).1,
+            locations,
+        };
+
+        for (i, location) in index.locations.iter().enumerate() {
+            let cell = index.cell_of(location);
+            index.cells[cell].push(i);
+        }
+
+        index
+    }
+
+    /// Build an index over `locations`, choosing a cell size that holds about two locations per
+    /// cell on uniformly spread instances
+    pub fn from_locations(locations: Vec<&'a Location>) -> SpatialIndex<'a> {
+        let (min_x, max_x) = bounds(locations.iter().map(|l| l.x));
+        let (min_y, max_y) = bounds(locations.iter().map(|l| l.y));
+
+        let area = (max_x - min_x + 1.0) * (max_y - min_y + 1.0);
+        let cell_size = (2.0 * area / locations.len().max(1) as f64).sqrt().ceil();
+
+        SpatialIndex::new(locations, cell_size)
+    }
+
+    /// The number of locations still in the index
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The latest due date of all locations this index was built with, an upper bound for the
+    /// locations still present
+    pub fn max_due_date(&self) -> f64 {
+        self.max_due_date
+    }
+
+    /// The locations still in the index, cell by cell
+    pub fn remaining(&self) -> impl Iterator<Item = &'a Location> + '_ {
+        self.cells.iter().flatten().map(|&i| self.locations[i])
+    }
+
+    /// Remove a location from the index, returns false if it was not present
+    pub fn remove(&mut self, location: &Location) -> bool {
+        let cell = self.cell_of(location);
+
+        let locations = &self.locations;
+        let position = self.cells[cell]
+            .iter()
+            .position(|&i| locations[i] == location);
+
+        match position {
+            Some(position) => {
+                self.cells[cell].remove(position);
+                self.len -= 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get all locations within `radius` of `from`, in the order they were given to the index
+    pub fn within(&self, from: &Location, radius: f32) -> Vec<&'a Location> {
+        if radius < 0.0 || self.is_empty() {
+            return Vec::new();
+        }
+
+        // Travel matrices are not bound by the coordinates, check every location
+        if from.matrix_index.is_some() {
+            let mut found: Vec<usize> = self
+                .cells
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|&i| from.travel_time_to(self.locations[i]) <= radius)
+                .collect();
+            found.sort_unstable();
+
+            return found.into_iter().map(|i| self.locations[i]).collect();
+        }
+
+        let reach = f64::from(radius);
+        let col_range = self.cell_range(from.x, self.min_x, reach, self.n_cols);
+        let row_range = self.cell_range(from.y, self.min_y, reach, self.n_rows);
+
+        let mut found: Vec<usize> = Vec::new();
+
+        for row in row_range {
+            for col in col_range.clone() {
+                found.extend(
+                    self.cells[row * self.n_cols + col]
+                        .iter()
+                        .filter(|&&i| from.distance_to(self.locations[i]) <= radius),
+                );
+            }
+        }
+
+        // Keep the input order so results match a linear scan
+        found.sort_unstable();
+
+        found.into_iter().map(|i| self.locations[i]).collect()
+    }
+
+    fn cell_of(&self, location: &Location) -> usize {
+        // Casting saturates, locations left of the grid land in its first cell
+        let col = (((location.x - self.min_x) / self.cell_size) as usize).min(self.n_cols - 1);
+        let row = (((location.y - self.min_y) / self.cell_size) as usize).min(self.n_rows - 1);
+
+        row * self.n_cols + col
+    }
+
+    fn cell_range(
+        &self,
+        coord: f64,
+        min: f64,
+        reach: f64,
+        n_cells: usize,
+    ) -> std::ops::RangeInclusive<usize> {
+        let offset = coord - min;
+        let max = (n_cells - 1) as f64;
+
+        let first = ((offset - reach) / self.cell_size).floor().clamp(0.0, max);
+        let last = ((offset + reach) / self.cell_size).floor().clamp(0.0, max);
+
+        first as usize..=last as usize
+    }
+}
+
+// Get the smallest and largest of `values`, 0 for both if there is none
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values
+        .fold(None, |bounds: Option<(f64, f64)>, value| match bounds {
+            Some((min, max)) => Some((min.min(value), max.max(value))),
+            None => Some((value, value)),
+        })
+        .unwrap_or((0.0, 0.0))
+}
@@ -0,0 +1,192 @@
+use crate::vrp::Vrp;
+use std::fmt;
+
+/// Minimum, maximum, mean and standard deviation of a set of values
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Summary {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub std_dev: f32,
+}
+
+impl Summary {
+    pub fn of(values: impl IntoIterator<Item = f32>) -> Summary {
+        let values: Vec<f32> = values.into_iter().collect();
+
+        if values.is_empty() {
+            return Summary::default();
+        }
+
+        let n = values.len() as f32;
+        let mean = values.iter().sum::<f32>() / n;
+
+        Summary {
+            min: values.iter().copied().fold(f32::INFINITY, f32::min),
+            max: values.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            mean,
+            std_dev: (values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / n).sqrt(),
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:.1}, max {:.1}, mean {:.1}, std dev {:.1}",
+            self.min, self.max, self.mean, self.std_dev
+        )
+    }
+}
+
+/// The solomon instance classes: clustered (C), random (R) or mixed (RC) customers, with short
+/// routes (series 1) or long routes (series 2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SolomonClass {
+    C1,
+    C2,
+    R1,
+    R2,
+    RC1,
+    RC2,
+}
+
+impl SolomonClass {
+    /// Get the class of a solomon or Gehring & Homberger instance from its name, like `RC104`
+    /// or `c1_2_1`: the customer layout letters followed by the series digit
+    pub fn from_instance_name(name: &str) -> Option<SolomonClass> {
+        let name = name.to_uppercase();
+        let (layout, rest) = if let Some(rest) = name.strip_prefix("RC") {
+            ("RC", rest)
+        } else if let Some(rest) = name.strip_prefix('R') {
+            ("R", rest)
+        } else {
+            ("C", name.strip_prefix('C')?)
+        };
+
+        match (layout, rest.chars().next()?) {
+            ("C", '1') => Some(SolomonClass::C1),
+            ("C", '2') => Some(SolomonClass::C2),
+            ("R", '1') => Some(SolomonClass::R1),
+            ("R", '2') => Some(SolomonClass::R2),
+            ("RC", '1') => Some(SolomonClass::RC1),
+            ("RC", '2') => Some(SolomonClass::RC2),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SolomonClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Descriptive statistics of an instance, see [statistics](Vrp::statistics)
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceStatistics {
+    pub n_customers: usize,
+    pub demand: Summary,
+    /// Width of the customer time windows
+    pub time_window_width: Summary,
+    /// Mean time window width divided by the scheduling horizon (the warehouse window), small
+    /// values mean tight windows
+    pub tightness: f32,
+    /// Distance of the customers to the warehouse
+    pub depot_distance: Summary,
+    /// Clark-Evans index of the customer positions, the mean nearest neighbor distance divided by
+    /// its expected value for uniformly random positions. About 1 for random positions, lower
+    /// for clustered ones
+    pub dispersion: f32,
+    /// Best guess of the solomon class of this instance
+    pub class: SolomonClass,
+}
+
+impl Vrp {
+    /// Compute descriptive statistics of this instance
+    pub fn statistics(&self) -> InstanceStatistics {
+        let table = self.location_table();
+        let n = self.customers.len();
+
+        let horizon = (self.warehouse.due_date - self.warehouse.ready_time).max(0.0) as f32;
+        let time_window_width = Summary::of(
+            self.customers
+                .iter()
+                .map(|c| (c.due_date - c.ready_time).max(0.0) as f32),
+        );
+
+        // -- Clark-Evans index over the customers bounding box --
+        let nearest: Vec<f32> = (1..=n)
+            .map(|a| {
+                (1..=n)
+                    .filter(|&b| b != a)
+                    .map(|b| table.distance(a, b))
+                    .fold(f32::INFINITY, f32::min)
+            })
+            .collect();
+
+        let bounds = |values: &[f64]| {
+            values
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &v| {
+                    (min.min(v as f32), max.max(v as f32))
+                })
+        };
+        let (min_x, max_x) = bounds(&table.xs[1..]);
+        let (min_y, max_y) = bounds(&table.ys[1..]);
+        let area = (max_x - min_x) * (max_y - min_y);
+
+        let dispersion = if n > 1 && area > 0.0 {
+            let expected = 0.5 * (area / n as f32).sqrt();
+            nearest.iter().sum::<f32>() / n as f32 / expected
+        } else {
+            1.0
+        };
+
+        let demand = Summary::of(self.customers.iter().map(|c| c.demand as f32));
+
+        // Series 2 vehicles carry many customers, the solomon series 2 capacities are 700 and
+        // 1000 against 200 for series 1, with the same demands
+        let long_routes = demand.mean > 0.0 && self.vehicle_capacity as f32 / demand.mean > 25.0;
+
+        let class = match (dispersion, long_routes) {
+            (d, false) if d < 0.6 => SolomonClass::C1,
+            (d, true) if d < 0.6 => SolomonClass::C2,
+            (d, false) if d < 0.85 => SolomonClass::RC1,
+            (d, true) if d < 0.85 => SolomonClass::RC2,
+            (_, false) => SolomonClass::R1,
+            (_, true) => SolomonClass::R2,
+        };
+
+        InstanceStatistics {
+            n_customers: n,
+            demand,
+            tightness: if horizon > 0.0 {
+                time_window_width.mean / horizon
+            } else {
+                0.0
+            },
+            time_window_width,
+            depot_distance: Summary::of((1..=n).map(|i| table.distance(0, i))),
+            dispersion,
+            class,
+        }
+    }
+}
+
+impl InstanceStatistics {
+    /// Print these statistics to a Markdown string
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("# Instance statistics\n\n");
+        output.push_str(&format! {"- N° of customers: {}\n", self.n_customers});
+        output.push_str(&format! {"- Demand: {}\n", self.demand});
+        output.push_str(&format! {"- Time window width: {}\n", self.time_window_width});
+        output.push_str(&format! {"- Time window tightness: {:.3}\n", self.tightness});
+        output.push_str(&format! {"- Distance to the warehouse: {}\n", self.depot_distance});
+        output.push_str(&format! {"- Dispersion (Clark-Evans): {:.2}\n", self.dispersion});
+        output.push_str(&format! {"- Likely solomon class: {}\n", self.class});
+        output
+    }
+}
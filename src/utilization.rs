@@ -0,0 +1,153 @@
+use crate::route::Route;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+
+/// Capacity use of the routes of a solution, see [utilization](VrpResult::utilization)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utilization {
+    /// Demand carried by each route divided by the vehicle capacity
+    pub load_factors: Vec<f32>,
+    /// Total demand divided by the capacity of all routes
+    pub average: f32,
+    /// Lower bound on the number of vehicles needed to carry the demand of the served customers,
+    /// ignoring time windows. See [min_fleet_size](Vrp::min_fleet_size)
+    pub min_fleet_size: u16,
+}
+
+impl Utilization {
+    /// The number of routes over the [lower bound](Utilization::min_fleet_size). Capacity never
+    /// rules out serving the customers with this many vehicles less, time windows may
+    pub fn spare_vehicles(&self) -> usize {
+        self.load_factors
+            .len()
+            .saturating_sub(self.min_fleet_size as usize)
+    }
+
+    /// Print this utilization to a Markdown string
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format! {"- Average utilization: {:.1}%\n", self.average * 100.0});
+
+        let (min, max) = self
+            .load_factors
+            .iter()
+            .fold((f32::INFINITY, 0f32), |(min, max), &f| {
+                (min.min(f), max.max(f))
+            });
+        if !self.load_factors.is_empty() {
+            output.push_str(&format! {
+                "- Least and most loaded routes: {:.1}%, {:.1}%\n", min * 100.0, max * 100.0
+            });
+        }
+
+        output.push_str(&format! {
+            "- Vehicles: {} used, at least {} needed for the demand\n",
+            self.load_factors.len(), self.min_fleet_size
+        });
+        if self.spare_vehicles() > 0 {
+            output.push_str(&format! {
+                "- Capacity allows dropping up to {} vehicles, if time windows do\n",
+                self.spare_vehicles()
+            });
+        }
+
+        output.push_str("\n| Route | Load |\n|---|---|\n");
+        for (i, factor) in self.load_factors.iter().enumerate() {
+            output.push_str(&format! {"| {} | {:.1}% |\n", i + 1, factor * 100.0});
+        }
+        output
+    }
+}
+
+impl Route {
+    /// Get the demand carried by this route divided by `capacity`
+    pub fn load_factor(&self, capacity: f64) -> f32 {
+        self.total_demand() as f32 / capacity as f32
+    }
+}
+
+impl VrpResult {
+    /// Compute the load of every route and how many vehicles the served demand needs at least
+    pub fn utilization(&self) -> Utilization {
+        let load_factors: Vec<f32> = self
+            .routes
+            .iter()
+            .map(|route| route.load_factor(self.vehicle_capacity))
+            .collect();
+
+        let demands: Vec<f64> = self
+            .routes
+            .iter()
+            .flat_map(|route| route.customers.iter().map(|c| c.demand))
+            .collect();
+
+        let total: f64 = demands.iter().sum();
+        let average = match self.routes.len() {
+            0 => 0.0,
+            n => total as f32 / (n as f32 * self.vehicle_capacity as f32),
+        };
+
+        Utilization {
+            load_factors,
+            average,
+            min_fleet_size: bin_packing_bound(&demands, self.vehicle_capacity),
+        }
+    }
+}
+
+impl Vrp {
+    /// Get a lower bound on the number of vehicles needed to carry the demand of all customers,
+    /// ignoring time windows.
+    ///
+    /// This is the bin packing bound of Martello and Toth, it is at least the total demand divided
+    /// by the capacity and also counts the customers too large to share a vehicle
+    pub fn min_fleet_size(&self) -> u16 {
+        let demands: Vec<f64> = self.customers.iter().map(|c| c.demand).collect();
+        bin_packing_bound(&demands, self.vehicle_capacity)
+    }
+}
+
+/// The L2 bound of Martello and Toth on the number of bins of size `capacity` holding `items`.
+///
+/// For every threshold `k`, items larger than `capacity - k` fit along no item of size `k` or
+/// more, items larger than half the capacity fit along no other such item, and the items between
+/// `k` and half the capacity can only fill the space the large ones leave
+fn bin_packing_bound(items: &[f64], capacity: f64) -> u16 {
+    let total: f64 = items.iter().sum();
+
+    if capacity <= 0.0 {
+        return if total > 0.0 { u16::MAX } else { 0 };
+    }
+
+    let mut thresholds: Vec<f64> = items
+        .iter()
+        .copied()
+        .filter(|&d| 2.0 * d <= capacity)
+        .collect();
+    thresholds.push(0.0);
+    thresholds.sort_unstable_by(f64::total_cmp);
+    thresholds.dedup();
+
+    let bound = thresholds
+        .into_iter()
+        .map(|k| {
+            let (mut n_large, mut n_half, mut half_space, mut small) = (0.0, 0.0, 0.0, 0.0);
+
+            for &d in items {
+                if d > capacity - k {
+                    n_large += 1.0;
+                } else if 2.0 * d > capacity {
+                    n_half += 1.0;
+                    half_space += capacity - d;
+                } else if d >= k {
+                    small += d;
+                }
+            }
+
+            n_large + n_half + (f64::max(small - half_space, 0.0) / capacity).ceil()
+        })
+        .fold((total / capacity).ceil(), f64::max);
+
+    // Casting saturates at u16::MAX
+    bound as u16
+}
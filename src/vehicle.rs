@@ -0,0 +1,210 @@
+use crate::location::hash_f64;
+use crate::vrp::Vrp;
+use crate::vrp_result::VrpResult;
+use serde::Serialize;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// A vehicle of the fleet of an instance, see [fleet](Vrp::fleet)
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Vehicle {
+    pub id: u16,
+    pub name: Option<String>,
+    pub capacity: f64,
+}
+
+// Like locations, vehicles read by the crate never have a NaN capacity, so equality is total
+impl Eq for Vehicle {}
+
+impl Hash for Vehicle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.name.hash(state);
+        hash_f64(self.capacity, state);
+    }
+}
+
+impl Vehicle {
+    /// The name of this vehicle, or `Vehicle <id>` for anonymous ones
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("Vehicle {}", self.id),
+        }
+    }
+}
+
+/// How [assign_vehicles](VrpResult::assign_vehicles) gives a vehicle to every route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AssignmentStrategy {
+    /// The route at index `i` gets the vehicle at index `i` of the fleet
+    #[default]
+    ByIndex,
+    /// The most loaded routes first get the smallest free vehicle carrying their demand
+    ByCapacityFit,
+}
+
+impl FromStr for AssignmentStrategy {
+    type Err = String;
+
+    /// Parse `index` or `capacity_fit`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "index" => Ok(AssignmentStrategy::ByIndex),
+            "capacity_fit" => Ok(AssignmentStrategy::ByCapacityFit),
+            other => Err(format!("Unknown vehicle assignment: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for AssignmentStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AssignmentStrategy::ByIndex => "index",
+            AssignmentStrategy::ByCapacityFit => "capacity_fit",
+        })
+    }
+}
+
+/// Error raised when the routes of a solution cannot all get a vehicle
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssignmentError {
+    /// The solution has more routes than the fleet has vehicles
+    NotEnoughVehicles { n_routes: usize, n_vehicles: usize },
+    /// No free vehicle can carry the demand of the route at index `route`
+    NoVehicleFits { route: usize, demand: f64 },
+}
+
+impl fmt::Display for AssignmentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssignmentError::NotEnoughVehicles {
+                n_routes,
+                n_vehicles,
+            } => write!(
+                f,
+                "{} routes need a vehicle, the fleet only has {}",
+                n_routes, n_vehicles
+            ),
+            AssignmentError::NoVehicleFits { route, demand } => write!(
+                f,
+                "No free vehicle can carry the demand {} of route {}",
+                demand,
+                route + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AssignmentError {}
+
+impl Vrp {
+    /// Get the vehicles of this instance: the [named vehicles](Vrp::vehicles) if there are any,
+    /// else `n_vehicles` anonymous vehicles of `vehicle_capacity` with ids from 1
+    pub fn fleet(&self) -> Vec<Vehicle> {
+        if !self.vehicles.is_empty() {
+            return self.vehicles.clone();
+        }
+
+        (1..=self.n_vehicles)
+            .map(|id| Vehicle {
+                id,
+                name: None,
+                capacity: self.vehicle_capacity,
+            })
+            .collect()
+    }
+
+    /// The same instance with an explicit fleet, `n_vehicles` becomes the number of vehicles
+    pub fn with_vehicles(&self, vehicles: Vec<Vehicle>) -> Vrp {
+        Vrp {
+            n_vehicles: vehicles.len().try_into().unwrap_or(u16::MAX),
+            vehicles,
+            ..self.clone()
+        }
+    }
+}
+
+impl VrpResult {
+    /// Give a vehicle of the [fleet](Vrp::fleet) of `vrp` to every route, replacing the
+    /// previous assignment.
+    ///
+    /// Fails without changing this solution if there are more routes than vehicles, or if a
+    /// route carries more than its vehicle can
+    pub fn assign_vehicles(
+        &mut self,
+        vrp: &Vrp,
+        strategy: AssignmentStrategy,
+    ) -> Result<(), AssignmentError> {
+        let fleet = vrp.fleet();
+        if self.routes.len() > fleet.len() {
+            return Err(AssignmentError::NotEnoughVehicles {
+                n_routes: self.routes.len(),
+                n_vehicles: fleet.len(),
+            });
+        }
+
+        let too_heavy = |route: usize| AssignmentError::NoVehicleFits {
+            route,
+            demand: self.routes[route].max_load(),
+        };
+
+        let vehicles = match strategy {
+            AssignmentStrategy::ByIndex => {
+                if let Some(route) = self
+                    .routes
+                    .iter()
+                    .zip(&fleet)
+                    .position(|(route, vehicle)| route.max_load() > vehicle.capacity)
+                {
+                    return Err(too_heavy(route));
+                }
+                fleet[..self.routes.len()].to_vec()
+            }
+            AssignmentStrategy::ByCapacityFit => {
+                // Taking the smallest vehicle that fits in decreasing demand order never leaves
+                // a route without a vehicle if some assignment exists
+                let mut order: Vec<usize> = (0..self.routes.len()).collect();
+                order.sort_by(|&a, &b| {
+                    self.routes[b]
+                        .max_load()
+                        .total_cmp(&self.routes[a].max_load())
+                });
+
+                let mut free = fleet;
+                let mut vehicles = vec![Vehicle::default(); self.routes.len()];
+                for i in order {
+                    let demand = self.routes[i].max_load();
+                    let best = free
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, vehicle)| vehicle.capacity >= demand)
+                        .min_by(|(_, a), (_, b)| a.capacity.total_cmp(&b.capacity))
+                        .map(|(j, _)| j)
+                        .ok_or_else(|| too_heavy(i))?;
+                    vehicles[i] = free.remove(best);
+                }
+                vehicles
+            }
+        };
+
+        self.vehicles = vehicles;
+        Ok(())
+    }
+
+    /// Get the vehicle driving the route at `index`, `None` until
+    /// [assigned](VrpResult::assign_vehicles)
+    pub fn vehicle_of(&self, index: usize) -> Option<&Vehicle> {
+        self.vehicles.get(index)
+    }
+
+    /// The name of the route at `index` in reports: the [label](Vehicle::label) of its vehicle,
+    /// or `Route <index + 1>` without one
+    pub fn route_name(&self, index: usize) -> String {
+        match self.vehicle_of(index) {
+            Some(vehicle) => vehicle.label(),
+            None => format!("Route {}", index + 1),
+        }
+    }
+}
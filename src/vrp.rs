@@ -1,59 +1,404 @@
-use crate::location::Location;
+use crate::distance::TravelMatrix;
+use crate::location::{hash_f64, Location, LocationKind};
+use crate::location_table::LocationTable;
+use crate::objective::Objective;
+#[cfg(feature = "plot")]
+use crate::report::{save_plot, Plot, PlotFormat};
+use crate::route::{DriverBreak, Route};
+use crate::vehicle::Vehicle;
 use crate::vrp_result::VrpResult;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
 
+#[cfg(feature = "plot")]
+use plotters::coord::Shift;
+#[cfg(feature = "plot")]
 use plotters::prelude::*;
 
+/// The [JSON format](Vrp::from_json) of an instance
+#[derive(Serialize, Deserialize)]
+struct InstanceJson {
+    warehouse: Location,
+    customers: Vec<Location>,
+    n_vehicles: u16,
+    vehicle_capacity: f64,
+    vehicles: Option<Vec<VehicleJson>>,
+    other_depots: Option<Vec<Location>>,
+    open: Option<bool>,
+    max_route_duration: Option<f64>,
+    driver_breaks: Option<Vec<DriverBreak>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distances: Option<Vec<Vec<f32>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    durations: Option<Vec<Vec<f32>>>,
+}
+
+/// A vehicle of an [InstanceJson], with the capacity of the instance if it has none
+#[derive(Serialize, Deserialize)]
+struct VehicleJson {
+    id: u16,
+    name: Option<String>,
+    capacity: Option<f64>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Vrp {
     pub customers: Vec<Location>,
     pub warehouse: Location,
     pub n_vehicles: u16,
-    pub vehicle_capacity: u16,
+    pub vehicle_capacity: f64,
+    /// Named vehicles of the fleet, empty for an anonymous fleet of `n_vehicles`, see
+    /// [fleet](Vrp::fleet)
+    pub vehicles: Vec<Vehicle>,
+    /// Depots other than `warehouse` of a multi-depot instance, empty for a single depot, see
+    /// [depots](Vrp::depots). Routes start and end at the depot in their
+    /// [warehouse](crate::route::Route::warehouse)
+    pub other_depots: Vec<Location>,
+    /// Open VRP: vehicles stop at their last customer instead of coming back to the depot, the
+    /// routes built for this instance are [open](Route::open)
+    pub open: bool,
+    /// Longest a route may take, the driver shift length, from leaving the depot at 0 to coming
+    /// back, or to leaving the last customer of [open](Vrp::open) routes. Independent of the
+    /// due date of the depot, `None` for no limit
+    pub max_route_duration: Option<f64>,
+    /// Breaks every driver takes, in order, when the route still runs at their latest start,
+    /// see [breaks](Route::breaks)
+    pub driver_breaks: Vec<DriverBreak>,
+    /// What the heuristics rank solutions by, copied to their [results](VrpResult::objective).
+    /// Not part of the [fingerprint](Vrp::fingerprint), solutions ranked another way still
+    /// belong to the instance
+    pub objective: Objective,
 }
 
 impl Vrp {
+    /// Create an instance, `warehouse` is marked as the [depot](LocationKind::Depot)
     pub fn new(
         warehouse: Location,
         customers: Vec<Location>,
         n_vehicles: u16,
-        vehicle_capacity: u16,
+        vehicle_capacity: f64,
     ) -> Vrp {
         Vrp {
-            warehouse,
+            warehouse: Location {
+                kind: LocationKind::Depot,
+                ..warehouse
+            },
             customers,
             n_vehicles,
             vehicle_capacity,
+            vehicles: Vec::new(),
+            other_depots: Vec::new(),
+            open: false,
+            max_route_duration: None,
+            driver_breaks: Vec::new(),
+            objective: Objective::default(),
+        }
+    }
+
+    /// The same instance with `depots` as its [other depots](Vrp::other_depots), they are
+    /// marked as [depots](LocationKind::Depot)
+    pub fn with_other_depots(&self, depots: Vec<Location>) -> Vrp {
+        Vrp {
+            other_depots: depots
+                .into_iter()
+                .map(|depot| Location {
+                    kind: LocationKind::Depot,
+                    ..depot
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// A route of this instance without customers, from the warehouse and
+    /// [open](Route::open) if the instance is
+    pub fn empty_route(&self) -> Route {
+        self.empty_route_from(&self.warehouse)
+    }
+
+    /// Same as [empty_route](Vrp::empty_route), from `depot`, one of the [depots](Vrp::depots)
+    pub fn empty_route_from(&self, depot: &Location) -> Route {
+        Route {
+            warehouse: depot.clone(),
+            customers: Vec::new(),
+            open: self.open,
+            max_duration: self.max_route_duration,
+            breaks: self.driver_breaks.clone(),
+        }
+    }
+
+    /// Whether a route of this instance leaving `last` at `departure` ends within the
+    /// [max route duration](Vrp::max_route_duration)
+    pub(crate) fn fits_max_duration(&self, last: &Location, departure: f32) -> bool {
+        let Some(max) = self.max_route_duration else {
+            return true;
+        };
+        let end = if self.open {
+            departure
+        } else {
+            last.cost_to(&self.warehouse, departure)
+        };
+        end <= max as f32
+    }
+
+    /// Whether the driver of `route` can still take every [break](Vrp::driver_breaks) on time
+    /// once `next` is served last. Time windows are checked again, capacity is not
+    pub(crate) fn fits_breaks(&self, route: &Route, next: &Location) -> bool {
+        if self.driver_breaks.is_empty() {
+            return true;
         }
+        let customers: Vec<&Location> = route.customers.iter().chain([next]).collect();
+        route.is_valid_with(&customers, f64::INFINITY)
+    }
+
+    /// Iterate over every depot, `warehouse` first
+    pub fn depots(&self) -> impl Iterator<Item = &Location> {
+        std::iter::once(&self.warehouse).chain(self.other_depots.iter())
+    }
+
+    /// Whether this instance has more than one depot
+    pub fn is_multi_depot(&self) -> bool {
+        !self.other_depots.is_empty()
     }
 
     pub fn to_result(&self) -> VrpResult {
         VrpResult {
             n_vehicles: self.n_vehicles,
             vehicle_capacity: self.vehicle_capacity,
+            objective: self.objective,
             coord_bounds: self.get_coord_bounds(),
+            instance_fingerprint: self.fingerprint(),
             ..Default::default()
         }
     }
 
+    /// Read an instance from a JSON object:
+    ///
+    /// ```json
+    /// {
+    ///   "warehouse": { "id": 0, "x": 40, "y": 50, "demand": 0, "ready_time": 0,
+    ///                  "due_date": 1236, "service_time": 0 },
+    ///   "customers": [{ "id": 1, "x": 45, "y": 68, "demand": 10, "ready_time": 912,
+    ///                   "due_date": 967, "service_time": 90, "priority": "high" }],
+    ///   "n_vehicles": 25,
+    ///   "vehicle_capacity": 200,
+    ///   "vehicles": [{ "id": 1, "name": "Van", "capacity": 150 }],
+    ///   "other_depots": [],
+    ///   "open": false,
+    ///   "max_route_duration": 480,
+    ///   "driver_breaks": [{ "earliest": 200, "latest": 300, "duration": 30 }],
+    ///   "distances": [[0, 18.7], [18.7, 0]],
+    ///   "durations": [[0, 20], [20, 0]]
+    /// }
+    /// ```
+    ///
+    /// Locations are in the [JSON format](Location) of locations, vehicles have an `id` and an
+    /// optional `name` and `capacity`, the one of the instance if they have none, and breaks
+    /// are [DriverBreak] objects. Every field after `vehicle_capacity` is optional, absent or
+    /// null: an anonymous fleet, a single depot, routes coming back to it, no duration limit nor
+    /// break. The optional `distances` and `durations` rows are the
+    /// [travel matrix](Vrp::with_travel_matrix) of the instance, indexed like
+    /// [locations](Vrp::locations) then the other depots, travelling takes as long as the
+    /// distance without `durations`. The [objective](Vrp::objective) is a setting of the run,
+    /// not of the instance, it is left to its default
+    pub fn from_json(instance: &serde_json::Value) -> Result<Vrp, String> {
+        InstanceJson::deserialize(instance)
+            .map_err(|e| format!("Invalid instance: {}", e))
+            .and_then(Vrp::from_instance_json)
+    }
+
+    fn from_instance_json(instance: InstanceJson) -> Result<Vrp, String> {
+        let mut vrp = Vrp {
+            open: instance.open.unwrap_or(false),
+            max_route_duration: instance.max_route_duration,
+            driver_breaks: instance.driver_breaks.unwrap_or_default(),
+            ..Vrp::new(
+                instance.warehouse,
+                instance.customers,
+                instance.n_vehicles,
+                instance.vehicle_capacity,
+            )
+        }
+        .with_other_depots(instance.other_depots.unwrap_or_default());
+
+        let vehicles: Vec<Vehicle> = instance
+            .vehicles
+            .unwrap_or_default()
+            .into_iter()
+            .map(|vehicle| Vehicle {
+                id: vehicle.id,
+                name: vehicle.name,
+                capacity: vehicle.capacity.unwrap_or(vrp.vehicle_capacity),
+            })
+            .collect();
+        // No named vehicle is an anonymous fleet, like exported
+        if !vehicles.is_empty() {
+            vrp = vrp.with_vehicles(vehicles);
+        }
+
+        match (instance.distances, instance.durations) {
+            (Some(distances), durations) => TravelMatrix::explicit(distances, durations)
+                .and_then(|matrix| vrp.with_travel_matrix(matrix))
+                .ok_or_else(|| {
+                    String::from("The travel matrix must have a row and column per location")
+                }),
+            (None, Some(_)) => Err(String::from("Durations need distances")),
+            (None, None) => Ok(vrp),
+        }
+    }
+
+    /// Export this instance in its [JSON format](Vrp::from_json)
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.instance_json()).expect("Instances are valid JSON")
+    }
+
+    fn instance_json(&self) -> InstanceJson {
+        let matrix = self
+            .warehouse
+            .matrix_index
+            .as_ref()
+            .map(|index| &index.matrix);
+        let rows = |values: &[f32]| {
+            values
+                .chunks(matrix.map_or(1, |matrix| matrix.size.max(1)))
+                .map(<[f32]>::to_vec)
+                .collect()
+        };
+
+        InstanceJson {
+            warehouse: self.warehouse.clone(),
+            customers: self.customers.clone(),
+            n_vehicles: self.n_vehicles,
+            vehicle_capacity: self.vehicle_capacity,
+            vehicles: Some(
+                self.vehicles
+                    .iter()
+                    .map(|vehicle| VehicleJson {
+                        id: vehicle.id,
+                        name: vehicle.name.clone(),
+                        capacity: Some(vehicle.capacity),
+                    })
+                    .collect(),
+            ),
+            other_depots: Some(self.other_depots.clone()),
+            open: Some(self.open),
+            max_route_duration: self.max_route_duration,
+            driver_breaks: Some(self.driver_breaks.clone()),
+            distances: matrix.map(|matrix| rows(&matrix.distances)),
+            durations: matrix.map(|matrix| rows(&matrix.durations)),
+        }
+    }
+
+    /// Print this instance to a JSON string
+    pub fn as_json_string(&self) -> String {
+        serde_json::to_string_pretty(&self.instance_json()).expect("Instances are valid JSON")
+    }
+
+    /// Read an instance in the [JSON format](Vrp::from_json) from a file
+    pub fn from_json_file(path: impl AsRef<Path>) -> Result<Vrp, String> {
+        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid instance JSON: {}", e))
+            .and_then(Vrp::from_instance_json)
+    }
+
+    /// A hash of the content of this VRP, any edit changes it. Only stable within one process
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.warehouse.hash(&mut hasher);
+        self.customers.hash(&mut hasher);
+        self.n_vehicles.hash(&mut hasher);
+        hash_f64(self.vehicle_capacity, &mut hasher);
+        self.vehicles.hash(&mut hasher);
+        self.other_depots.hash(&mut hasher);
+        self.open.hash(&mut hasher);
+        self.max_route_duration.is_some().hash(&mut hasher);
+        if let Some(duration) = self.max_route_duration {
+            hash_f64(duration, &mut hasher);
+        }
+        for driver_break in &self.driver_breaks {
+            hash_f64(driver_break.earliest, &mut hasher);
+            hash_f64(driver_break.latest, &mut hasher);
+            hash_f64(driver_break.duration, &mut hasher);
+        }
+        if let Some(index) = &self.warehouse.matrix_index {
+            index.matrix.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Iterate over all locations of this VRP, the warehouse has index 0 and the customer at
+    /// `customers[i]` has index `i + 1`
+    pub fn locations(&self) -> impl Iterator<Item = &Location> {
+        std::iter::once(&self.warehouse).chain(self.customers.iter())
+    }
+
+    /// Get a location by its index (see [locations](Vrp::locations))
+    pub fn location(&self, index: usize) -> Option<&Location> {
+        match index {
+            0 => Some(&self.warehouse),
+            i => self.customers.get(i - 1),
+        }
+    }
+
+    /// Get the index of a location (see [locations](Vrp::locations))
+    pub fn index_of(&self, location: &Location) -> Option<usize> {
+        self.locations().position(|l| l == location)
+    }
+
+    /// Compute, for each location, the indices of its `k` nearest time-compatible customers,
+    /// sorted by increasing distance.
+    ///
+    /// The returned lists are indexed like [locations](Vrp::locations). A customer `b` is
+    /// time-compatible with `a` if it can still be served after serving `a` as early as possible,
+    /// so lists may hold fewer than `k` entries on instances with tight time windows.
+    pub fn neighbor_lists(&self, k: usize) -> Vec<Vec<usize>> {
+        let table = self.location_table();
+
+        (0..table.len())
+            .map(|a| {
+                let earliest_departure = table.ready_times[a] + table.service_times[a];
+
+                let mut neighbors: Vec<(usize, f32)> = (1..table.len())
+                    .filter(|&b| b != a)
+                    .map(|b| (b, table.distance(a, b)))
+                    .filter(|&(b, distance)| earliest_departure + distance <= table.due_dates[b])
+                    .collect();
+
+                neighbors
+                    .sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+                neighbors.into_iter().take(k).map(|(b, _)| b).collect()
+            })
+            .collect()
+    }
+
+    /// Copy the locations of this VRP into a [LocationTable], indexed like
+    /// [locations](Vrp::locations)
+    pub fn location_table(&self) -> LocationTable {
+        LocationTable::from_locations(self.locations())
+    }
+
     pub fn get_coord_bounds(&self) -> (i32, i32, i32, i32) {
-        let x_coords = std::iter::once(self.warehouse.x as i32).chain(
-            self.customers
-                .iter()
-                .map(|c| c.x as i32)
-                .chain(std::iter::once(self.warehouse.x as i32)),
-        );
-
-        let y_coords = std::iter::once(self.warehouse.y as i32).chain(
-            self.customers
-                .iter()
-                .map(|c| c.y as i32)
-                .chain(std::iter::once(self.warehouse.y as i32)),
-        );
+        let x_coords: Vec<i32> = self
+            .depots()
+            .chain(self.customers.iter())
+            .map(|l| l.x as i32)
+            .collect();
+
+        let y_coords: Vec<i32> = self
+            .depots()
+            .chain(self.customers.iter())
+            .map(|l| l.y as i32)
+            .collect();
         (
-            x_coords.clone().min().unwrap() - 10,
-            x_coords.max().unwrap() + 10,
-            y_coords.clone().min().unwrap() - 10,
-            y_coords.max().unwrap() + 10,
+            x_coords.iter().min().unwrap() - 10,
+            x_coords.iter().max().unwrap() + 10,
+            y_coords.iter().min().unwrap() - 10,
+            y_coords.iter().max().unwrap() + 10,
         )
     }
 
@@ -63,72 +408,110 @@ impl Vrp {
         output.push_str("# Vrp problem\n");
         output.push_str("## Details\n\n");
         output.push_str(&format! {"- N° of customers: {}\n", self.customers.len()});
+        if self.is_multi_depot() {
+            output.push_str(&format! {"- N° of depots: {}\n", self.depots().count()});
+        }
         output.push_str(&format! {"- N° of vehicles: {}\n", self.n_vehicles});
         output.push_str(&format! {"- Vehicle capacity: {}\n", self.vehicle_capacity});
+        if self.open {
+            output.push_str("- Open routes: vehicles do not return to the depot\n");
+        }
+        if let Some(max) = self.max_route_duration {
+            output.push_str(&format! {"- Max route duration: {}\n", max});
+        }
+        for driver_break in &self.driver_breaks {
+            output.push_str(&format! {
+                "- Driver break: {} starting between {} and {}\n",
+                driver_break.duration, driver_break.earliest, driver_break.latest
+            });
+        }
 
-        output.push_str("\n## Display\n\n");
+        #[cfg(feature = "plot")]
+        {
+            output.push_str("\n## Display\n\n");
 
-        output.push_str(&self.plot());
+            output.push_str(&self.plot());
+        }
 
         output
     }
 
+    #[cfg(feature = "plot")]
     pub fn plot(&self) -> String {
         let mut svg_data: String = String::new();
         {
             let root = SVGBackend::with_string(&mut svg_data, (800, 480)).into_drawing_area();
-            root.fill(&WHITE).unwrap();
-
-            let coord_bounds = self.get_coord_bounds();
-
-            let mut chart = ChartBuilder::on(&root)
-                .margin(5)
-                .x_label_area_size(35)
-                .y_label_area_size(40)
-                .build_cartesian_2d(
-                    coord_bounds.0..coord_bounds.1,
-                    coord_bounds.2..coord_bounds.3,
-                )
-                .unwrap();
+            self.draw(&root);
+            root.present().unwrap();
+        }
+        svg_data
+    }
 
-            chart
-                .configure_mesh()
-                .x_desc("X")
-                .y_desc("Y")
-                .draw()
-                .unwrap();
+    /// Plot the locations like [plot](Vrp::plot) to an image of `size` pixels at `path`
+    #[cfg(feature = "plot")]
+    pub fn plot_to_file(
+        &self,
+        path: impl AsRef<Path>,
+        format: PlotFormat,
+        size: (u32, u32),
+    ) -> std::io::Result<()> {
+        save_plot(self, path.as_ref(), format, size)
+    }
+}
+
+#[cfg(feature = "plot")]
+impl Plot for Vrp {
+    fn draw<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>) {
+        root.fill(&WHITE).unwrap();
+
+        let coord_bounds = self.get_coord_bounds();
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(5)
+            .x_label_area_size(35)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                coord_bounds.0..coord_bounds.1,
+                coord_bounds.2..coord_bounds.3,
+            )
+            .unwrap();
+
+        chart
+            .configure_mesh()
+            .x_desc("X")
+            .y_desc("Y")
+            .draw()
+            .unwrap();
 
-            // -- Plot the locations --
-            // Plot the warehouse
+        // -- Plot the locations --
+        // Plot the depots
+        for depot in self.depots() {
             chart
                 .draw_series(std::iter::once(Circle::new(
-                    (self.warehouse.x as i32, self.warehouse.y as i32),
+                    (depot.x as i32, depot.y as i32),
                     5,
                     RED.filled(),
                 )))
                 .unwrap();
+        }
 
-            // Plot the customers
-            for customer in &self.customers {
-                chart
-                    .draw_series(std::iter::once(Circle::new(
-                        (customer.x as i32, customer.y as i32),
-                        5,
-                        BLUE.filled(),
-                    )))
-                    .unwrap();
-
-                chart
-                    .draw_series(std::iter::once(Text::new(
-                        format!("{}", customer.id),
-                        (customer.x as i32 + 1, customer.y as i32 + 1),
-                        ("sans-serif", 15).into_font(),
-                    )))
-                    .unwrap();
-            }
+        // Plot the customers
+        for customer in &self.customers {
+            chart
+                .draw_series(std::iter::once(Circle::new(
+                    (customer.x as i32, customer.y as i32),
+                    5,
+                    BLUE.filled(),
+                )))
+                .unwrap();
 
-            root.present().unwrap();
+            chart
+                .draw_series(std::iter::once(Text::new(
+                    format!("{}", customer.id),
+                    (customer.x as i32 + 1, customer.y as i32 + 1),
+                    ("sans-serif", 15).into_font(),
+                )))
+                .unwrap();
         }
-        svg_data
     }
 }
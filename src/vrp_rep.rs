@@ -0,0 +1,330 @@
+use crate::distance::TravelMatrix;
+use crate::location::Location;
+use crate::vehicle::Vehicle;
+use crate::vrp::Vrp;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// An element of an XML document, only what the VRP-REP schema uses: attributes, child elements
+/// and text
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    fn from_start(start: &BytesStart) -> Option<Element> {
+        let attributes = start
+            .attributes()
+            .map(|attribute| {
+                let attribute = attribute.ok()?;
+                Some((
+                    String::from(attribute.key.local_name().as_ref()),
+                    attribute
+                        .normalized_value(XmlVersion::Implicit1_0)
+                        .ok()?
+                        .into_owned(),
+                ))
+            })
+            .collect::<Option<HashMap<String, String>>>()?;
+
+        Some(Element {
+            name: String::from(start.local_name().as_ref()),
+            attributes,
+            ..Default::default()
+        })
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// The number in the text of the child `name`, `None` if there is no such child, fails if
+    /// it is not a finite number
+    fn number(&self, name: &str) -> Result<Option<f64>, ()> {
+        self.child(name)
+            .map(|child| match child.text.trim().parse::<f64>() {
+                Ok(value) if value.is_finite() => Ok(value),
+                _ => Err(()),
+            })
+            .transpose()
+    }
+
+    fn attribute<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.attributes.get(name)?.parse().ok()
+    }
+}
+
+/// Read a whole XML document into its root element
+fn read_document(reader: impl BufRead) -> Option<Element> {
+    let mut reader = Reader::from_reader(reader);
+    reader.config_mut().trim_text(true);
+
+    let mut buffer = Vec::new();
+    // The open elements, the innermost last
+    let mut stack: Vec<Element> = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buffer).ok()? {
+            Event::Start(start) => stack.push(Element::from_start(&start)?),
+            Event::Empty(start) => {
+                let element = Element::from_start(&start)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Some(element),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&text.xml10_content());
+                }
+            }
+            Event::CData(data) => {
+                if let Some(element) = stack.last_mut() {
+                    element.text.push_str(&data.xml10_content());
+                }
+            }
+            Event::End(_) => {
+                let element = stack.pop()?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None => return Some(element),
+                }
+            }
+            Event::Eof => return None,
+            _ => {}
+        }
+        buffer.clear();
+    }
+}
+
+/// Parse an instance of the [VRP-REP](http://www.vrp-rep.org) XML schema, as published on
+/// vrp-rep.org.
+///
+/// Nodes of type 0 are depots, the departure node of the first vehicle profile is the warehouse
+/// and the others are [other depots](Vrp::other_depots). Every request gives the `quantity`,
+/// `tw` and `service_time` of its node, customers without a request have no demand. Profiles
+/// of the same capacity give `n_vehicles`, else every vehicle is one of the
+/// [named vehicles](Vrp::vehicles), a profile without `number` has one vehicle per customer.
+/// The `max_travel_time` of the first profile is the due date of the warehouse.
+///
+/// Distances are `euclidean`, rounded to `decimals` if given, or the `length` and
+/// `travel_time` of the `links` of the network as its [travel matrix](Vrp::with_travel_matrix),
+/// nodes without a link between them cannot be travelled between. Nodes without a time window
+/// can be served at any time. `None` if the document is not a valid instance
+pub fn parse_vrp_rep_from_reader(reader: impl BufRead) -> Option<Vrp> {
+    let instance = read_document(reader)?;
+    let network = instance.child("network")?;
+    let profiles: Vec<&Element> = instance
+        .child("fleet")?
+        .children("vehicle_profile")
+        .collect();
+
+    let nodes: Vec<&Element> = network.child("nodes")?.children("node").collect();
+    let position: HashMap<u16, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| Some((node.attribute("id")?, i)))
+        .collect::<Option<_>>()?;
+    if position.len() != nodes.len() {
+        return None;
+    }
+
+    let mut locations: Vec<Location> = nodes
+        .iter()
+        .map(|node| {
+            Some(Location {
+                id: node.attribute("id")?,
+                x: node.number("cx").ok()?.unwrap_or_default(),
+                y: node.number("cy").ok()?.unwrap_or_default(),
+                ..Default::default()
+            })
+        })
+        .collect::<Option<_>>()?;
+
+    let mut time_windows: Vec<Option<(f64, f64)>> = vec![None; nodes.len()];
+    for request in instance
+        .child("requests")
+        .into_iter()
+        .flat_map(|r| r.children("request"))
+    {
+        let node = *position.get(&request.attribute("node")?)?;
+        let location = &mut locations[node];
+
+        location.demand += request.number("quantity").ok()?.unwrap_or_default();
+        location.service_time += request.number("service_time").ok()?.unwrap_or_default();
+        if let Some(tw) = request.child("tw") {
+            let (start, end) = (tw.number("start").ok()?, tw.number("end").ok()?);
+            time_windows[node] = Some((start.unwrap_or(0.0), end.unwrap_or(f64::INFINITY)));
+        }
+    }
+
+    let depots: Vec<usize> = nodes
+        .iter()
+        .enumerate()
+        .filter(|(_, node)| node.attributes.get("type").map(String::as_str) == Some("0"))
+        .map(|(i, _)| i)
+        .collect();
+    let warehouse = match profiles.first()?.child("departure_node") {
+        Some(node) => *position.get(&node.text.trim().parse().ok()?)?,
+        None => *depots.first()?,
+    };
+
+    // Matrix rows follow the locations of the instance, then its other depots
+    let order: Vec<usize> = std::iter::once(warehouse)
+        .chain((0..nodes.len()).filter(|i| *i != warehouse && !depots.contains(i)))
+        .chain(depots.iter().copied().filter(|&i| i != warehouse))
+        .collect();
+    let n_customers = nodes.len() - 1 - depots.iter().filter(|&&i| i != warehouse).count();
+
+    let matrix = match network.child("links") {
+        Some(links) => {
+            let index: HashMap<usize, usize> = order
+                .iter()
+                .enumerate()
+                .map(|(i, &node)| (node, i))
+                .collect();
+            let size = order.len();
+            let mut distances = vec![vec![f32::INFINITY; size]; size];
+            let mut durations = vec![vec![f32::INFINITY; size]; size];
+            for i in 0..size {
+                distances[i][i] = 0.0;
+                durations[i][i] = 0.0;
+            }
+
+            for link in links.children("link") {
+                let tail = index[position.get(&link.attribute("tail")?)?];
+                let head = index[position.get(&link.attribute("head")?)?];
+                let length = link.number("length").ok()??;
+                let time = link.number("travel_time").ok()?.unwrap_or(length);
+
+                let directed = link.attributes.get("directed").map(String::as_str) == Some("true");
+                let arcs = if directed {
+                    vec![(tail, head)]
+                } else {
+                    vec![(tail, head), (head, tail)]
+                };
+                for (from, to) in arcs {
+                    distances[from][to] = length as f32;
+                    durations[from][to] = time as f32;
+                }
+            }
+            Some(TravelMatrix::explicit(distances, Some(durations))?)
+        }
+        None => match network.number("decimals").ok()? {
+            Some(decimals) => {
+                let scale = 10f64.powi(decimals as i32);
+                let distances = order
+                    .iter()
+                    .map(|&a| {
+                        order
+                            .iter()
+                            .map(|&b| {
+                                let (a, b) = (&locations[a], &locations[b]);
+                                ((a.x - b.x).hypot(a.y - b.y) * scale).round() as f32 / scale as f32
+                            })
+                            .collect()
+                    })
+                    .collect();
+                Some(TravelMatrix::explicit(distances, None)?)
+            }
+            None => None,
+        },
+    };
+
+    // Every route of at most all customers ends by then, so it never binds
+    let longest = match &matrix {
+        Some(matrix) => matrix
+            .durations
+            .iter()
+            .filter(|d| d.is_finite())
+            .fold(0f64, |max, &d| max.max(f64::from(d))),
+        None => locations
+            .iter()
+            .flat_map(|a| locations.iter().map(move |b| (a.x - b.x).hypot(a.y - b.y)))
+            .fold(0f64, f64::max),
+    };
+    let latest_due_date = time_windows
+        .iter()
+        .flatten()
+        .map(|&(_, end)| end)
+        .filter(|end| end.is_finite())
+        .fold(0f64, f64::max);
+    let horizon = latest_due_date
+        + longest * nodes.len() as f64
+        + locations.iter().map(|l| l.service_time).sum::<f64>();
+
+    for (location, time_window) in locations.iter_mut().zip(&time_windows) {
+        let (ready_time, due_date) = time_window.unwrap_or((0.0, horizon));
+        location.ready_time = ready_time;
+        location.due_date = if due_date.is_finite() {
+            due_date
+        } else {
+            horizon
+        };
+    }
+    if let Some(max_travel_time) = profiles[0].number("max_travel_time").ok()? {
+        locations[warehouse].due_date = max_travel_time;
+    }
+
+    let mut vehicles: Vec<Vehicle> = Vec::new();
+    for profile in &profiles {
+        let number = match profile.attributes.get("number") {
+            Some(number) => number.parse().ok()?,
+            None => u16::try_from(n_customers).ok()?,
+        };
+        let capacity = profile.number("capacity").ok()?.unwrap_or(f64::INFINITY);
+        for _ in 0..number {
+            vehicles.push(Vehicle {
+                id: u16::try_from(vehicles.len() + 1).ok()?,
+                name: None,
+                capacity,
+            });
+        }
+    }
+    let vehicle_capacity = vehicles.iter().map(|v| v.capacity).fold(0.0, f64::max);
+
+    let location = |i: usize| locations[i].clone();
+    let mut vrp = Vrp::new(
+        location(warehouse),
+        order[1..=n_customers]
+            .iter()
+            .map(|&i| location(i))
+            .collect(),
+        u16::try_from(vehicles.len()).ok()?,
+        vehicle_capacity,
+    )
+    .with_other_depots(
+        order[n_customers + 1..]
+            .iter()
+            .map(|&i| location(i))
+            .collect(),
+    );
+    if vehicles.iter().any(|v| v.capacity != vehicle_capacity) {
+        vrp = vrp.with_vehicles(vehicles);
+    }
+
+    match matrix {
+        Some(matrix) => vrp.with_travel_matrix(matrix),
+        None => Some(vrp),
+    }
+}
+
+impl Vrp {
+    /// Read an instance of the [VRP-REP XML schema](parse_vrp_rep_from_reader)
+    pub fn from_vrp_rep_file(path: impl AsRef<Path>) -> Option<Vrp> {
+        let file = File::open(path).ok()?;
+        parse_vrp_rep_from_reader(BufReader::new(file))
+    }
+}
@@ -1,33 +1,311 @@
+use crate::heuristics::stats::SolveStats;
+use crate::heuristics::trace::SearchTrace;
+#[cfg(feature = "plot")]
+use crate::location::Location;
+use crate::objective::Objective;
+#[cfg(feature = "plot")]
+use crate::report::{draw_route_arcs, save_plot, Plot, PlotFormat};
+use crate::report::{ReportOptions, RouteOrder};
+use crate::route::Stop;
+use crate::vehicle::Vehicle;
 use crate::{route::Route, vrp::Vrp};
+#[cfg(feature = "plot")]
+use plotters::coord::Shift;
+#[cfg(feature = "plot")]
 use plotters::prelude::*;
+#[cfg(feature = "plot")]
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::Path;
 
 #[derive(Debug, Clone, Default)]
 pub struct VrpResult {
     pub n_vehicles: u16,
-    pub vehicle_capacity: u16,
-    pub routes: Vec<Route>,
+    pub vehicle_capacity: f64,
+    /// What this solution is ranked by, the [objective](Vrp::objective) of its instance
+    pub objective: Objective,
+    /// Read with [routes](VrpResult::routes), edits go through [push_route](VrpResult::push_route),
+    /// [replace_route](VrpResult::replace_route), [remove_route](VrpResult::remove_route) or
+    /// [routes_mut](VrpResult::routes_mut) so the cached cost stays up to date
+    pub(crate) routes: Vec<Route>,
     pub coord_bounds: (i32, i32, i32, i32),
     pub heuristic_cost_history: Option<Vec<f32>>,
+    /// Restarts of the search of the heuristic, like the pheromone resets of aco, as the number
+    /// of entries of the [cost history](VrpResult::heuristic_cost_history) before each one
+    pub heuristic_restarts: Vec<usize>,
+    /// Effort spent by the solver that built this solution, `None` for solutions built by hand
+    pub stats: Option<SolveStats>,
+    /// Statistics of every iteration of the solver that built this solution, if its params
+    /// asked for them
+    pub trace: Option<SearchTrace>,
+    /// Ids of the customers no route serves because the constraints rule them out, the solution
+    /// is partial if this is not empty
+    pub unassigned: Vec<u16>,
+    /// Vehicle driving each route, by route index, see
+    /// [assign_vehicles](VrpResult::assign_vehicles). Empty before the assignment, routes added
+    /// after it have no vehicle until the next one
+    pub vehicles: Vec<Vehicle>,
+    pub(crate) cost_cache: CostCache,
+    /// [Fingerprint](Vrp::fingerprint) of the instance this solution was built for
+    pub(crate) instance_fingerprint: u64,
 }
 
+/// Per-route costs and their total, maintained incrementally
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CostCache {
+    route_costs: Vec<f32>,
+    total: f32,
+    valid: bool,
+}
+
+/// The [JSON format](VrpResult::to_json) of a solution, borrowing it
+#[derive(Serialize)]
+struct SolutionJson<'a> {
+    objective: String,
+    total_cost: f32,
+    total_distance: f32,
+    is_complete: bool,
+    n_vehicles: u16,
+    vehicle_capacity: f64,
+    routes: Vec<RouteJson<'a>>,
+    unassigned: &'a [u16],
+    heuristic_cost_history: Option<&'a [f32]>,
+    heuristic_restarts: &'a [usize],
+    stats: Option<SolveStats>,
+    trace: Option<&'a SearchTrace>,
+}
+
+/// A route of a [SolutionJson], with its schedule and totals
+#[derive(Serialize)]
+struct RouteJson<'a> {
+    vehicle: Option<&'a Vehicle>,
+    depot: u16,
+    customers: Vec<u16>,
+    stops: Vec<StopJson>,
+    break_starts: Option<Vec<f32>>,
+    duration: f32,
+    total_cost: f32,
+    total_distance: f32,
+    total_demand: f64,
+    is_valid: bool,
+}
+
+/// The [Stop] at a customer of a [RouteJson]
+#[derive(Serialize)]
+struct StopJson {
+    customer: u16,
+    #[serde(flatten)]
+    stop: Stop,
+}
+
+/// The fields of a [SolutionJson] read back by [from_json](VrpResult::from_json), the others
+/// are computed again from the instance
+#[derive(Deserialize)]
+struct SolutionInput {
+    n_vehicles: Option<f64>,
+    vehicle_capacity: Option<f64>,
+    routes: Vec<RouteInput>,
+    #[serde(default)]
+    unassigned: Vec<u16>,
+    heuristic_cost_history: Option<Vec<f32>>,
+    #[serde(default)]
+    heuristic_restarts: Vec<usize>,
+    stats: Option<SolveStats>,
+}
+
+#[derive(Deserialize)]
+struct RouteInput {
+    customers: Vec<u16>,
+    vehicle: Option<VehicleRef>,
+    depot: Option<u16>,
+}
+
+/// A vehicle of a [RouteInput], looked up by id in the fleet of the instance
+#[derive(Deserialize)]
+struct VehicleRef {
+    id: u16,
+}
+
+/// Error raised when a saved solution cannot be loaded
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    /// The file is not valid JSON
+    Json(serde_json::Error),
+    /// The solution does not match its format or the instance
+    Invalid(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "Failed to read solution: {}", e),
+            LoadError::Json(e) => write!(f, "Invalid solution JSON: {}", e),
+            LoadError::Invalid(e) => write!(f, "Invalid solution: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
 impl VrpResult {
     pub fn from_vrp(
         vrp: &Vrp,
         routes: Vec<Route>,
         heuristic_cost_history: Option<Vec<f32>>,
     ) -> VrpResult {
-        VrpResult {
+        let mut result = VrpResult {
             routes,
             heuristic_cost_history,
             ..vrp.to_result()
-        }
+        };
+        result.refresh_cost();
+        result
+    }
+
+    /// Whether every customer is assigned to a route
+    pub fn is_complete(&self) -> bool {
+        self.unassigned.is_empty()
     }
 
+    /// Get the total cost of all routes, uses the cached cost when it is up to date
     pub fn total_cost(&self) -> f32 {
+        if self.cost_is_cached() {
+            return self.cost_cache.total;
+        }
         self.routes.iter().map(|x| x.total_cost()).sum()
     }
 
+    /// Get the total distance of all routes
+    pub fn total_distance(&self) -> f32 {
+        self.routes.iter().map(Route::total_distance).sum()
+    }
+
+    /// Whether this solution is better than `other` under its [objective](VrpResult::objective)
+    pub fn is_better_than(&self, other: &VrpResult) -> bool {
+        self.objective.is_better(self, other)
+    }
+
+    /// Get the cost of the route at `index`
+    pub fn route_cost(&self, index: usize) -> f32 {
+        if self.cost_is_cached() {
+            return self.cost_cache.route_costs[index];
+        }
+        self.routes[index].total_cost()
+    }
+
+    /// Get by how much the total cost would change if the route at `index` was replaced by `route`,
+    /// without modifying this result
+    pub fn cost_delta_if_replaced(&self, index: usize, route: &Route) -> f32 {
+        route.total_cost() - self.route_cost(index)
+    }
+
+    /// The routes of this solution
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// The routes of this solution to edit them freely, drops the cached cost so the next
+    /// [total_cost](VrpResult::total_cost) computes it again. Prefer the methods updating the
+    /// cache, like [replace_route](VrpResult::replace_route)
+    pub fn routes_mut(&mut self) -> &mut Vec<Route> {
+        self.invalidate_cost();
+        &mut self.routes
+    }
+
+    /// Add a route, updating the cached cost
+    pub fn push_route(&mut self, route: Route) {
+        if self.cost_is_cached() {
+            let cost = route.total_cost();
+            self.cost_cache.route_costs.push(cost);
+            self.cost_cache.total += cost;
+        }
+        self.routes.push(route);
+    }
+
+    /// Replace the route at `index`, updating the cached cost, returns the previous route
+    pub fn replace_route(&mut self, index: usize, route: Route) -> Route {
+        if self.cost_is_cached() {
+            let cost = route.total_cost();
+            self.cost_cache.total += cost - self.cost_cache.route_costs[index];
+            self.cost_cache.route_costs[index] = cost;
+        }
+        std::mem::replace(&mut self.routes[index], route)
+    }
+
+    /// Remove the route at `index` and its vehicle, updating the cached cost, returns the removed
+    /// route
+    pub fn remove_route(&mut self, index: usize) -> Route {
+        if self.cost_is_cached() {
+            self.cost_cache.total -= self.cost_cache.route_costs.remove(index);
+        }
+        if index < self.vehicles.len() {
+            self.vehicles.remove(index);
+        }
+        self.routes.remove(index)
+    }
+
+    /// Recompute the cached cost from scratch, this also clears the rounding errors accumulated by
+    /// incremental updates
+    pub fn refresh_cost(&mut self) {
+        let route_costs: Vec<f32> = self.routes.iter().map(|x| x.total_cost()).collect();
+
+        self.cost_cache = CostCache {
+            total: route_costs.iter().sum(),
+            route_costs,
+            valid: true,
+        };
+    }
+
+    /// Drop the cached cost, [routes_mut](VrpResult::routes_mut) calls this
+    pub fn invalidate_cost(&mut self) {
+        self.cost_cache = CostCache::default();
+    }
+
+    /// Whether `vrp` changed since this solution was built for it, a stale solution may visit
+    /// removed customers or miss new ones
+    pub fn is_stale(&self, vrp: &Vrp) -> bool {
+        self.instance_fingerprint != vrp.fingerprint()
+    }
+
+    fn cost_is_cached(&self) -> bool {
+        self.cost_cache.valid && self.cost_cache.route_costs.len() == self.routes.len()
+    }
+
+    /// The same solution with every route in its [normalized](Route::normalized) orientation and
+    /// the routes sorted by customer ids, so equivalent solutions compare equal. Routes keep
+    /// their vehicle
+    pub fn normalized(&self) -> VrpResult {
+        let routes: Vec<Route> = self
+            .routes
+            .iter()
+            .map(|route| route.normalized(self.vehicle_capacity))
+            .collect();
+
+        let mut order: Vec<usize> = (0..routes.len()).collect();
+        order.sort_by(|&a, &b| {
+            routes[a]
+                .customers
+                .iter()
+                .map(|c| c.id)
+                .cmp(routes[b].customers.iter().map(|c| c.id))
+        });
+
+        let mut result = VrpResult {
+            routes: order.iter().map(|&i| routes[i].clone()).collect(),
+            vehicles: order
+                .iter()
+                .map_while(|&i| self.vehicle_of(i).cloned())
+                .collect(),
+            ..self.clone()
+        };
+        result.refresh_cost();
+        result
+    }
+
     pub fn total_cost_with(&self, routes: &[Route]) -> f32 {
         routes.iter().map(|x| x.total_cost()).sum()
     }
@@ -54,106 +332,360 @@ impl VrpResult {
         output.push_str("Vrp problem\n");
         output.push_str(&format! {"Total cost: {}\n", self.total_cost()});
         output.push_str(&format! {"n_vehicles: {}\n", self.n_vehicles});
+        if !self.is_complete() {
+            output.push_str(&format! {"Unassigned customers: {:?}\n", self.unassigned});
+        }
         output.push('\n');
         for (i, route) in self.routes.iter().enumerate() {
             output.push('\n');
             output.push_str(&format! {"Is valid: {}\n", route.is_valid(self.vehicle_capacity)});
-            output.push_str(&route.print_to_string(Some(&format! {"Route {}", i + 1})));
+            output.push_str(&route.print_to_string(Some(&self.route_name(i))));
             output.push('\n');
         }
         output
     }
 
-    /// Print this VRP problem to a Markdown string
+    /// Print this VRP problem to a Markdown string, with the default [ReportOptions]
     pub fn as_md_string(&self) -> String {
+        self.as_md_string_with(&ReportOptions::default())
+    }
+
+    /// Print this VRP problem to a Markdown string, with the routes in the
+    /// [order](ReportOptions::route_order) of the options, the seed only matters for the charts
+    /// of the `plot` feature
+    pub fn as_md_string_with(&self, options: &ReportOptions) -> String {
+        if options.route_order != RouteOrder::Construction {
+            return self
+                .with_route_order(options.route_order)
+                .as_md_string_with(&ReportOptions {
+                    route_order: RouteOrder::Construction,
+                    ..*options
+                });
+        }
+
         let mut output = String::new();
         output.push_str("# Vrp problem\n");
+
+        if !self.is_complete() {
+            output.push_str(&format! {
+                "\n> **Partial solution**: {} customers are not served: {}\n\n",
+                self.unassigned.len(),
+                self.unassigned.iter().map(u16::to_string).collect::<Vec<_>>().join(", ")
+            });
+        }
+
         output.push_str("## Details\n\n");
         output.push_str(&format! {"- Total cost: {}\n", self.total_cost()});
         output.push_str(&format! {"- N° of vehicles: {}\n", self.n_vehicles});
         output.push_str(&format! {"- Vehicle capacity: {}\n", self.vehicle_capacity});
 
-        if let Some(val) = self.plot_heuristic_cost_history() {
-            output.push_str("\n## Heuristic Cost History\n\n");
-            output.push_str(&val);
+        output.push_str("\n## Capacity Utilization\n\n");
+        output.push_str(&self.utilization().as_md_string());
+
+        if let Some(stats) = &self.stats {
+            output.push_str("\n## Solver Effort\n\n");
+            output.push_str(&stats.as_md_string());
         }
 
-        output.push_str("\n## Display\n\n");
+        #[cfg(feature = "plot")]
+        {
+            if let Some(val) = self.plot_heuristic_cost_history() {
+                output.push_str("\n## Heuristic Cost History\n\n");
+                output.push_str(&val);
+            }
 
-        output.push_str(&self.plot());
+            output.push_str("\n## Display\n\n");
+
+            output.push_str(&self.plot_with(options));
+        }
 
         output.push_str("\n## Routes\n");
         for (i, route) in self.routes.iter().enumerate() {
-            output.push_str(&format! {"\n### Route {}\n", i + 1});
+            output.push_str(&format! {"\n### {}\n", self.route_name(i)});
             output.push_str(&route.print_to_md_string(self.vehicle_capacity, self.coord_bounds));
         }
         output
     }
 
-    pub fn plot(&self) -> String {
-        let mut svg_data: String = String::new();
-        {
-            let root = SVGBackend::with_string(&mut svg_data, (800, 480)).into_drawing_area();
-            root.fill(&WHITE).unwrap();
+    /// Export this solution as a JSON value: its routes with their customer ids and the
+    /// schedule of every stop, the unassigned customers, the totals and the data of the solver
+    /// that built it, like its [stats](VrpResult::stats)
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self.solution_json()).expect("Solutions are valid JSON")
+    }
 
-            let coord_bounds = self.coord_bounds;
+    fn solution_json(&self) -> SolutionJson<'_> {
+        let routes = self
+            .routes
+            .iter()
+            .enumerate()
+            .map(|(i, route)| RouteJson {
+                vehicle: self.vehicle_of(i),
+                depot: route.warehouse.id,
+                customers: route.customers.iter().map(|c| c.id).collect(),
+                stops: route
+                    .customers
+                    .iter()
+                    .zip(route.schedule())
+                    .map(|(customer, stop)| StopJson {
+                        customer: customer.id,
+                        stop,
+                    })
+                    .collect(),
+                break_starts: (!route.breaks.is_empty()).then(|| route.break_starts()),
+                duration: route.duration(),
+                total_cost: route.total_cost(),
+                total_distance: route.total_distance(),
+                total_demand: route.total_demand(),
+                is_valid: route.is_valid(self.vehicle_capacity),
+            })
+            .collect();
 
-            let mut chart = ChartBuilder::on(&root)
-                .margin(5)
-                .x_label_area_size(35)
-                .y_label_area_size(40)
-                .build_cartesian_2d(
-                    coord_bounds.0..coord_bounds.1,
-                    coord_bounds.2..coord_bounds.3,
-                )
-                .unwrap();
+        SolutionJson {
+            objective: self.objective.to_string(),
+            total_cost: self.total_cost(),
+            total_distance: self.total_distance(),
+            is_complete: self.is_complete(),
+            n_vehicles: self.n_vehicles,
+            vehicle_capacity: self.vehicle_capacity,
+            routes,
+            unassigned: &self.unassigned,
+            heuristic_cost_history: self.heuristic_cost_history.as_deref(),
+            heuristic_restarts: &self.heuristic_restarts,
+            stats: self.stats,
+            trace: self.trace.as_ref(),
+        }
+    }
 
-            chart
-                .configure_mesh()
-                .x_desc("X")
-                .y_desc("Y")
-                .draw()
-                .unwrap();
+    /// Print this solution to a JSON string
+    pub fn as_json_string(&self) -> String {
+        serde_json::to_string_pretty(&self.solution_json()).expect("Solutions are valid JSON")
+    }
 
-            // -- Plot the locations --
-            // Plot the warehouse
-            if let Some(val) = self.routes.first() {
-                chart
-                    .draw_series(std::iter::once(Circle::new(
-                        (val.warehouse.x as i32, val.warehouse.y as i32),
-                        3,
-                        RED.filled(),
-                    )))
-                    .unwrap();
+    /// Read a solution exported by [to_json](VrpResult::to_json) back, customers are looked up
+    /// by id in `vrp`. Only the routes, the unassigned customers and the data of the solver are
+    /// read, schedules and totals are computed again from the instance.
+    ///
+    /// Fails if the fleet differs from the instance, or if a customer is unknown or visited
+    /// twice, counting the unassigned ones. Routes breaking time windows or capacity are kept,
+    /// like solvers may return them.
+    pub fn from_json(value: &serde_json::Value, vrp: &Vrp) -> Result<VrpResult, LoadError> {
+        let solution =
+            SolutionInput::deserialize(value).map_err(|e| LoadError::Invalid(e.to_string()))?;
+
+        for (key, found, expected) in [
+            ("n_vehicles", solution.n_vehicles, f64::from(vrp.n_vehicles)),
+            (
+                "vehicle_capacity",
+                solution.vehicle_capacity,
+                vrp.vehicle_capacity,
+            ),
+        ] {
+            if let Some(found) = found.filter(|&found| found != expected) {
+                return Err(LoadError::Invalid(format!(
+                    "{} is {}, the instance has {}",
+                    key, found, expected
+                )));
             }
+        }
+
+        let ids: Vec<Vec<u16>> = solution
+            .routes
+            .iter()
+            .map(|route| route.customers.clone())
+            .collect();
 
-            // Plot the customers
-            for route in &self.routes {
-                let color = random_color();
-                for customer in &route.customers {
-                    chart
-                        .draw_series(std::iter::once(Circle::new(
-                            (customer.x as i32, customer.y as i32),
-                            3,
-                            color.filled(),
-                        )))
-                        .unwrap();
-
-                    chart
-                        .draw_series(std::iter::once(Text::new(
-                            format!("{}", customer.id),
-                            (customer.x as i32 + 1, customer.y as i32 + 1),
-                            ("sans-serif", 15).into_font(),
-                        )))
-                        .unwrap();
+        // Vehicles are looked up by id in the fleet, the assignment stops at the first route
+        // without one like after adding routes
+        let fleet = vrp.fleet();
+        let mut used = HashSet::new();
+        let vehicles = solution
+            .routes
+            .iter()
+            .map(|route| match route.vehicle {
+                Some(VehicleRef { id }) => {
+                    if !used.insert(id) {
+                        return Err(LoadError::Invalid(format!(
+                            "Vehicle {} drives two routes",
+                            id
+                        )));
+                    }
+                    fleet
+                        .iter()
+                        .find(|v| v.id == id)
+                        .cloned()
+                        .map(Some)
+                        .ok_or_else(|| LoadError::Invalid(format!("Unknown vehicle {}", id)))
                 }
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<Option<Vehicle>>, _>>()?
+            .into_iter()
+            .map_while(|vehicle| vehicle)
+            .collect();
+
+        let mut routes = VrpResult::routes_from_ids(&ids, vrp)?;
+        VrpResult::check_unassigned(&solution.unassigned, &ids, vrp)?;
+
+        // Routes start from the warehouse unless they name another depot of the instance
+        for (route, input) in routes.iter_mut().zip(&solution.routes) {
+            if let Some(id) = input.depot {
+                route.warehouse = vrp
+                    .depots()
+                    .find(|d| d.id == id)
+                    .cloned()
+                    .ok_or_else(|| LoadError::Invalid(format!("Unknown depot {}", id)))?;
+            }
+        }
+
+        Ok(VrpResult {
+            unassigned: solution.unassigned,
+            vehicles,
+            heuristic_restarts: solution.heuristic_restarts,
+            stats: solution.stats,
+            ..VrpResult::from_vrp(vrp, routes, solution.heuristic_cost_history)
+        })
+    }
+
+    /// Check that the unassigned ids are customers of `vrp`, listed once and not visited by the
+    /// routes `routes`
+    pub(crate) fn check_unassigned(
+        unassigned: &[u16],
+        routes: &[Vec<u16>],
+        vrp: &Vrp,
+    ) -> Result<(), LoadError> {
+        let mut seen: HashSet<u16> = routes.iter().flatten().copied().collect();
+
+        for &id in unassigned {
+            if !seen.insert(id) {
+                return Err(LoadError::Invalid(format!(
+                    "Customer {} is both visited and unassigned, or unassigned twice",
+                    id
+                )));
+            }
+            if !vrp.customers.iter().any(|c| c.id == id) {
+                return Err(LoadError::Invalid(format!("Unknown customer {}", id)));
             }
+        }
+
+        Ok(())
+    }
+
+    /// Build routes visiting the customers of `vrp` with the given ids, fails if an id is unknown
+    /// or visited twice
+    pub(crate) fn routes_from_ids(ids: &[Vec<u16>], vrp: &Vrp) -> Result<Vec<Route>, LoadError> {
+        let mut visited = HashSet::new();
+
+        ids.iter()
+            .map(|route| {
+                let customers = route
+                    .iter()
+                    .map(|&id| {
+                        if !visited.insert(id) {
+                            return Err(LoadError::Invalid(format!(
+                                "Customer {} is visited twice",
+                                id
+                            )));
+                        }
 
+                        vrp.customers
+                            .iter()
+                            .find(|c| c.id == id)
+                            .cloned()
+                            .ok_or_else(|| LoadError::Invalid(format!("Unknown customer {}", id)))
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                Ok(Route {
+                    customers,
+                    ..vrp.empty_route()
+                })
+            })
+            .collect()
+    }
+
+    /// Write this solution to a JSON file, see [load](VrpResult::load)
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        fs::write(path, self.as_json_string())
+    }
+
+    /// Load a solution written by [save](VrpResult::save) and check it against `vrp`, see
+    /// [from_json](VrpResult::from_json)
+    pub fn load(path: impl AsRef<Path>, vrp: &Vrp) -> Result<VrpResult, LoadError> {
+        let content = fs::read_to_string(path).map_err(LoadError::Io)?;
+        let value = serde_json::from_str(&content).map_err(LoadError::Json)?;
+
+        VrpResult::from_json(&value, vrp)
+    }
+
+    /// Print this solution to a CSV string, one line per visited customer, the vehicle column holds
+    /// the vehicle id and is empty for routes without a vehicle
+    pub fn as_csv_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str("route,vehicle,position,customer_id,demand,ready_time,due_date,arrival,waiting_time,departure\n");
+
+        for (i, route) in self.routes.iter().enumerate() {
+            let vehicle = self
+                .vehicle_of(i)
+                .map_or_else(String::new, |v| v.id.to_string());
+            for (j, (customer, stop)) in route.customers.iter().zip(route.schedule()).enumerate() {
+                output.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{},{}\n",
+                    i + 1,
+                    vehicle,
+                    j + 1,
+                    customer.id,
+                    customer.demand,
+                    customer.ready_time,
+                    customer.due_date,
+                    stop.arrival,
+                    stop.waiting_time,
+                    stop.departure
+                ));
+            }
+        }
+        output
+    }
+
+    /// Plot the routes with the default [ReportOptions]
+    #[cfg(feature = "plot")]
+    pub fn plot(&self) -> String {
+        self.plot_with(&ReportOptions::default())
+    }
+
+    /// Plot the routes, each one in a random color drawn from the [seed](ReportOptions::seed),
+    /// arrows show the direction of travel
+    #[cfg(feature = "plot")]
+    pub fn plot_with(&self, options: &ReportOptions) -> String {
+        let mut svg_data: String = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg_data, (800, 480)).into_drawing_area();
+            ResultPlot {
+                result: self,
+                options,
+            }
+            .draw(&root);
             root.present().unwrap();
         }
         svg_data
     }
 
+    /// Plot the routes like [plot](VrpResult::plot) to an image of `size` pixels at `path`
+    #[cfg(feature = "plot")]
+    pub fn plot_to_file(
+        &self,
+        path: impl AsRef<Path>,
+        format: PlotFormat,
+        size: (u32, u32),
+    ) -> std::io::Result<()> {
+        let plot = ResultPlot {
+            result: self,
+            options: &ReportOptions::default(),
+        };
+        save_plot(&plot, path.as_ref(), format, size)
+    }
+
+    #[cfg(feature = "plot")]
     pub fn plot_heuristic_cost_history(&self) -> Option<String> {
         let mut svg_data: String = String::new();
         if let Some(history) = &self.heuristic_cost_history {
@@ -186,6 +718,16 @@ impl VrpResult {
                 .label("Cost")
                 .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
 
+            if !self.heuristic_restarts.is_empty() {
+                chart
+                    .draw_series(self.heuristic_restarts.iter().map(|&i| {
+                        PathElement::new(vec![(i, min_cost), (i, max_cost)], BLUE.mix(0.5))
+                    }))
+                    .unwrap()
+                    .label("Restart")
+                    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE.mix(0.5)));
+            }
+
             chart
                 .configure_series_labels()
                 .background_style(WHITE.mix(0.8))
@@ -200,8 +742,83 @@ impl VrpResult {
     }
 }
 
-fn random_color() -> RGBColor {
-    let mut rng = rand::thread_rng();
+/// The routes of a solution, colored from the seed of their report options
+#[cfg(feature = "plot")]
+struct ResultPlot<'a> {
+    result: &'a VrpResult,
+    options: &'a ReportOptions,
+}
+
+#[cfg(feature = "plot")]
+impl Plot for ResultPlot<'_> {
+    fn draw<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>) {
+        let mut rng = self.options.rng();
+        root.fill(&WHITE).unwrap();
+
+        let coord_bounds = self.result.coord_bounds;
+
+        let mut chart = ChartBuilder::on(root)
+            .margin(5)
+            .x_label_area_size(35)
+            .y_label_area_size(40)
+            .build_cartesian_2d(
+                coord_bounds.0..coord_bounds.1,
+                coord_bounds.2..coord_bounds.3,
+            )
+            .unwrap();
+
+        chart
+            .configure_mesh()
+            .x_desc("X")
+            .y_desc("Y")
+            .draw()
+            .unwrap();
+
+        // -- Plot the locations --
+        // Plot the depots of the routes, several on multi-depot instances
+        let mut depots: Vec<&Location> = Vec::new();
+        for route in &self.result.routes {
+            if !depots.contains(&&route.warehouse) {
+                depots.push(&route.warehouse);
+            }
+        }
+        for depot in depots {
+            chart
+                .draw_series(std::iter::once(Circle::new(
+                    (depot.x as i32, depot.y as i32),
+                    3,
+                    RED.filled(),
+                )))
+                .unwrap();
+        }
+
+        // Plot the customers and the arcs of their route
+        for route in &self.result.routes {
+            let color = random_color(&mut rng);
+            draw_route_arcs(&mut chart, route, color);
+            for customer in &route.customers {
+                chart
+                    .draw_series(std::iter::once(Circle::new(
+                        (customer.x as i32, customer.y as i32),
+                        3,
+                        color.filled(),
+                    )))
+                    .unwrap();
+
+                chart
+                    .draw_series(std::iter::once(Text::new(
+                        format!("{}", customer.id),
+                        (customer.x as i32 + 1, customer.y as i32 + 1),
+                        ("sans-serif", 15).into_font(),
+                    )))
+                    .unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "plot")]
+fn random_color(rng: &mut impl Rng) -> RGBColor {
     RGBColor(
         rng.gen_range(0..=255),
         rng.gen_range(0..=255),
@@ -0,0 +1,104 @@
+use crate::route::Route;
+use crate::vrp_result::VrpResult;
+
+/// When one route leaves the depot and the waiting it saves, see
+/// [minimize_waiting](VrpResult::minimize_waiting)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Departure {
+    /// Time the vehicle leaves the depot
+    pub time: f32,
+    /// Waiting at the customers when leaving at `time`
+    pub waiting_time: f32,
+    /// Waiting avoided compared to leaving at 0
+    pub waiting_saved: f32,
+}
+
+/// Departure times of every route of a solution, with the least waiting at the customers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WaitingPlan {
+    /// Departure of each route, by route index
+    pub departures: Vec<Departure>,
+}
+
+impl WaitingPlan {
+    /// Get the waiting left at the customers of all routes
+    pub fn total_waiting_time(&self) -> f32 {
+        self.departures.iter().map(|d| d.waiting_time).sum()
+    }
+
+    /// Get the waiting all routes avoid compared to leaving at 0
+    pub fn waiting_saved(&self) -> f32 {
+        self.departures.iter().map(|d| d.waiting_saved).sum()
+    }
+
+    /// Print this plan to a Markdown table
+    pub fn as_md_string(&self) -> String {
+        let mut output = String::new();
+        output.push_str(&format! {"- Waiting saved: {:.2}\n", self.waiting_saved()});
+        output.push_str(&format! {"- Waiting left: {:.2}\n", self.total_waiting_time()});
+
+        output.push_str("\n| Route | Departure | Waiting | Saved |\n|---|---|---|---|\n");
+        for (i, departure) in self.departures.iter().enumerate() {
+            output.push_str(&format! {
+                "| {} | {:.2} | {:.2} | {:.2} |\n",
+                i + 1, departure.time, departure.waiting_time, departure.waiting_saved
+            });
+        }
+        output
+    }
+}
+
+impl Route {
+    /// Get the total waiting at the customers, leaving the warehouse at 0
+    pub fn waiting_time(&self) -> f32 {
+        self.schedule().iter().map(|stop| stop.waiting_time).sum()
+    }
+
+    /// Get the latest time the vehicle can leave the warehouse without arriving late at a
+    /// customer or coming back later than when leaving at 0.
+    ///
+    /// Leaving later only shortens the waiting at the customers, so this departure waits the
+    /// least. Every time unit of delay is absorbed by the next waiting times, up to the due date
+    /// of each customer. Returns 0 for routes already late when leaving at 0
+    pub fn latest_departure(&self) -> f32 {
+        let mut waited = 0.0;
+        let mut delay = f32::INFINITY;
+
+        for (customer, stop) in self.customers.iter().zip(self.schedule()) {
+            delay = delay.min(customer.due_date as f32 - stop.arrival + waited);
+            waited += stop.waiting_time;
+        }
+
+        // Any delay over the total waiting would also delay the return
+        delay.min(waited).max(0.0)
+    }
+}
+
+impl VrpResult {
+    /// Delay the departure of every route to its [latest departure](Route::latest_departure),
+    /// keeping the order of the customers. No arrival becomes late and no route comes back
+    /// later, so costs do not change and the total waiting is the least these routes allow
+    pub fn minimize_waiting(&self) -> WaitingPlan {
+        let departures = self
+            .routes
+            .iter()
+            .map(|route| {
+                let time = route.latest_departure();
+                let before = route.waiting_time();
+                let after: f32 = route
+                    .schedule_from(time)
+                    .iter()
+                    .map(|stop| stop.waiting_time)
+                    .sum();
+
+                Departure {
+                    time,
+                    waiting_time: after,
+                    waiting_saved: (before - after).max(0.0),
+                }
+            })
+            .collect();
+
+        WaitingPlan { departures }
+    }
+}